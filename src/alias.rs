@@ -0,0 +1,231 @@
+//! Command aliases from `[alias]` in config.toml, expanded before argument parsing.
+//!
+//! An alias expands to a command line split like a shell would. `$1`..`$9` take the
+//! arguments after the alias name and `$@` takes all of them; arguments not consumed by
+//! a placeholder are appended, as with git aliases.
+
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+/// Global options that take a value, so the word after them is not the command
+const GLOBAL_VALUE_FLAGS: [&str; 3] = ["--space", "--token", "--profile"];
+
+/// Aliases may refer to other aliases up to this depth
+const MAX_DEPTH: usize = 10;
+
+/// Top-level command names (and their aliases), which `[alias]` entries cannot override
+pub fn builtin_commands(cmd: &clap::Command) -> Vec<String> {
+    let mut names = vec!["help".to_string()];
+    for sub in cmd.get_subcommands() {
+        names.push(sub.get_name().to_string());
+        names.extend(sub.get_all_aliases().map(str::to_string));
+    }
+    names
+}
+
+/// Expand an alias in `args` (including the program name at index 0).
+///
+/// `builtins` are the real top-level commands, which aliases never shadow.
+pub fn expand(
+    args: Vec<String>,
+    aliases: &BTreeMap<String, String>,
+    builtins: &[String],
+) -> Result<Vec<String>> {
+    let mut args = args;
+    let mut seen: Vec<String> = Vec::new();
+    loop {
+        let Some(index) = command_index(&args) else {
+            return Ok(args);
+        };
+        let name = &args[index];
+        if builtins.contains(name) {
+            return Ok(args);
+        }
+        let Some(expansion) = aliases.get(name) else {
+            return Ok(args);
+        };
+        if seen.contains(name) || seen.len() >= MAX_DEPTH {
+            seen.push(name.clone());
+            bail!("Alias loop: {}", seen.join(" -> "));
+        }
+        seen.push(name.clone());
+
+        let expanded = substitute(name, expansion, &args[index + 1..])?;
+        args.truncate(index);
+        args.extend(expanded);
+    }
+}
+
+/// Position of the first word that is not a global option or its value
+fn command_index(args: &[String]) -> Option<usize> {
+    let mut i = 1;
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "--" {
+            return None;
+        }
+        if GLOBAL_VALUE_FLAGS.contains(&arg.as_str()) {
+            i += 2;
+            continue;
+        }
+        if arg.starts_with('-') {
+            i += 1;
+            continue;
+        }
+        return Some(i);
+    }
+    None
+}
+
+/// Split `expansion` into words and fill in `$N` and `$@` from `rest`
+pub fn substitute(name: &str, expansion: &str, rest: &[String]) -> Result<Vec<String>> {
+    let Some(words) = shlex::split(expansion) else {
+        bail!("Alias '{}' has unbalanced quotes: {}", name, expansion);
+    };
+
+    let mut used = 0;
+    let mut uses_all = false;
+    let mut out = Vec::new();
+    for word in words {
+        if word == "$@" {
+            uses_all = true;
+            out.extend(rest.iter().cloned());
+            continue;
+        }
+        let mut result = String::new();
+        let mut chars = word.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                result.push(c);
+                continue;
+            }
+            match chars.peek().copied() {
+                Some(d @ '1'..='9') => {
+                    chars.next();
+                    let n = d.to_digit(10).unwrap_or_default() as usize;
+                    let Some(value) = rest.get(n - 1) else {
+                        bail!(
+                            "Alias '{}' expects at least {} argument{}",
+                            name,
+                            n,
+                            if n == 1 { "" } else { "s" }
+                        );
+                    };
+                    result.push_str(value);
+                    used = used.max(n);
+                }
+                Some('@') => {
+                    chars.next();
+                    uses_all = true;
+                    result.push_str(&rest.join(" "));
+                }
+                _ => result.push(c),
+            }
+        }
+        out.push(result);
+    }
+
+    if !uses_all {
+        out.extend(rest.iter().skip(used).cloned());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    fn aliases(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn builtins() -> Vec<String> {
+        args(&["task", "project"])
+    }
+
+    #[test]
+    fn test_expand_appends_remaining_args() {
+        let aliases = aliases(&[("od", "task query overdue")]);
+        let expanded =
+            expand(args(&["rpsn", "od", "--limit", "5"]), &aliases, &builtins()).unwrap();
+        assert_eq!(
+            expanded,
+            args(&["rpsn", "task", "query", "overdue", "--limit", "5"])
+        );
+    }
+
+    #[test]
+    fn test_expand_positional_parameters() {
+        let aliases = aliases(&[("todo", "task create $1 --title '$2' --priority 3")]);
+        let expanded = expand(
+            args(&["rpsn", "--json", "todo", "12", "Fix it", "--dry"]),
+            &aliases,
+            &builtins(),
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            args(&[
+                "rpsn",
+                "--json",
+                "task",
+                "create",
+                "12",
+                "--title",
+                "Fix it",
+                "--priority",
+                "3",
+                "--dry"
+            ])
+        );
+
+        let err = expand(args(&["rpsn", "todo", "12"]), &aliases, &builtins()).unwrap_err();
+        assert!(err.to_string().contains("at least 2 arguments"));
+    }
+
+    #[test]
+    fn test_expand_all_args_and_global_values() {
+        let aliases = aliases(&[("q", "task query $@ --include-closed")]);
+        let expanded = expand(
+            args(&["rpsn", "--profile", "q", "q", "stale"]),
+            &aliases,
+            &builtins(),
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            args(&[
+                "rpsn",
+                "--profile",
+                "q",
+                "task",
+                "query",
+                "stale",
+                "--include-closed"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_builtins_are_not_shadowed_and_loops_fail() {
+        let shadow = aliases(&[("task", "project list")]);
+        let input = args(&["rpsn", "task", "list", "1"]);
+        assert_eq!(expand(input.clone(), &shadow, &builtins()).unwrap(), input);
+
+        let chained = aliases(&[("a", "b --x"), ("b", "task list")]);
+        assert_eq!(
+            expand(args(&["rpsn", "a"]), &chained, &builtins()).unwrap(),
+            args(&["rpsn", "task", "list", "--x"])
+        );
+
+        let looped = aliases(&[("a", "b"), ("b", "a")]);
+        assert!(expand(args(&["rpsn", "a"]), &looped, &builtins()).is_err());
+    }
+}
@@ -3,7 +3,11 @@ use reqwest::{header, multipart, Client, Method, RequestBuilder, Response};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
+use crate::cache::{Cache, CacheMode, Resource};
 use crate::telemetry_span;
 
 const TOKEN_MASK: &str = "***REDACTED***";
@@ -36,12 +40,47 @@ fn sanitize_json_value(value: &Value) -> Value {
     }
 }
 
+/// Whether a request failed because the resource does not exist (HTTP 404)
+pub fn is_not_found(error: &anyhow::Error) -> bool {
+    error.to_string().contains("API error (404 Not Found)")
+}
+
+/// Rate-limit state reported by the most recent API response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    /// Seconds until the window resets, as reported by the server
+    pub reset_secs: Option<u64>,
+    pub observed_at: Instant,
+}
+
+impl RateLimit {
+    /// Whether there is enough headroom for non-essential (background) requests.
+    ///
+    /// Below 10% remaining, background work waits until the window resets.
+    pub fn allows_background(&self, now: Instant) -> bool {
+        if self.remaining.saturating_mul(10) >= self.limit {
+            return true;
+        }
+        match self.reset_secs {
+            Some(reset) => now.duration_since(self.observed_at) >= Duration::from_secs(reset),
+            None => false,
+        }
+    }
+}
+
 pub struct RepsonaClient {
+    space_id: String,
     base_url: String,
     api_token: String,
     dry_run: bool,
     trace: bool,
     client: Client,
+    quiet: AtomicBool,
+    rate_limit: Mutex<Option<RateLimit>>,
+    cache: Option<Cache>,
+    cache_mode: CacheMode,
 }
 
 impl RepsonaClient {
@@ -56,14 +95,118 @@ impl RepsonaClient {
             .expect("Failed to build HTTP client with TLS");
 
         RepsonaClient {
+            space_id,
             base_url,
             api_token,
             dry_run,
             trace,
             client,
+            quiet: AtomicBool::new(false),
+            rate_limit: Mutex::new(None),
+            cache: None,
+            cache_mode: CacheMode::Off,
         }
     }
 
+    /// True when requests are printed instead of sent (`--dry-run`)
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// A client for another space with the same `--dry-run` and `--trace` settings and no cache
+    pub fn for_space(&self, space_id: String, api_token: String) -> Self {
+        RepsonaClient::new(space_id, api_token, self.dry_run, self.trace)
+    }
+
+    pub fn space_id(&self) -> &str {
+        &self.space_id
+    }
+
+    /// Serve slow-changing resources from `cache` according to `mode`
+    pub fn with_cache(mut self, cache: Cache, mode: CacheMode) -> Self {
+        self.cache = Some(cache);
+        self.cache_mode = mode;
+        self
+    }
+
+    pub fn cache(&self) -> Option<&Cache> {
+        self.cache.as_ref()
+    }
+
+    /// GET `endpoint`, going through the local cache under `key`
+    pub(crate) async fn get_cached<T: DeserializeOwned + Serialize>(
+        &self,
+        endpoint: &str,
+        key: &str,
+        resource: Resource,
+    ) -> Result<T> {
+        self.get_with_cache(endpoint, key, resource, self.cache_mode)
+            .await
+    }
+
+    /// GET `endpoint` from the API even when cached, updating the cache entry under `key`
+    pub(crate) async fn get_refreshed<T: DeserializeOwned + Serialize>(
+        &self,
+        endpoint: &str,
+        key: &str,
+        resource: Resource,
+    ) -> Result<T> {
+        let mode = match self.cache_mode {
+            CacheMode::Off => CacheMode::Off,
+            _ => CacheMode::Refresh,
+        };
+        self.get_with_cache(endpoint, key, resource, mode).await
+    }
+
+    async fn get_with_cache<T: DeserializeOwned + Serialize>(
+        &self,
+        endpoint: &str,
+        key: &str,
+        resource: Resource,
+        mode: CacheMode,
+    ) -> Result<T> {
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|_| !self.dry_run && mode != CacheMode::Off);
+        if let Some(cache) = cache.filter(|_| mode == CacheMode::Normal) {
+            if let Some(value) = cache.get(key, cache.ttl(resource)) {
+                if self.trace {
+                    eprintln!("[TRACE] Cache hit: {}", key);
+                }
+                return Ok(value);
+            }
+        }
+
+        let value: T = self.get(endpoint).await?;
+        if let Some(cache) = cache {
+            let _ = cache.put(key, &value);
+        }
+        Ok(value)
+    }
+
+    /// Drop cached entries under each key prefix after a successful mutation
+    pub(crate) fn invalidate(&self, prefixes: &[String]) {
+        if self.dry_run {
+            return;
+        }
+        if let Some(cache) = &self.cache {
+            for prefix in prefixes {
+                let _ = cache.invalidate(prefix);
+            }
+        }
+    }
+
+    /// Stop printing rate-limit notices to stderr (used by full-screen views)
+    pub fn set_quiet(&self, quiet: bool) {
+        self.quiet.store(quiet, Ordering::Relaxed);
+    }
+
+    /// Rate-limit state from the most recent response, if the server sent one
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit.lock().ok().and_then(|guard| *guard)
+    }
+
     fn build_request(&self, method: Method, endpoint: &str) -> RequestBuilder {
         let url = format!("{}/{}", self.base_url, endpoint);
         self.client
@@ -94,6 +237,30 @@ impl RepsonaClient {
     }
 
     fn handle_rate_limits(&self, headers: &header::HeaderMap) {
+        let header_u64 = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        if let (Some(limit), Some(remaining)) = (
+            header_u64("RateLimit-Limit"),
+            header_u64("RateLimit-Remaining"),
+        ) {
+            if let Ok(mut state) = self.rate_limit.lock() {
+                *state = Some(RateLimit {
+                    limit,
+                    remaining,
+                    reset_secs: header_u64("RateLimit-Reset"),
+                    observed_at: Instant::now(),
+                });
+            }
+        }
+
+        if self.quiet.load(Ordering::Relaxed) {
+            return;
+        }
+
         if let Some(limit) = headers.get("RateLimit-Limit") {
             if let Some(remaining) = headers.get("RateLimit-Remaining") {
                 if let Ok(limit_str) = limit.to_str() {
@@ -431,6 +598,43 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("Dry run"));
     }
 
+    #[test]
+    fn test_rate_limit_recorded_from_headers() {
+        let client = RepsonaClient::new("test".to_string(), "test-token".to_string(), false, false);
+        client.set_quiet(true);
+        assert!(client.rate_limit().is_none());
+
+        let mut headers = header::HeaderMap::new();
+        headers.insert("RateLimit-Limit", "1000".parse().unwrap());
+        headers.insert("RateLimit-Remaining", "42".parse().unwrap());
+        headers.insert("RateLimit-Reset", "30".parse().unwrap());
+        client.handle_rate_limits(&headers);
+
+        let rate = client.rate_limit().expect("rate limit should be recorded");
+        assert_eq!(rate.limit, 1000);
+        assert_eq!(rate.remaining, 42);
+        assert_eq!(rate.reset_secs, Some(30));
+    }
+
+    #[test]
+    fn test_rate_limit_allows_background() {
+        let now = Instant::now();
+        let plenty = RateLimit {
+            limit: 1000,
+            remaining: 500,
+            reset_secs: Some(60),
+            observed_at: now,
+        };
+        assert!(plenty.allows_background(now));
+
+        let low = RateLimit {
+            remaining: 50,
+            ..plenty
+        };
+        assert!(!low.allows_background(now));
+        assert!(low.allows_background(now + Duration::from_secs(61)));
+    }
+
     // =========================================================================
     // Property-Based Tests
     // =========================================================================
@@ -44,16 +44,29 @@ impl crate::api::RepsonaClient {
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("file");
+        self.upload_file_bytes(project_id, file_name, file_bytes)
+            .await
+    }
+
+    pub async fn upload_file_bytes(
+        &self,
+        project_id: u64,
+        file_name: &str,
+        file_bytes: Vec<u8>,
+    ) -> Result<ApiResponse<FilesData>> {
         let file_part = multipart::Part::bytes(file_bytes).file_name(file_name.to_string());
         let form = multipart::Form::new().part("file", file_part);
         self.post_multipart(&format!("project/{}/file", project_id), form)
             .await
     }
 
+    pub async fn download_file_bytes(&self, file_hash: &str) -> Result<Vec<u8>> {
+        self.get_bytes(&format!("file/{}/download", file_hash))
+            .await
+    }
+
     pub async fn download_file(&self, file_hash: &str, output_path: Option<&Path>) -> Result<()> {
-        let bytes = self
-            .get_bytes(&format!("file/{}/download", file_hash))
-            .await?;
+        let bytes = self.download_file_bytes(file_hash).await?;
 
         let path = output_path
             .map(Path::to_path_buf)
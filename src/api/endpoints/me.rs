@@ -41,7 +41,9 @@ impl crate::api::RepsonaClient {
     }
 
     pub async fn update_me(&self, updates: MeUpdateRequest) -> Result<ApiResponse<UserData>> {
-        self.patch("me", &updates).await
+        let response = self.patch("me", &updates).await?;
+        self.invalidate(&["users".to_string()]);
+        Ok(response)
     }
 
     pub async fn get_me_tasks(&self, _filter: &TaskFilter) -> Result<ApiResponse<TasksData>> {
@@ -23,6 +23,8 @@ pub struct UpdateNoteRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<u64>>,
 }
 
@@ -41,8 +43,11 @@ impl crate::api::RepsonaClient {
         project_id: u64,
         request: &CreateNoteRequest,
     ) -> Result<ApiResponse<NoteData>> {
-        self.post(&format!("project/{}/note", project_id), request)
-            .await
+        let response = self
+            .post(&format!("project/{}/note", project_id), request)
+            .await?;
+        self.invalidate(&[format!("completion/project/{}/notes", project_id)]);
+        Ok(response)
     }
 
     pub async fn update_note(
@@ -51,13 +56,18 @@ impl crate::api::RepsonaClient {
         note_id: u64,
         request: &UpdateNoteRequest,
     ) -> Result<ApiResponse<NoteData>> {
-        self.patch(&format!("project/{}/note/{}", project_id, note_id), request)
-            .await
+        let response = self
+            .patch(&format!("project/{}/note/{}", project_id, note_id), request)
+            .await?;
+        self.invalidate(&[format!("completion/project/{}/notes", project_id)]);
+        Ok(response)
     }
 
     pub async fn delete_note(&self, project_id: u64, note_id: u64) -> Result<()> {
-        self.delete(&format!("project/{}/note/{}", project_id, note_id))
-            .await
+        self.delete::<()>(&format!("project/{}/note/{}", project_id, note_id))
+            .await?;
+        self.invalidate(&[format!("completion/project/{}/notes", project_id)]);
+        Ok(())
     }
 
     pub async fn get_note_children(
@@ -1,4 +1,5 @@
 use crate::api::types::*;
+use crate::cache::Resource;
 use anyhow::Result;
 use serde::Serialize;
 
@@ -25,7 +26,14 @@ pub struct UpdateProjectRequest {
 
 impl crate::api::RepsonaClient {
     pub async fn list_projects(&self) -> Result<ApiResponse<ProjectsData>> {
-        self.get("project").await
+        self.get_cached("project", "projects", Resource::Projects)
+            .await
+    }
+
+    /// List projects from the API, bypassing (and refreshing) the cached list
+    pub async fn list_projects_fresh(&self) -> Result<ApiResponse<ProjectsData>> {
+        self.get_refreshed("project", "projects", Resource::Projects)
+            .await
     }
 
     pub async fn get_project(&self, project_id: u64) -> Result<ApiResponse<ProjectData>> {
@@ -36,7 +44,9 @@ impl crate::api::RepsonaClient {
         &self,
         request: &CreateProjectRequest,
     ) -> Result<ApiResponse<ProjectData>> {
-        self.post("project", request).await
+        let response = self.post("project", request).await?;
+        self.invalidate(&["projects".to_string()]);
+        Ok(response)
     }
 
     pub async fn update_project(
@@ -44,16 +54,31 @@ impl crate::api::RepsonaClient {
         project_id: u64,
         request: &UpdateProjectRequest,
     ) -> Result<ApiResponse<ProjectData>> {
-        self.patch(&format!("project/{}", project_id), request)
-            .await
+        let response = self
+            .patch(&format!("project/{}", project_id), request)
+            .await?;
+        self.invalidate(&["projects".to_string()]);
+        Ok(response)
     }
 
     pub async fn delete_project(&self, project_id: u64) -> Result<()> {
-        self.delete(&format!("project/{}", project_id)).await
+        self.delete::<()>(&format!("project/{}", project_id))
+            .await?;
+        self.invalidate(&[
+            "projects".to_string(),
+            format!("project/{}/", project_id),
+            format!("completion/project/{}/", project_id),
+        ]);
+        Ok(())
     }
 
     pub async fn list_project_members(&self, project_id: u64) -> Result<ApiResponse<UsersData>> {
-        self.get(&format!("project/{}/users", project_id)).await
+        self.get_cached(
+            &format!("project/{}/users", project_id),
+            &format!("project/{}/members", project_id),
+            Resource::Members,
+        )
+        .await
     }
 
     pub async fn add_project_member(
@@ -61,11 +86,14 @@ impl crate::api::RepsonaClient {
         project_id: u64,
         user_id: u64,
     ) -> Result<ApiResponse<ProjectData>> {
-        self.post(
-            &format!("project/{}/user", project_id),
-            &serde_json::json!({ "user": user_id }),
-        )
-        .await
+        let response = self
+            .post(
+                &format!("project/{}/user", project_id),
+                &serde_json::json!({ "user": user_id }),
+            )
+            .await?;
+        self.invalidate(&[format!("project/{}/members", project_id)]);
+        Ok(response)
     }
 
     pub async fn remove_project_member(
@@ -73,8 +101,11 @@ impl crate::api::RepsonaClient {
         project_id: u64,
         user_id: u64,
     ) -> Result<ApiResponse<ProjectData>> {
-        self.delete(&format!("project/{}/user/{}", project_id, user_id))
-            .await
+        let response = self
+            .delete(&format!("project/{}/user/{}", project_id, user_id))
+            .await?;
+        self.invalidate(&[format!("project/{}/members", project_id)]);
+        Ok(response)
     }
 
     pub async fn get_project_activity(&self, project_id: u64) -> Result<ApiResponse<ActivityData>> {
@@ -85,13 +116,23 @@ impl crate::api::RepsonaClient {
         &self,
         project_id: u64,
     ) -> Result<ApiResponse<StatusesData>> {
-        self.get(&format!("project/{}/status", project_id)).await
+        self.get_cached(
+            &format!("project/{}/status", project_id),
+            &format!("project/{}/statuses", project_id),
+            Resource::Statuses,
+        )
+        .await
     }
 
     pub async fn list_project_milestones(
         &self,
         project_id: u64,
     ) -> Result<ApiResponse<MilestonesData>> {
-        self.get(&format!("project/{}/milestone", project_id)).await
+        self.get_cached(
+            &format!("project/{}/milestone", project_id),
+            &format!("project/{}/milestones", project_id),
+            Resource::Milestones,
+        )
+        .await
     }
 }
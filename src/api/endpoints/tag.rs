@@ -1,8 +1,9 @@
 use crate::api::types::*;
+use crate::cache::Resource;
 use anyhow::Result;
 
 impl crate::api::RepsonaClient {
     pub async fn list_tags(&self) -> Result<ApiResponse<TagsData>> {
-        self.get("tag/all").await
+        self.get_cached("tag/all", "tags", Resource::Tags).await
     }
 }
@@ -83,8 +83,11 @@ impl crate::api::RepsonaClient {
         project_id: u64,
         request: &CreateTaskRequest,
     ) -> Result<ApiResponse<TaskData>> {
-        self.post(&format!("project/{}/task", project_id), request)
-            .await
+        let response = self
+            .post(&format!("project/{}/task", project_id), request)
+            .await?;
+        self.invalidate(&[format!("completion/project/{}/tasks", project_id)]);
+        Ok(response)
     }
 
     pub async fn update_task(
@@ -93,13 +96,18 @@ impl crate::api::RepsonaClient {
         task_id: u64,
         request: &UpdateTaskRequest,
     ) -> Result<ApiResponse<TaskData>> {
-        self.patch(&format!("project/{}/task/{}", project_id, task_id), request)
-            .await
+        let response = self
+            .patch(&format!("project/{}/task/{}", project_id, task_id), request)
+            .await?;
+        self.invalidate(&[format!("completion/project/{}/tasks", project_id)]);
+        Ok(response)
     }
 
     pub async fn delete_task(&self, project_id: u64, task_id: u64) -> Result<()> {
-        self.delete(&format!("project/{}/task/{}", project_id, task_id))
-            .await
+        self.delete::<()>(&format!("project/{}/task/{}", project_id, task_id))
+            .await?;
+        self.invalidate(&[format!("completion/project/{}/tasks", project_id)]);
+        Ok(())
     }
 
     pub async fn set_task_status(
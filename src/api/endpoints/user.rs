@@ -1,4 +1,5 @@
 use crate::api::types::*;
+use crate::cache::Resource;
 use anyhow::Result;
 use serde::Serialize;
 
@@ -15,7 +16,7 @@ pub struct SetPaymentRequest {
 
 impl crate::api::RepsonaClient {
     pub async fn list_users(&self) -> Result<ApiResponse<UsersData>> {
-        self.get("user").await
+        self.get_cached("user", "users", Resource::Users).await
     }
 
     pub async fn get_user(&self, user_id: u64) -> Result<ApiResponse<UserData>> {
@@ -27,7 +28,11 @@ impl crate::api::RepsonaClient {
         user_id: u64,
         request: &SetUserRoleRequest,
     ) -> Result<ApiResponse<UserData>> {
-        self.patch(&format!("user/{}/role", user_id), request).await
+        let response = self
+            .patch(&format!("user/{}/role", user_id), request)
+            .await?;
+        self.invalidate(&["users".to_string()]);
+        Ok(response)
     }
 
     pub async fn set_user_payment(
@@ -35,8 +40,11 @@ impl crate::api::RepsonaClient {
         user_id: u64,
         request: &SetPaymentRequest,
     ) -> Result<ApiResponse<UserData>> {
-        self.patch(&format!("user/{}/payment", user_id), request)
-            .await
+        let response = self
+            .patch(&format!("user/{}/payment", user_id), request)
+            .await?;
+        self.invalidate(&["users".to_string()]);
+        Ok(response)
     }
 
     pub async fn get_user_activity(&self, user_id: u64) -> Result<ApiResponse<ActivityData>> {
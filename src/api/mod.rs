@@ -3,7 +3,7 @@ pub mod types;
 
 pub mod endpoints;
 
-pub use client::RepsonaClient;
+pub use client::{RateLimit, RepsonaClient};
 
 #[cfg(test)]
 mod live_api_tests;
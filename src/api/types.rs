@@ -126,6 +126,9 @@ pub struct Task {
     pub project: ProjectSummary,
     pub milestone: Option<Milestone>,
     pub parent: Option<u64>,
+    /// Attachments, when the response includes them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<File>,
     #[serde(rename = "sortOrder")]
     pub sort_order: u32,
     #[serde(rename = "createdAt")]
@@ -142,6 +145,9 @@ pub struct Note {
     pub tags: Vec<Tag>,
     pub parent: Option<u64>,
     pub project: ProjectSummary,
+    /// Attachments, when the response includes them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<File>,
     #[serde(rename = "sortOrder")]
     pub sort_order: u32,
     #[serde(rename = "createdAt")]
@@ -406,6 +412,77 @@ pub struct InviteData {
     pub invite: Invite,
 }
 
+/// Fixtures shared by unit tests across the crate
+#[cfg(test)]
+pub mod test_support {
+    use super::*;
+
+    /// An open "Task {id}" in project 7 with every optional field empty.
+    ///
+    /// Override plain fields with struct update syntax (`Task { priority: 3, ..task(1) }`)
+    /// and nested ones with the builder methods below.
+    pub fn task(id: u64) -> Task {
+        Task {
+            id,
+            name: format!("Task {}", id),
+            description: None,
+            status: Status {
+                id: 1,
+                name: "Todo".to_string(),
+                is_closed: false,
+                color: None,
+            },
+            priority: 0,
+            due_date: None,
+            start_date: None,
+            responsible_user: None,
+            ball_holding_user: None,
+            tags: Vec::new(),
+            project: ProjectSummary {
+                id: 7,
+                name: "p7".to_string(),
+            },
+            milestone: None,
+            parent: None,
+            files: Vec::new(),
+            sort_order: 0,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    impl Task {
+        pub fn named(self, name: &str) -> Self {
+            Task {
+                name: name.to_string(),
+                ..self
+            }
+        }
+
+        pub fn with_status(self, id: u64, name: &str, is_closed: bool) -> Self {
+            Task {
+                status: Status {
+                    id,
+                    name: name.to_string(),
+                    is_closed,
+                    color: None,
+                },
+                ..self
+            }
+        }
+
+        pub fn in_project(self, id: u64, name: &str) -> Self {
+            Task {
+                project: ProjectSummary {
+                    id,
+                    name: name.to_string(),
+                },
+                ..self
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
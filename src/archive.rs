@@ -0,0 +1,332 @@
+//! Project archives for `rpsn project export` and `rpsn project import`.
+//!
+//! An archive is a tar file holding `manifest.json`, one JSON file per kind of record,
+//! and attachments under `files/<hash>/<filename>`. The manifest carries a format name
+//! and version so older archives stay readable as the layout grows.
+
+use anyhow::{bail, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::api::types::*;
+
+pub const FORMAT: &str = "rpsn-project-archive";
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    /// RFC 3339 time of the export
+    pub exported_at: String,
+    pub space_id: String,
+    pub project_id: u64,
+    /// Number of records in each JSON file
+    pub counts: BTreeMap<String, usize>,
+    pub files: Vec<FileEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub id: u64,
+    pub hash: String,
+    pub filename: String,
+    pub size: u64,
+    /// Location inside the archive
+    pub path: String,
+}
+
+/// Everything exported from one project
+#[derive(Debug, Clone)]
+pub struct ProjectArchive {
+    pub project: Project,
+    pub members: Vec<User>,
+    pub statuses: Vec<Status>,
+    pub milestones: Vec<Milestone>,
+    pub tags: Vec<Tag>,
+    pub tasks: Vec<Task>,
+    pub task_comments: BTreeMap<u64, Vec<TaskComment>>,
+    pub task_history: BTreeMap<u64, Vec<History>>,
+    pub notes: Vec<Note>,
+    pub note_comments: BTreeMap<u64, Vec<NoteComment>>,
+    pub note_history: BTreeMap<u64, Vec<History>>,
+    /// Attachment contents by file hash
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
+impl ProjectArchive {
+    pub fn new(project: Project) -> Self {
+        ProjectArchive {
+            project,
+            members: Vec::new(),
+            statuses: Vec::new(),
+            milestones: Vec::new(),
+            tags: Vec::new(),
+            tasks: Vec::new(),
+            task_comments: BTreeMap::new(),
+            task_history: BTreeMap::new(),
+            notes: Vec::new(),
+            note_comments: BTreeMap::new(),
+            note_history: BTreeMap::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Attachments of every task and note, once per hash
+    pub fn attachments(&self) -> Vec<&File> {
+        let mut seen = BTreeMap::new();
+        for file in self
+            .tasks
+            .iter()
+            .flat_map(|t| &t.files)
+            .chain(self.notes.iter().flat_map(|n| &n.files))
+        {
+            seen.entry(file.hash.as_str()).or_insert(file);
+        }
+        seen.into_values().collect()
+    }
+
+    fn manifest(&self, space_id: &str, exported_at: String) -> Manifest {
+        let counts = [
+            ("members", self.members.len()),
+            ("statuses", self.statuses.len()),
+            ("milestones", self.milestones.len()),
+            ("tags", self.tags.len()),
+            ("tasks", self.tasks.len()),
+            (
+                "task_comments",
+                self.task_comments.values().map(Vec::len).sum(),
+            ),
+            (
+                "task_history",
+                self.task_history.values().map(Vec::len).sum(),
+            ),
+            ("notes", self.notes.len()),
+            (
+                "note_comments",
+                self.note_comments.values().map(Vec::len).sum(),
+            ),
+            (
+                "note_history",
+                self.note_history.values().map(Vec::len).sum(),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+        let files = self
+            .attachments()
+            .into_iter()
+            .filter(|f| self.files.contains_key(&f.hash))
+            .map(|f| FileEntry {
+                id: f.id,
+                hash: f.hash.clone(),
+                filename: f.filename.clone(),
+                size: self.files[&f.hash].len() as u64,
+                path: file_path(f),
+            })
+            .collect();
+        Manifest {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            exported_at,
+            space_id: space_id.to_string(),
+            project_id: self.project.id,
+            counts,
+            files,
+        }
+    }
+
+    /// Write the archive as a tar stream
+    pub fn write_to<W: Write>(&self, writer: W, space_id: &str) -> Result<Manifest> {
+        let now = chrono::Utc::now();
+        let manifest = self.manifest(space_id, now.to_rfc3339());
+        let mut builder = tar::Builder::new(writer);
+        let mtime = now.timestamp().max(0) as u64;
+
+        append_json(&mut builder, "manifest.json", &manifest, mtime)?;
+        append_json(&mut builder, "project.json", &self.project, mtime)?;
+        append_json(&mut builder, "members.json", &self.members, mtime)?;
+        append_json(&mut builder, "statuses.json", &self.statuses, mtime)?;
+        append_json(&mut builder, "milestones.json", &self.milestones, mtime)?;
+        append_json(&mut builder, "tags.json", &self.tags, mtime)?;
+        append_json(&mut builder, "tasks.json", &self.tasks, mtime)?;
+        append_json(
+            &mut builder,
+            "task_comments.json",
+            &self.task_comments,
+            mtime,
+        )?;
+        append_json(&mut builder, "task_history.json", &self.task_history, mtime)?;
+        append_json(&mut builder, "notes.json", &self.notes, mtime)?;
+        append_json(
+            &mut builder,
+            "note_comments.json",
+            &self.note_comments,
+            mtime,
+        )?;
+        append_json(&mut builder, "note_history.json", &self.note_history, mtime)?;
+        for entry in &manifest.files {
+            append(&mut builder, &entry.path, &self.files[&entry.hash], mtime)?;
+        }
+        builder.into_inner()?.flush()?;
+        Ok(manifest)
+    }
+
+    pub fn write(&self, path: &Path, space_id: &str) -> Result<Manifest> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        self.write_to(std::io::BufWriter::new(file), space_id)
+    }
+
+    /// Read an archive written by any version up to [`VERSION`]
+    pub fn read_from<R: Read>(reader: R) -> Result<(Manifest, ProjectArchive)> {
+        let mut entries: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut tar = tar::Archive::new(reader);
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().into_owned();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            entries.insert(path, data);
+        }
+
+        let manifest: Manifest = json(&entries, "manifest.json")?;
+        if manifest.format != FORMAT {
+            bail!("Not an rpsn project archive (format '{}')", manifest.format);
+        }
+        if manifest.version > VERSION {
+            bail!(
+                "Archive version {} is newer than this rpsn supports ({}); upgrade rpsn",
+                manifest.version,
+                VERSION
+            );
+        }
+
+        let mut archive = ProjectArchive::new(json(&entries, "project.json")?);
+        archive.members = json(&entries, "members.json")?;
+        archive.statuses = json(&entries, "statuses.json")?;
+        archive.milestones = json(&entries, "milestones.json")?;
+        archive.tags = json(&entries, "tags.json")?;
+        archive.tasks = json(&entries, "tasks.json")?;
+        archive.task_comments = json(&entries, "task_comments.json")?;
+        archive.task_history = json(&entries, "task_history.json")?;
+        archive.notes = json(&entries, "notes.json")?;
+        archive.note_comments = json(&entries, "note_comments.json")?;
+        archive.note_history = json(&entries, "note_history.json")?;
+        for file in &manifest.files {
+            let data = entries
+                .remove(&file.path)
+                .with_context(|| format!("Archive is missing {}", file.path))?;
+            archive.files.insert(file.hash.clone(), data);
+        }
+        Ok((manifest, archive))
+    }
+
+    pub fn read(path: &Path) -> Result<(Manifest, ProjectArchive)> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        Self::read_from(std::io::BufReader::new(file))
+            .with_context(|| format!("Invalid project archive {}", path.display()))
+    }
+}
+
+fn file_path(file: &File) -> String {
+    let name: String = file
+        .filename
+        .chars()
+        .map(|c| if c == '/' || c == '\\' { '_' } else { c })
+        .collect();
+    format!("files/{}/{}", file.hash, name)
+}
+
+fn append_json<W: Write, T: Serialize>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    value: &T,
+    mtime: u64,
+) -> Result<()> {
+    append(builder, name, &serde_json::to_vec_pretty(value)?, mtime)
+}
+
+fn append<W: Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+    mtime: u64,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    builder
+        .append_data(&mut header, name, data)
+        .with_context(|| format!("Failed to add {} to the archive", name))
+}
+
+fn json<T: DeserializeOwned>(entries: &HashMap<String, Vec<u8>>, name: &str) -> Result<T> {
+    let data = entries
+        .get(name)
+        .with_context(|| format!("Archive is missing {}", name))?;
+    serde_json::from_slice(data).with_context(|| format!("Invalid {} in the archive", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn sample() -> ProjectArchive {
+        let project: Project = serde_json::from_value(serde_json::json!({
+            "id": 12, "name": "src", "fullName": "Source", "purpose": "Keep",
+            "avatarUrl": null, "isClosed": false, "isPublic": false,
+            "createdAt": 0, "updatedAt": 0
+        }))
+        .unwrap();
+        let task = Task {
+            description: Some("text".to_string()),
+            priority: 3,
+            files: vec![File {
+                id: 9,
+                hash: "abc".to_string(),
+                filename: "a/b.txt".to_string(),
+                size: 5,
+                file_type: "text/plain".to_string(),
+            }],
+            ..test_support::task(5).named("Spec").in_project(12, "src")
+        };
+        let mut archive = ProjectArchive::new(project);
+        archive.tasks.push(task);
+        archive.files.insert("abc".to_string(), b"hello".to_vec());
+        archive
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let archive = sample();
+        let mut buffer = Vec::new();
+        let written = archive.write_to(&mut buffer, "space").unwrap();
+        assert_eq!(written.counts["tasks"], 1);
+        assert_eq!(written.files[0].path, "files/abc/a_b.txt");
+
+        let (manifest, read) = ProjectArchive::read_from(buffer.as_slice()).unwrap();
+        assert_eq!(manifest, written);
+        assert_eq!(read.project.purpose.as_deref(), Some("Keep"));
+        assert_eq!(read.tasks[0].files[0].hash, "abc");
+        assert_eq!(read.files["abc"], b"hello");
+    }
+
+    #[test]
+    fn test_archive_rejects_newer_version() {
+        let mut manifest = sample().manifest("space", String::new());
+        manifest.version = VERSION + 1;
+        let mut builder = tar::Builder::new(Vec::new());
+        append_json(&mut builder, "manifest.json", &manifest, 0).unwrap();
+        let buffer = builder.into_inner().unwrap();
+
+        let err = ProjectArchive::read_from(buffer.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("newer"), "{}", err);
+    }
+}
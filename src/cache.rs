@@ -0,0 +1,379 @@
+//! On-disk cache for slow-changing API data, one directory per profile and space.
+//!
+//! Entries are JSON files keyed by resource path (`projects`, `project/12/statuses`).
+//! Each [`Resource`] kind has its own TTL, overridable via `[cache.ttl]` in the config.
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config;
+
+/// Kinds of cached data, each with its own TTL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Projects,
+    Users,
+    Tags,
+    Statuses,
+    Milestones,
+    Members,
+    /// Task and note titles used by shell completion
+    Completion,
+}
+
+impl Resource {
+    pub const ALL: [Resource; 7] = [
+        Resource::Projects,
+        Resource::Users,
+        Resource::Tags,
+        Resource::Statuses,
+        Resource::Milestones,
+        Resource::Members,
+        Resource::Completion,
+    ];
+
+    /// Name used in `[cache.ttl]` and as the last segment of cache keys
+    pub fn name(self) -> &'static str {
+        match self {
+            Resource::Projects => "projects",
+            Resource::Users => "users",
+            Resource::Tags => "tags",
+            Resource::Statuses => "statuses",
+            Resource::Milestones => "milestones",
+            Resource::Members => "members",
+            Resource::Completion => "completion",
+        }
+    }
+
+    pub fn default_ttl(self) -> Duration {
+        let secs = match self {
+            Resource::Projects => 600,
+            Resource::Users | Resource::Tags | Resource::Statuses => 3600,
+            Resource::Milestones | Resource::Members => 600,
+            Resource::Completion => 60,
+        };
+        Duration::from_secs(secs)
+    }
+
+    /// Resource a cache key belongs to (`project/12/statuses` -> `Statuses`)
+    pub fn from_key(key: &str) -> Option<Resource> {
+        if key.starts_with("completion/") {
+            return Some(Resource::Completion);
+        }
+        let last = key.rsplit('/').next()?;
+        Resource::ALL.into_iter().find(|r| r.name() == last)
+    }
+}
+
+/// How the client uses the cache for the current command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Serve fresh entries from the cache
+    Normal,
+    /// Always fetch, then store the result (`--refresh-cache`)
+    Refresh,
+    /// Neither read nor write (`--no-cache`)
+    Off,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    #[serde(default)]
+    key: String,
+    stored_at: u64,
+    value: T,
+}
+
+/// Metadata of one cache file, as shown by `rpsn cache stats`
+#[derive(Debug, Clone, Serialize)]
+pub struct EntryInfo {
+    pub key: String,
+    pub resource: Option<&'static str>,
+    pub age_secs: u64,
+    pub ttl_secs: Option<u64>,
+    pub fresh: bool,
+    pub bytes: u64,
+}
+
+pub struct Cache {
+    dir: PathBuf,
+    ttls: HashMap<String, u64>,
+}
+
+impl Cache {
+    /// Root of all space caches: `~/.config/rpsn/cache`
+    pub fn root_dir() -> Result<PathBuf> {
+        Ok(config::config_dir()?.join("cache"))
+    }
+
+    /// Cache directory for a space: `~/.config/rpsn/cache/<profile>/<space_id>`.
+    ///
+    /// Profiles may use tokens with different access to the same space, so they never share
+    /// entries.
+    pub fn for_space(profile: &str, space_id: &str) -> Result<Self> {
+        Ok(Self::new(
+            Self::root_dir()?
+                .join(file_name(profile))
+                .join(file_name(space_id)),
+        ))
+    }
+
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            ttls: HashMap::new(),
+        }
+    }
+
+    /// Override default TTLs, in seconds, by resource name
+    pub fn with_ttls(mut self, ttls: HashMap<String, u64>) -> Self {
+        self.ttls = ttls;
+        self
+    }
+
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    pub fn ttl(&self, resource: Resource) -> Duration {
+        self.ttls
+            .get(resource.name())
+            .map(|secs| Duration::from_secs(*secs))
+            .unwrap_or_else(|| resource.default_ttl())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", file_name(key)))
+    }
+
+    /// Cached value for `key`, unless missing, unreadable, or older than `ttl`
+    pub fn get<T: DeserializeOwned>(&self, key: &str, ttl: Duration) -> Option<T> {
+        let content = fs::read_to_string(self.path(key)).ok()?;
+        let entry: CacheEntry<T> = serde_json::from_str(&content).ok()?;
+        (now_secs().saturating_sub(entry.stored_at) < ttl.as_secs()).then_some(entry.value)
+    }
+
+    /// Store `value` under `key`. Entries hold API data, so they are private to the owner.
+    pub fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        config::create_private_dir(&self.dir)?;
+        let entry = CacheEntry {
+            key: key.to_string(),
+            stored_at: now_secs(),
+            value,
+        };
+        // Write then rename so concurrent readers never see a partial file
+        let path = self.path(key);
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        config::open_private_file(&tmp, true)?
+            .write_all(serde_json::to_string(&entry)?.as_bytes())?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Remove every entry whose key starts with `prefix`; returns how many were removed
+    pub fn invalidate(&self, prefix: &str) -> Result<usize> {
+        let prefix = file_name(prefix);
+        let mut removed = 0;
+        for path in self.files()? {
+            let matches = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .is_some_and(|stem| stem.starts_with(&prefix));
+            if matches {
+                fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Remove all entries for this space; returns how many were removed
+    pub fn clear(&self) -> Result<usize> {
+        let files = self.files()?;
+        for path in &files {
+            fs::remove_file(path)?;
+        }
+        Ok(files.len())
+    }
+
+    pub fn stats(&self) -> Result<Vec<EntryInfo>> {
+        #[derive(Deserialize)]
+        struct Header {
+            #[serde(default)]
+            key: String,
+            stored_at: u64,
+        }
+
+        let now = now_secs();
+        let mut entries = Vec::new();
+        for path in self.files()? {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(header) = serde_json::from_str::<Header>(&content) else {
+                continue;
+            };
+            let key = if header.key.is_empty() {
+                path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default()
+                    .to_string()
+            } else {
+                header.key
+            };
+            let resource = Resource::from_key(&key);
+            let age_secs = now.saturating_sub(header.stored_at);
+            let ttl_secs = resource.map(|r| self.ttl(r).as_secs());
+            entries.push(EntryInfo {
+                resource: resource.map(Resource::name),
+                fresh: ttl_secs.is_some_and(|ttl| age_secs < ttl),
+                age_secs,
+                ttl_secs,
+                bytes: content.len() as u64,
+                key,
+            });
+        }
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(entries)
+    }
+
+    fn files(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Map a key such as `project/12/tasks` to a safe file name
+pub(crate) fn file_name(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_put_then_get() {
+        let dir = TempDir::new().unwrap();
+        let cache = Cache::new(dir.path().join("space"));
+        cache.put("project/12/tasks", &vec![1u64, 2, 3]).unwrap();
+
+        let value: Option<Vec<u64>> = cache.get("project/12/tasks", Duration::from_secs(60));
+        assert_eq!(value, Some(vec![1, 2, 3]));
+        assert!(dir.path().join("space/project_12_tasks.json").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_entries_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new().unwrap();
+        let cache = Cache::new(dir.path().join("profile/space"));
+        cache.put("projects", &1).unwrap();
+
+        let mode = |path: PathBuf| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(dir.path().join("profile")), 0o700);
+        assert_eq!(mode(cache.dir().clone()), 0o700);
+        assert_eq!(mode(cache.path("projects")), 0o600);
+    }
+
+    #[test]
+    fn test_expired_and_missing_entries() {
+        let dir = TempDir::new().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        cache.put("projects", &"x").unwrap();
+
+        assert_eq!(cache.get::<String>("projects", Duration::ZERO), None);
+        assert_eq!(cache.get::<String>("users", Duration::from_secs(60)), None);
+        // Wrong shape is treated as a miss rather than an error
+        assert_eq!(cache.get::<u64>("projects", Duration::from_secs(60)), None);
+    }
+
+    #[test]
+    fn test_file_name_is_sanitized() {
+        assert_eq!(file_name("project/12/tasks"), "project_12_tasks");
+        assert_eq!(file_name("../etc"), "___etc");
+    }
+
+    #[test]
+    fn test_invalidate_by_prefix() {
+        let dir = TempDir::new().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        cache.put("project/1/statuses", &1).unwrap();
+        cache.put("project/12/statuses", &2).unwrap();
+        cache.put("projects", &3).unwrap();
+
+        assert_eq!(cache.invalidate("project/1/").unwrap(), 1);
+        let ttl = Duration::from_secs(60);
+        assert_eq!(cache.get::<u64>("project/1/statuses", ttl), None);
+        assert_eq!(cache.get::<u64>("project/12/statuses", ttl), Some(2));
+        assert_eq!(cache.get::<u64>("projects", ttl), Some(3));
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert!(cache.stats().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ttl_overrides_and_stats() {
+        let dir = TempDir::new().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf())
+            .with_ttls(HashMap::from([("projects".to_string(), 0)]));
+        assert_eq!(cache.ttl(Resource::Projects), Duration::ZERO);
+        assert_eq!(cache.ttl(Resource::Users), Resource::Users.default_ttl());
+
+        cache.put("projects", &1).unwrap();
+        cache.put("project/3/milestones", &2).unwrap();
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].key, "project/3/milestones");
+        assert_eq!(stats[0].resource, Some("milestones"));
+        assert!(stats[0].fresh);
+        assert_eq!(stats[1].key, "projects");
+        assert!(!stats[1].fresh);
+    }
+
+    #[test]
+    fn test_resource_from_key() {
+        assert_eq!(Resource::from_key("projects"), Some(Resource::Projects));
+        assert_eq!(
+            Resource::from_key("project/4/members"),
+            Some(Resource::Members)
+        );
+        assert_eq!(
+            Resource::from_key("completion/project/4/tasks"),
+            Some(Resource::Completion)
+        );
+        assert_eq!(Resource::from_key("project/4/history"), None);
+    }
+}
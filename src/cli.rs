@@ -1,4 +1,5 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "rpsn")]
@@ -52,6 +53,14 @@ pub struct Cli {
     #[arg(long)]
     pub trace: bool,
 
+    /// Bypass the local metadata cache for this command
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Refetch cached metadata (projects, users, tags, ...) and update the cache
+    #[arg(long, conflicts_with = "no_cache")]
+    pub refresh_cache: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -82,6 +91,92 @@ pub enum Commands {
     #[command(subcommand)]
     Note(NoteCommands),
 
+    /// Kanban board - Tasks grouped into one column per project status
+    Board {
+        /// Project ID to show
+        project_id: u64,
+        /// Only show tasks assigned to this user (ID or name)
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Only show tasks with this tag (ID or name)
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only show tasks in this milestone (ID or name)
+        #[arg(long)]
+        milestone: Option<String>,
+        /// One line per card
+        #[arg(long)]
+        compact: bool,
+    },
+
+    /// Offline mirror - Copy projects, tasks, notes, comments, and users into a local database
+    Sync {
+        /// Refetch everything instead of only items changed since the last sync
+        #[arg(long)]
+        full: bool,
+        /// Only sync these projects (repeatable)
+        #[arg(long = "project")]
+        projects: Vec<u64>,
+        /// Concurrent comment requests
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+    },
+
+    /// Full-text search over the offline mirror (run `rpsn sync` first)
+    Search {
+        /// Words to search for (all must match)
+        query: String,
+        /// Only search this project
+        #[arg(long)]
+        project: Option<u64>,
+        /// Only search this kind of item (repeatable)
+        #[arg(long, value_enum)]
+        kind: Vec<SearchKind>,
+        /// Maximum number of results
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Apply a YAML or JSON change plan: creates, updates, deletes and comments
+    Apply {
+        /// Plan file ("-" reads stdin)
+        file: PathBuf,
+        /// Where to write the ref-to-ID mapping (default: <plan>.ids.json)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import a Trello, Asana or Jira export file into a project
+    ///
+    /// Labels are matched to existing tags by name; tags cannot be created through the
+    /// API, so labels without one are noted in the task description.
+    Import {
+        /// Export format
+        #[arg(value_enum)]
+        source: ImportSource,
+        /// Trello board JSON, Asana project JSON or Jira issue CSV
+        file: PathBuf,
+        /// Import into this project instead of creating a new one
+        #[arg(long)]
+        project: Option<u64>,
+        /// Name for the new project (default: the name in the export)
+        #[arg(long)]
+        name: Option<String>,
+        /// Directory holding downloaded attachments (default: next to FILE)
+        #[arg(long, value_name = "DIR")]
+        attachments: Option<PathBuf>,
+    },
+
+    /// Full-screen terminal UI - Browse projects, tasks, and inbox interactively
+    Tui {
+        /// Background refresh interval in seconds (0 to disable)
+        #[arg(long, default_value_t = 30)]
+        refresh: u64,
+    },
+
+    /// Interactive shell - Run commands with history, completion, and a project context
+    Shell,
+
     /// File operations - Upload, download, attach/detach files
     #[command(subcommand)]
     File(FileCommands),
@@ -110,15 +205,53 @@ pub enum Commands {
     #[command(subcommand)]
     Idlink(IdlinkCommands),
 
+    /// Calendar - Month grid of due dates, iCalendar export, or a subscribable feed
+    Calendar(CalendarArgs),
+
+    /// Timeline - ASCII Gantt chart of a project's tasks, grouped by milestone
+    Timeline {
+        /// Project ID to show
+        project_id: u64,
+        /// First day to show (YYYY-MM-DD, today, +2w, ...; default: earliest task date)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to show (default: latest due date)
+        #[arg(long)]
+        to: Option<String>,
+        /// Include tasks in a closed status
+        #[arg(long)]
+        include_closed: bool,
+    },
+
+    /// Milestone reports - Burndown and burnup charts
+    #[command(subcommand)]
+    Milestone(MilestoneCommands),
+
+    /// Local cache - Inspect or clear cached projects, users, tags, statuses, and milestones
+    #[command(subcommand)]
+    Cache(CacheCommands),
+
     /// Error reporting - Generate safe error reports for GitHub issues
     #[command(subcommand)]
     Report(ReportCommands),
 
     /// Generate shell completion script for bash, zsh, fish, etc.
+    ///
+    /// bash, zsh and fish scripts also complete project/task/note/user IDs with their names.
     Completion {
         /// Shell type (bash, zsh, fish, elvish, powershell)
         #[arg(value_enum)]
         shell: Shell,
+        /// Emit static completions only (commands and flags, no API lookups)
+        #[arg(long = "static")]
+        static_only: bool,
+    },
+
+    /// Print completion candidates for the given words (used by completion scripts)
+    #[command(name = "complete-words", hide = true)]
+    Complete {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
     },
 
     /// Print or export Agent Skills-compatible SKILL.md content for rpsn
@@ -201,6 +334,28 @@ pub enum ConfigCommands {
     },
     /// Show current user information (verify credentials)
     Whoami,
+    /// Manage command aliases ([alias] in config.toml)
+    #[command(subcommand)]
+    Alias(AliasCommands),
+}
+
+#[derive(Subcommand)]
+pub enum AliasCommands {
+    /// Add or replace an alias; use $1..$9 for arguments and $@ for all of them
+    Add {
+        /// Alias name
+        name: String,
+        /// Command line the alias expands to (without the leading "rpsn")
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// List aliases
+    List,
+    /// Remove an alias
+    Remove {
+        /// Alias name
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -308,6 +463,81 @@ pub enum ProjectCommands {
         /// Project ID
         project_id: u64,
     },
+    /// Converge a project to the settings declared in a TOML file
+    Sync {
+        /// Project ID (defaults to `id` in the file)
+        project_id: Option<u64>,
+        /// Project file (see `project export-config`)
+        #[arg(long)]
+        file: PathBuf,
+        /// Also remove members not listed in the file (webhooks and ID links are never removed)
+        #[arg(long)]
+        prune: bool,
+    },
+    /// Create a new project from an existing one: notes, tasks and subtasks
+    Clone {
+        /// Project ID to copy from
+        source: u64,
+        /// Name of the new project
+        #[arg(long)]
+        name: String,
+        /// Full name of the new project
+        #[arg(long)]
+        full_name: Option<String>,
+        /// Tasks to copy: `all`, `none`, or a `task query` filter such as 'tag=template'
+        #[arg(long, default_value = "all", value_name = "FILTER")]
+        tasks: String,
+        /// Do not copy the note tree
+        #[arg(long)]
+        no_notes: bool,
+        /// Move dates so the earliest task or milestone date falls on DATE (YYYY-MM-DD or +7d)
+        #[arg(long, value_name = "DATE")]
+        start_on: Option<String>,
+        /// Create the new project in the space of another config profile
+        #[arg(long, value_name = "PROFILE")]
+        to_profile: Option<String>,
+    },
+    /// Back up a project with its tasks, notes, comments, history and attachments
+    Export {
+        /// Project ID
+        project_id: u64,
+        /// Archive file to write (tar)
+        #[arg(long, short)]
+        out: PathBuf,
+    },
+    /// Publish a project as a static HTML site or a Markdown document
+    Publish {
+        /// Project ID
+        project_id: u64,
+        /// Output directory (html) or file (markdown)
+        #[arg(long, short)]
+        out: PathBuf,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = PublishFormat::Html)]
+        format: PublishFormat,
+        /// Days of recent activity to include
+        #[arg(long, default_value_t = 14)]
+        days: u64,
+    },
+    /// Restore a project archive into a new project
+    Import {
+        /// Archive written by `project export`
+        archive: PathBuf,
+        /// Name of the new project (default: the archived name)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Write a project's current settings as a TOML file for `project sync`
+    ExportConfig {
+        /// Project ID
+        project_id: u64,
+        /// Output file (default: stdout)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Also write the space-wide tags, webhooks (including their URLs) and ID links
+        #[arg(long)]
+        include_space: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -317,12 +547,50 @@ pub enum TaskCommands {
         /// Project ID containing the tasks
         project_id: u64,
     },
+    /// Find tasks across projects by filters or a built-in view
+    Query {
+        /// Built-in view (overdue, due-this-week, unassigned, stale) or @name of a saved query
+        view: Option<String>,
+        /// Only query these projects (repeatable; default: all open projects)
+        #[arg(long = "project")]
+        projects: Vec<u64>,
+        /// Responsible user (ID, name, "me", or "none")
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Status (ID or name)
+        #[arg(long)]
+        status: Option<String>,
+        /// Tag (ID or name)
+        #[arg(long)]
+        tag: Option<String>,
+        /// Due before this date (YYYY-MM-DD, today, +7d)
+        #[arg(long)]
+        due_before: Option<String>,
+        /// Priority, exact or compared (3, ">=3", "<2")
+        #[arg(long, allow_hyphen_values = true)]
+        priority: Option<String>,
+        /// Updated on or after this date (YYYY-MM-DD, 7d for seven days ago)
+        #[arg(long)]
+        updated_since: Option<String>,
+        /// Include tasks in closed statuses (implied by --status)
+        #[arg(long)]
+        include_closed: bool,
+        /// Maximum number of tasks to show
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Projects fetched concurrently
+        #[arg(long, default_value_t = 6)]
+        concurrency: usize,
+    },
     /// Get detailed information about a specific task
     Get {
         /// Project ID
         project_id: u64,
         /// Task ID to retrieve
         task_id: u64,
+        /// Print the description as raw text instead of rendered markdown
+        #[arg(long)]
+        raw: bool,
     },
     /// Create a new task in a project
     Create {
@@ -331,9 +599,12 @@ pub enum TaskCommands {
         /// Task title (required)
         #[arg(long)]
         title: String,
-        /// Task description (supports markdown)
-        #[arg(long)]
+        /// Task description (supports markdown; "-" reads stdin)
+        #[arg(long, visible_alias = "body")]
         description: Option<String>,
+        /// Read the description from a file ("-" reads stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "description")]
+        body_file: Option<PathBuf>,
         /// Status ID (use 'project status-list' to see available statuses)
         #[arg(long)]
         status: Option<u64>,
@@ -359,9 +630,12 @@ pub enum TaskCommands {
         /// New task title
         #[arg(long)]
         title: Option<String>,
-        /// New description
-        #[arg(long)]
+        /// New description ("-" reads stdin)
+        #[arg(long, visible_alias = "body")]
         description: Option<String>,
+        /// Read the description from a file ("-" reads stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "description")]
+        body_file: Option<PathBuf>,
         /// New status ID
         #[arg(long)]
         status: Option<u64>,
@@ -378,6 +652,13 @@ pub enum TaskCommands {
         #[arg(long)]
         tags: Option<String>,
     },
+    /// Edit a task's title, status, priority, due date, tags, assignee and description in $EDITOR
+    Edit {
+        /// Project ID containing the task
+        project_id: u64,
+        /// Task ID to edit
+        task_id: u64,
+    },
     /// Mark a task as completed/done
     Done {
         /// Project ID
@@ -412,6 +693,9 @@ pub enum TaskCommands {
         project_id: u64,
         /// Task ID
         task_id: u64,
+        /// Print comments as raw text instead of rendered markdown
+        #[arg(long)]
+        raw: bool,
     },
     /// Add a comment to a task
     CommentAdd {
@@ -419,9 +703,12 @@ pub enum TaskCommands {
         project_id: u64,
         /// Task ID to comment on
         task_id: u64,
-        /// Comment text (supports markdown)
-        #[arg(long)]
-        comment: String,
+        /// Comment text (supports markdown; "-" reads stdin)
+        #[arg(long, visible_alias = "body", required_unless_present = "body_file")]
+        comment: Option<String>,
+        /// Read the comment from a file ("-" reads stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "comment")]
+        body_file: Option<PathBuf>,
         /// Reply to an existing comment (comment ID)
         #[arg(long)]
         reply_to: Option<u64>,
@@ -432,9 +719,18 @@ pub enum TaskCommands {
         project_id: u64,
         /// Task comment ID to update
         comment_id: u64,
-        /// New comment text
-        #[arg(long)]
-        comment: String,
+        /// New comment text ("-" reads stdin)
+        #[arg(long, visible_alias = "body", required_unless_present_any = ["edit", "body_file"])]
+        comment: Option<String>,
+        /// Read the comment from a file ("-" reads stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "comment")]
+        body_file: Option<PathBuf>,
+        /// Edit the current text in $EDITOR instead
+        #[arg(long, conflicts_with_all = ["comment", "body_file"], requires = "task")]
+        edit: bool,
+        /// Task the comment belongs to (needed by --edit to load the comment)
+        #[arg(long)]
+        task: Option<u64>,
     },
     /// Delete a comment from a task
     CommentDelete {
@@ -443,6 +739,54 @@ pub enum TaskCommands {
         /// Task comment ID to delete
         comment_id: u64,
     },
+    /// Update many tasks at once, selected by --where or --ids
+    BulkUpdate {
+        #[command(flatten)]
+        target: BulkTarget,
+        /// Field to change as key=value: title, status, priority, assignee, due (repeatable)
+        #[arg(long = "set", value_name = "KEY=VALUE", required = true)]
+        set: Vec<String>,
+    },
+    /// Mark many tasks as done, selected by --where or --ids
+    BulkDone {
+        #[command(flatten)]
+        target: BulkTarget,
+    },
+    /// Delete many tasks, selected by --where or --ids
+    BulkDelete {
+        #[command(flatten)]
+        target: BulkTarget,
+    },
+    /// Add a tag to or remove it from many tasks, selected by --where or --ids
+    BulkTag {
+        /// Whether to add or remove the tag
+        action: TagAction,
+        /// Tag ID or name
+        tag: String,
+        #[command(flatten)]
+        target: BulkTarget,
+    },
+    /// Create or update tasks from a CSV or JSONL file
+    Import {
+        /// Project ID to import into
+        project_id: u64,
+        /// CSV or JSONL file (`-` for stdin)
+        file: PathBuf,
+        /// Column for each field, e.g. 'title=Summary,due=Due Date,assignee=Owner'
+        /// (fields: key, title, description, status, priority, due, start, assignee,
+        /// milestone, tags, parent; unmapped fields use the column of the same name)
+        #[arg(long, value_name = "FIELD=COLUMN")]
+        map: Vec<String>,
+        /// Input format (default: from the file extension, else CSV)
+        #[arg(long, value_enum)]
+        format: Option<ImportFormat>,
+        /// File recording imported keys (default: <FILE>.ids.json)
+        #[arg(long)]
+        state: Option<PathBuf>,
+        /// Also write the per-row results as JSON
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
     /// Get activity log for a task
     Activity {
         /// Project ID
@@ -496,6 +840,9 @@ pub enum NoteCommands {
         project_id: u64,
         /// Note ID to retrieve
         note_id: u64,
+        /// Print the description as raw text instead of rendered markdown
+        #[arg(long)]
+        raw: bool,
     },
     /// Create a new note in a project
     Create {
@@ -504,9 +851,12 @@ pub enum NoteCommands {
         /// Note name/title
         #[arg(long)]
         name: String,
-        /// Note description/content (supports markdown)
-        #[arg(long)]
+        /// Note description/content (supports markdown; "-" reads stdin)
+        #[arg(long, visible_alias = "body")]
         description: Option<String>,
+        /// Read the description from a file ("-" reads stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "description")]
+        body_file: Option<PathBuf>,
         /// Parent note ID (to create a subnote)
         #[arg(long)]
         parent: Option<u64>,
@@ -526,13 +876,43 @@ pub enum NoteCommands {
         /// New note name
         #[arg(long)]
         name: Option<String>,
-        /// New description
-        #[arg(long)]
+        /// New description ("-" reads stdin)
+        #[arg(long, visible_alias = "body")]
         description: Option<String>,
+        /// Read the description from a file ("-" reads stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "description")]
+        body_file: Option<PathBuf>,
         /// New comma-separated tag IDs
         #[arg(long)]
         tags: Option<String>,
     },
+    /// Edit a note's title, tags and body in $EDITOR
+    Edit {
+        /// Project ID
+        project_id: u64,
+        /// Note ID to edit
+        note_id: u64,
+    },
+    /// Write the project's note tree to a directory of Markdown files
+    Pull {
+        /// Project ID
+        project_id: u64,
+        /// Directory to write (created if missing)
+        dir: PathBuf,
+        /// Overwrite files edited locally since they were pulled
+        #[arg(long)]
+        force: bool,
+    },
+    /// Create, update and move notes from a directory written by `note pull`
+    Push {
+        /// Project ID
+        project_id: u64,
+        /// Directory of Markdown files
+        dir: PathBuf,
+        /// Overwrite notes changed in Repsona since they were pulled
+        #[arg(long)]
+        force: bool,
+    },
     /// Delete a note (requires confirmation unless --yes is used)
     Delete {
         /// Project ID
@@ -553,6 +933,9 @@ pub enum NoteCommands {
         project_id: u64,
         /// Note ID
         note_id: u64,
+        /// Print comments as raw text instead of rendered markdown
+        #[arg(long)]
+        raw: bool,
     },
     /// Add a comment to a note
     CommentAdd {
@@ -560,9 +943,12 @@ pub enum NoteCommands {
         project_id: u64,
         /// Note ID to comment on
         note_id: u64,
-        /// Comment text (supports markdown)
-        #[arg(long)]
-        comment: String,
+        /// Comment text (supports markdown; "-" reads stdin)
+        #[arg(long, visible_alias = "body", required_unless_present = "body_file")]
+        comment: Option<String>,
+        /// Read the comment from a file ("-" reads stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "comment")]
+        body_file: Option<PathBuf>,
     },
     /// Update an existing comment on a note
     CommentUpdate {
@@ -572,9 +958,12 @@ pub enum NoteCommands {
         note_id: u64,
         /// Comment ID to update
         comment_id: u64,
-        /// New comment text
-        #[arg(long)]
-        comment: String,
+        /// New comment text ("-" reads stdin)
+        #[arg(long, visible_alias = "body", required_unless_present = "body_file")]
+        comment: Option<String>,
+        /// Read the comment from a file ("-" reads stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "comment")]
+        body_file: Option<PathBuf>,
     },
     /// Delete a comment from a note
     CommentDelete {
@@ -779,8 +1168,137 @@ pub enum IdlinkCommands {
     },
 }
 
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct CalendarArgs {
+    #[command(subcommand)]
+    pub command: Option<CalendarCommands>,
+    /// Project ID, or "me" for your tasks across open projects
+    #[arg(required = true)]
+    pub target: Option<String>,
+    /// Month to show as YYYY-MM (default: this month)
+    #[arg(long)]
+    pub month: Option<String>,
+    /// Task lines per day before collapsing into "+N more"
+    #[arg(long, default_value_t = 3)]
+    pub lines: usize,
+    /// Include tasks in a closed status
+    #[arg(long)]
+    pub include_closed: bool,
+}
+
+#[derive(Subcommand)]
+pub enum CalendarCommands {
+    /// Write tasks and milestones as an iCalendar (.ics) file
+    Export {
+        #[command(flatten)]
+        selection: CalendarSelection,
+        /// File to write (default: stdout)
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+    /// Serve the calendar over HTTP as a feed calendar apps can subscribe to
+    Serve {
+        #[command(flatten)]
+        selection: CalendarSelection,
+        /// Port to listen on
+        #[arg(long, default_value_t = 8765)]
+        port: u16,
+        /// Address to listen on; use 0.0.0.0 to reach the feed from other machines
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+        /// Seconds to reuse a fetched calendar before loading it again
+        #[arg(long, default_value_t = 300)]
+        refresh: u64,
+    },
+}
+
+/// Tasks and milestones included in a calendar
+#[derive(Args, Debug, Clone)]
+pub struct CalendarSelection {
+    /// Project ID (repeatable; default: all open projects)
+    #[arg(long = "project")]
+    pub projects: Vec<u64>,
+    /// Only tasks assigned to this user: ID, name or "me"
+    #[arg(long)]
+    pub assignee: Option<String>,
+    /// Further filter as comma-separated key=value pairs, as in `task query --where`
+    #[arg(long = "where", value_name = "FILTER")]
+    pub filter: Option<String>,
+    /// Include tasks in a closed status
+    #[arg(long)]
+    pub include_closed: bool,
+    /// Leave out milestones
+    #[arg(long)]
+    pub no_milestones: bool,
+    /// Write tasks as to-dos (VTODO) instead of all-day events
+    #[arg(long)]
+    pub todo: bool,
+}
+
+#[derive(Subcommand)]
+pub enum MilestoneCommands {
+    /// Remaining (or completed) work per day, replayed from task history, with an ideal line
+    Burndown {
+        /// Project ID
+        project_id: u64,
+        /// Milestone ID or name
+        milestone: String,
+        /// Weight tasks by priority (no priority counts as 1) instead of counting them
+        #[arg(long)]
+        points: bool,
+        /// Chart completed work against total scope (burnup) instead of remaining work
+        #[arg(long)]
+        burnup: bool,
+        /// First day of the chart (default: when the first task was created)
+        #[arg(long)]
+        from: Option<String>,
+        /// Also write the chart as an SVG image
+        #[arg(long)]
+        svg: Option<PathBuf>,
+        /// Also write the daily series as CSV
+        #[arg(long)]
+        csv: Option<PathBuf>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ReportCommands {
+    /// Summarize recent task activity for projects or a person (Markdown, HTML or JSON)
+    Digest {
+        /// Project ID (repeatable; default: all open projects)
+        #[arg(long = "project")]
+        projects: Vec<u64>,
+        /// Only tasks assigned to this user: ID, name or "me"
+        #[arg(long)]
+        user: Option<String>,
+        /// Start of the period: YYYY-MM-DD or an offset into the past such as 7d or 2w
+        #[arg(long, default_value = "7d")]
+        since: String,
+        /// Days ahead to list upcoming due dates
+        #[arg(long, default_value_t = 7)]
+        upcoming: u64,
+        /// Output format (the global --json flag also selects JSON)
+        #[arg(long, value_enum, default_value_t = DigestFormat::Markdown)]
+        format: DigestFormat,
+        /// File to write (default: stdout)
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+    /// Lead time, cycle time, time in status and weekly throughput from task history
+    Flow {
+        /// Project ID to analyze
+        project_id: u64,
+        /// Start of the period: YYYY-MM-DD or an offset into the past such as 90d or 12w
+        #[arg(long, default_value = "90d")]
+        since: String,
+        /// Output format: text with histograms, or one CSV row per completed task
+        #[arg(long, value_enum, default_value_t = FlowFormat::Text)]
+        format: FlowFormat,
+        /// File to write (default: stdout)
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
     /// Generate an error report from the last error (reads from stdin or file)
     Generate {
         /// Error message to report (or reads from stdin if not provided)
@@ -799,6 +1317,86 @@ pub enum ReportCommands {
     Info,
 }
 
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Show cached entries with their age and freshness
+    Stats,
+    /// Remove cached entries for the current space
+    Clear {
+        /// Remove cached entries for every space
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+/// Tasks selected by a bulk command
+#[derive(Args, Debug)]
+pub struct BulkTarget {
+    /// Project ID (default with --where: all open projects)
+    pub project_id: Option<u64>,
+    /// Filter as comma-separated key=value pairs, e.g. "status=Todo,tag=bug,priority>=3"
+    #[arg(
+        long = "where",
+        value_name = "FILTER",
+        required_unless_present = "ids",
+        conflicts_with = "ids"
+    )]
+    pub filter: Option<String>,
+    /// Task IDs separated by commas or whitespace, or "-" to read them from stdin.
+    /// Use PROJECT/TASK to name tasks without a project ID argument
+    #[arg(long)]
+    pub ids: Option<String>,
+    /// Requests sent concurrently
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TagAction {
+    Add,
+    Remove,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportFormat {
+    Csv,
+    Jsonl,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PublishFormat {
+    Html,
+    Markdown,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DigestFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FlowFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportSource {
+    Trello,
+    Asana,
+    Jira,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SearchKind {
+    Task,
+    Note,
+    Comment,
+}
+
 #[derive(ValueEnum, Clone, Copy)]
 pub enum Shell {
     Bash,
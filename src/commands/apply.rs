@@ -0,0 +1,553 @@
+use crate::api::{
+    endpoints::{note::*, project::*, task::*},
+    types::*,
+    RepsonaClient,
+};
+use crate::output::{confirm, format_date, print, print_success, OutputFormat};
+use crate::plan::{join_ids, Action, Change, ChangePlan, Kind, Ref};
+use crate::query;
+use crate::telemetry_span;
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "apply".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct ApplyOptions {
+    /// Plan file, or `-` for stdin
+    pub file: PathBuf,
+    /// Where to write the ref → ID mapping (default: `<plan>.ids.json`)
+    pub output: Option<PathBuf>,
+    /// Apply without asking (`--yes`)
+    pub yes: bool,
+}
+
+/// An item the plan created, recorded under its `ref`
+#[derive(Debug, Clone, Serialize)]
+pub struct Created {
+    #[serde(rename = "type")]
+    pub kind: Kind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<u64>,
+    pub id: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApplySummary {
+    pub applied: usize,
+    pub total: usize,
+    pub refs: BTreeMap<String, Created>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping_file: Option<String>,
+}
+
+/// State before the change, for the old side of the diff
+enum Current {
+    Project(Project),
+    Task(Box<Task>),
+    Note(Note),
+}
+
+pub async fn handle(client: &RepsonaClient, options: ApplyOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let dry_run = client.is_dry_run();
+    let today = chrono::Local::now().date_naive();
+
+    let validate_attrs = phase_attrs("validate_input");
+    let (plan, order) =
+        telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+            let text = read_plan(&options.file)?;
+            let plan = ChangePlan::parse(&text)?;
+            let order = plan.validate(today)?;
+            Ok::<_, anyhow::Error>((plan, order))
+        })?;
+
+    // Reads are refused in dry-run mode too, so the diff then shows new values only
+    let prepare_attrs = phase_attrs("prepare_request");
+    let current = if dry_run {
+        HashMap::new()
+    } else {
+        telemetry_span::with_span_async_result("load_current", &prepare_attrs, || {
+            load_current(client, &plan)
+        })
+        .await?
+    };
+
+    let render_attrs = phase_attrs("render_output");
+    if !json {
+        telemetry_span::with_span("render_output", &render_attrs, || {
+            print_plan(&plan, &order, &current)
+        });
+    }
+    if !options.yes && !dry_run {
+        if json {
+            bail!("Pass --yes to apply a plan with --json");
+        }
+        let proceed =
+            telemetry_span::with_span_result("interactive_confirm", &validate_attrs, || {
+                confirm(&format!("Apply {} change(s)? [y/N]: ", order.len()))
+            })?;
+        if !proceed {
+            eprintln!("{}", "Cancelled".yellow());
+            return Ok(());
+        }
+    }
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let mut refs: BTreeMap<String, Created> = BTreeMap::new();
+    let mut applied = 0;
+    let mut failure = None;
+    for &i in &order {
+        let change = &plan.changes[i];
+        let result = telemetry_span::with_span_async_result("apply_change", &exec_attrs, || {
+            execute(client, &plan, change, &refs, today)
+        })
+        .await;
+        let created = match result {
+            Ok(created) => created,
+            // Every request fails in dry-run mode after being printed; new items get
+            // ID 0 so the requests that use them can still be shown
+            Err(_) if dry_run => (change.action == Action::Create).then_some(Created {
+                kind: change.kind,
+                project_id: None,
+                id: 0,
+            }),
+            Err(e) => {
+                failure = Some((i, e));
+                break;
+            }
+        };
+        if !json && !dry_run {
+            let id = created.as_ref().map(|c| format!(" → {}", c.id));
+            eprintln!(
+                "{} {}. {}{}",
+                "✓".green(),
+                i + 1,
+                describe(&plan, change),
+                id.unwrap_or_default()
+            );
+        }
+        if let (Some(symbol), Some(created)) = (change.symbol(), created) {
+            refs.insert(symbol.to_string(), created);
+        }
+        applied += 1;
+    }
+
+    let mapping_file = if dry_run || refs.is_empty() {
+        None
+    } else {
+        mapping_path(&options)
+    };
+    if let Some(path) = &mapping_file {
+        std::fs::write(path, serde_json::to_string_pretty(&refs)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    if let Some((i, e)) = failure {
+        let written = mapping_file
+            .map(|p| format!("; IDs created so far are in {}", p.display()))
+            .unwrap_or_default();
+        bail!(
+            "Change {} ({}) failed after {} of {} change(s) were applied{}: {}",
+            i + 1,
+            describe(&plan, &plan.changes[i]),
+            applied,
+            order.len(),
+            written,
+            e
+        );
+    }
+
+    let summary = ApplySummary {
+        applied,
+        total: order.len(),
+        mapping_file: mapping_file.map(|p| p.display().to_string()),
+        refs,
+    };
+    telemetry_span::with_span_result("render_output", &render_attrs, || {
+        match format {
+            OutputFormat::Json => print(&summary, format)?,
+            OutputFormat::Human if dry_run => {
+                eprintln!(
+                    "{}",
+                    "Dry run: nothing was sent; items created by the plan are shown with ID 0"
+                        .dimmed()
+                );
+            }
+            OutputFormat::Human => {
+                print_success(&format!("Applied {} change(s)", summary.applied));
+                for (symbol, created) in &summary.refs {
+                    println!("  @{} = {} {}", symbol, created.kind, created.id);
+                }
+                if let Some(path) = &summary.mapping_file {
+                    println!("{}", format!("ID mapping written to {}", path).dimmed());
+                }
+            }
+        }
+        Ok::<(), anyhow::Error>(())
+    })
+}
+
+fn read_plan(file: &Path) -> Result<String> {
+    if file == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        return Ok(text);
+    }
+    std::fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))
+}
+
+/// `--output`, or `plan.ids.json` next to `plan.yaml`; none for a plan read from stdin
+fn mapping_path(options: &ApplyOptions) -> Option<PathBuf> {
+    if let Some(output) = &options.output {
+        return Some(output.clone());
+    }
+    if options.file == Path::new("-") {
+        return None;
+    }
+    Some(options.file.with_extension("ids.json"))
+}
+
+/// Fetch every existing item an update or delete names by ID
+async fn load_current(
+    client: &RepsonaClient,
+    plan: &ChangePlan,
+) -> Result<HashMap<usize, Current>> {
+    let mut current = HashMap::new();
+    for (i, change) in plan.changes.iter().enumerate() {
+        if !matches!(change.action, Action::Update | Action::Delete) {
+            continue;
+        }
+        let Some(Ref::Id(id)) = change.id else {
+            continue;
+        };
+        let project = plan.project_of(change);
+        let item = match (change.kind, project) {
+            (Kind::Project, _) => Current::Project(client.get_project(id).await?.data.project),
+            (Kind::Task, Some(Ref::Id(project_id))) => {
+                Current::Task(Box::new(client.get_task(*project_id, id).await?.data.task))
+            }
+            (Kind::Note, Some(Ref::Id(project_id))) => {
+                Current::Note(client.get_note(*project_id, id).await?.data.note)
+            }
+            _ => continue,
+        };
+        current.insert(i, item);
+    }
+    Ok(current)
+}
+
+fn print_plan(plan: &ChangePlan, order: &[usize], current: &HashMap<usize, Current>) {
+    eprintln!("\n{}", format!("Plan: {} change(s)", order.len()).bold());
+    for &i in order {
+        let change = &plan.changes[i];
+        let mark = match change.action {
+            Action::Create => "+".green(),
+            Action::Update => "~".yellow(),
+            Action::Delete => "-".red(),
+            Action::Comment => "#".cyan(),
+        };
+        let name = current
+            .get(&i)
+            .map(|c| format!(" \"{}\"", current_value(c, "name").unwrap_or_default()))
+            .unwrap_or_default();
+        eprintln!("  {} {}. {}{}", mark, i + 1, describe(plan, change), name);
+        for (field, new) in change.fields() {
+            let old = current.get(&i).and_then(|c| current_value(c, field));
+            match old {
+                Some(old) => eprintln!(
+                    "        {}: {} {} {}",
+                    field,
+                    preview(&old).red(),
+                    "→".dimmed(),
+                    preview(&new).green()
+                ),
+                None => eprintln!("        {}: {}", field, preview(&new).green()),
+            }
+        }
+    }
+    eprintln!();
+}
+
+/// `create task @child in project 12`, `update note 99 in project @launch`
+fn describe(plan: &ChangePlan, change: &Change) -> String {
+    let target = match (change.action, change.symbol(), &change.id) {
+        (Action::Create, Some(symbol), _) => format!(" @{}", symbol),
+        (_, _, Some(id)) => format!(" {}", id),
+        _ => String::new(),
+    };
+    let project = match (change.kind, plan.project_of(change)) {
+        (Kind::Project, _) | (_, None) => String::new(),
+        (_, Some(project)) => format!(" in project {}", project),
+    };
+    format!("{} {}{}{}", change.action, change.kind, target, project)
+}
+
+/// A field's value before the change, as it would be written in a plan
+fn current_value(current: &Current, field: &str) -> Option<String> {
+    let date = |d: Option<u64>| d.map(format_date).unwrap_or_else(|| "-".to_string());
+    let tag_ids = |tags: &[Tag]| join_ids(&tags.iter().map(|t| t.id).collect::<Vec<_>>());
+    let value = match (current, field) {
+        (Current::Project(p), "name") => p.name.clone(),
+        (Current::Project(p), "full-name") => p.full_name.clone(),
+        (Current::Project(p), "purpose") => p.purpose.clone().unwrap_or_default(),
+        (Current::Task(t), "name") => t.name.clone(),
+        (Current::Task(t), "description") => t.description.clone().unwrap_or_default(),
+        (Current::Task(t), "parent") => t.parent.map(|p| p.to_string()).unwrap_or_default(),
+        (Current::Task(t), "status") => t.status.id.to_string(),
+        (Current::Task(t), "priority") => t.priority.to_string(),
+        (Current::Task(t), "due") => date(t.due_date),
+        (Current::Task(t), "start") => date(t.start_date),
+        (Current::Task(t), "assignee") => t
+            .responsible_user
+            .as_ref()
+            .map(|u| u.id.to_string())
+            .unwrap_or_default(),
+        (Current::Task(t), "milestone") => t
+            .milestone
+            .as_ref()
+            .map(|m| m.id.to_string())
+            .unwrap_or_default(),
+        (Current::Task(t), "tags") => tag_ids(&t.tags),
+        (Current::Note(n), "name") => n.name.clone(),
+        (Current::Note(n), "description") => n.description.clone().unwrap_or_default(),
+        (Current::Note(n), "tags") => tag_ids(&n.tags),
+        _ => return None,
+    };
+    Some(if value.is_empty() {
+        "-".to_string()
+    } else {
+        value
+    })
+}
+
+/// First line of a value, noting how many more there are
+fn preview(value: &str) -> String {
+    let mut lines = value.lines();
+    let first = lines.next().unwrap_or_default().to_string();
+    match lines.count() {
+        0 => first,
+        more => format!("{} (+{} lines)", first, more),
+    }
+}
+
+fn resolve(reference: &Ref, refs: &BTreeMap<String, Created>) -> Result<u64> {
+    match reference {
+        Ref::Id(id) => Ok(*id),
+        Ref::Symbol(name) => refs
+            .get(name)
+            .map(|c| c.id)
+            .with_context(|| format!("@{} was not created", name)),
+    }
+}
+
+/// Send the request for one change; creates return the new item
+async fn execute(
+    client: &RepsonaClient,
+    plan: &ChangePlan,
+    change: &Change,
+    refs: &BTreeMap<String, Created>,
+    today: NaiveDate,
+) -> Result<Option<Created>> {
+    let id = change
+        .id
+        .as_ref()
+        .map(|r| resolve(r, refs))
+        .transpose()?
+        .unwrap_or_default();
+    let project_id = match change.kind {
+        Kind::Project => None,
+        _ => Some(resolve(
+            plan.project_of(change)
+                .context("No project for this change")?,
+            refs,
+        )?),
+    };
+    let parent = change
+        .parent
+        .as_ref()
+        .map(|r| resolve(r, refs))
+        .transpose()?;
+    let date = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(|d| query::parse_date(d, today))
+            .transpose()
+    };
+    let (due_date, start_date) = (date(&change.due)?, date(&change.start)?);
+
+    let in_project = project_id.unwrap_or_default();
+    let created = |id: u64| {
+        Some(Created {
+            kind: change.kind,
+            project_id,
+            id,
+        })
+    };
+
+    Ok(match (change.action, change.kind) {
+        (Action::Create, Kind::Project) => {
+            let request = CreateProjectRequest {
+                name: change.name.clone().unwrap_or_default(),
+                full_name: change.full_name.clone(),
+                purpose: change.purpose.clone(),
+            };
+            created(client.create_project(&request).await?.data.project.id)
+        }
+        (Action::Create, Kind::Task) => {
+            let request = CreateTaskRequest {
+                name: change.name.clone().unwrap_or_default(),
+                description: change.description.clone(),
+                status: change.status,
+                priority: change.priority,
+                due_date,
+                start_date,
+                responsible_user: change.assignee,
+                parent,
+                milestone: change.milestone,
+                tags: change.tags.clone(),
+                ..Default::default()
+            };
+            created(client.create_task(in_project, &request).await?.data.task.id)
+        }
+        (Action::Create, Kind::Note) => {
+            let request = CreateNoteRequest {
+                name: change.name.clone().unwrap_or_default(),
+                description: change.description.clone(),
+                parent,
+                tags: change.tags.clone(),
+                add_to_bottom: None,
+            };
+            created(client.create_note(in_project, &request).await?.data.note.id)
+        }
+        (Action::Update, Kind::Project) => {
+            let request = UpdateProjectRequest {
+                name: change.name.clone(),
+                full_name: change.full_name.clone(),
+                purpose: change.purpose.clone(),
+            };
+            client.update_project(id, &request).await?;
+            None
+        }
+        (Action::Update, Kind::Task) => {
+            let request = UpdateTaskRequest {
+                name: change.name.clone(),
+                description: change.description.clone(),
+                status: change.status,
+                priority: change.priority,
+                due_date,
+                start_date,
+                responsible_user: change.assignee,
+                ball_holding_user: None,
+                milestone: change.milestone,
+                parent,
+                tags: change.tags.clone(),
+            };
+            client.update_task(in_project, id, &request).await?;
+            None
+        }
+        (Action::Update, Kind::Note) => {
+            let request = UpdateNoteRequest {
+                name: change.name.clone(),
+                description: change.description.clone(),
+                parent: None,
+                tags: change.tags.clone(),
+            };
+            client.update_note(in_project, id, &request).await?;
+            None
+        }
+        (Action::Delete, Kind::Project) => {
+            client.delete_project(id).await?;
+            None
+        }
+        (Action::Delete, Kind::Task) => {
+            client.delete_task(in_project, id).await?;
+            None
+        }
+        (Action::Delete, Kind::Note) => {
+            client.delete_note(in_project, id).await?;
+            None
+        }
+        (Action::Comment, Kind::Task) => {
+            let body = change.body.clone().unwrap_or_default();
+            client.add_task_comment(in_project, id, body, None).await?;
+            None
+        }
+        (Action::Comment, Kind::Note) => {
+            let body = change.body.clone().unwrap_or_default();
+            client.add_note_comment(in_project, id, body).await?;
+            None
+        }
+        (Action::Comment, Kind::Project) => bail!("Projects take no comments"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_and_mapping_path() {
+        let plan = ChangePlan::parse(
+            r#"
+project: "@launch"
+changes:
+  - {action: create, type: project, ref: launch, name: Launch}
+  - {action: update, type: note, id: 99, name: Notes}
+"#,
+        )
+        .unwrap();
+        assert_eq!(describe(&plan, &plan.changes[0]), "create project @launch");
+        assert_eq!(
+            describe(&plan, &plan.changes[1]),
+            "update note 99 in project @launch"
+        );
+
+        let options = ApplyOptions {
+            file: PathBuf::from("plans/launch.yaml"),
+            output: None,
+            yes: false,
+        };
+        assert_eq!(
+            mapping_path(&options),
+            Some(PathBuf::from("plans/launch.ids.json"))
+        );
+        let stdin = ApplyOptions {
+            file: PathBuf::from("-"),
+            ..options
+        };
+        assert_eq!(mapping_path(&stdin), None);
+    }
+
+    #[test]
+    fn test_resolve_symbols() {
+        let refs = BTreeMap::from([(
+            "epic".to_string(),
+            Created {
+                kind: Kind::Task,
+                project_id: Some(12),
+                id: 456,
+            },
+        )]);
+        assert_eq!(
+            resolve(&Ref::Symbol("epic".to_string()), &refs).unwrap(),
+            456
+        );
+        assert_eq!(resolve(&Ref::Id(7), &refs).unwrap(), 7);
+        assert!(resolve(&Ref::Symbol("nope".to_string()), &refs).is_err());
+        assert_eq!(preview("one\ntwo\nthree"), "one (+2 lines)");
+    }
+}
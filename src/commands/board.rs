@@ -0,0 +1,516 @@
+use crate::api::{endpoints::me::TaskFilter, types::*, RepsonaClient};
+use crate::markdown::RenderOptions;
+use crate::output::{format_date, pad_to_width, print, truncate_to_width, OutputFormat};
+use crate::telemetry_span;
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+const MIN_COLUMN_WIDTH: usize = 18;
+const MAX_COLUMN_WIDTH: usize = 40;
+const COLUMN_GAP: &str = " │ ";
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "board".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct BoardOptions {
+    pub project_id: u64,
+    pub assignee: Option<String>,
+    pub tag: Option<String>,
+    pub milestone: Option<String>,
+    pub compact: bool,
+}
+
+/// One board column: a project status and the tasks currently in it
+#[derive(Debug, Serialize)]
+pub struct BoardColumn {
+    pub status: Status,
+    pub tasks: Vec<Task>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Board {
+    pub project_id: u64,
+    pub columns: Vec<BoardColumn>,
+}
+
+pub async fn handle(client: &RepsonaClient, options: BoardOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let (statuses, tasks) =
+        telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || async {
+            let filter = TaskFilter::default();
+            let (statuses, tasks) = tokio::join!(
+                client.list_project_statuses(options.project_id),
+                client.list_tasks(options.project_id, &filter)
+            );
+            Ok::<_, anyhow::Error>((statuses?.data.statuses, tasks?.data.tasks))
+        })
+        .await?;
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let board = telemetry_span::with_span("prepare_request", &prepare_attrs, || {
+        let tasks = tasks
+            .into_iter()
+            .filter(|t| matches_filters(t, &options))
+            .collect();
+        Board {
+            project_id: options.project_id,
+            columns: group_by_status(statuses, tasks),
+        }
+    });
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&board, format),
+        OutputFormat::Human => {
+            let width = RenderOptions::for_stdout().width;
+            println!("{}", render_board(&board, width, options.compact, now()));
+            Ok(())
+        }
+    })?;
+
+    Ok(())
+}
+
+fn now() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+/// Match an ID-or-name filter value against an entity
+pub(crate) fn matches_ref(filter: &str, id: u64, names: &[&str]) -> bool {
+    let filter = filter.trim();
+    if let Ok(filter_id) = filter.parse::<u64>() {
+        return filter_id == id;
+    }
+    names.iter().any(|n| n.eq_ignore_ascii_case(filter))
+}
+
+fn matches_filters(task: &Task, options: &BoardOptions) -> bool {
+    if let Some(assignee) = &options.assignee {
+        let matched = task
+            .responsible_user
+            .as_ref()
+            .is_some_and(|u| matches_ref(assignee, u.id, &[&u.name, &u.full_name]));
+        if !matched {
+            return false;
+        }
+    }
+    if let Some(tag) = &options.tag {
+        if !task.tags.iter().any(|t| matches_ref(tag, t.id, &[&t.name])) {
+            return false;
+        }
+    }
+    if let Some(milestone) = &options.milestone {
+        let matched = task
+            .milestone
+            .as_ref()
+            .is_some_and(|m| matches_ref(milestone, m.id, &[&m.name]));
+        if !matched {
+            return false;
+        }
+    }
+    true
+}
+
+/// Group tasks into columns in project status order.
+///
+/// Tasks whose status is missing from the status list get trailing columns
+/// so nothing silently disappears from the board.
+pub fn group_by_status(statuses: Vec<Status>, tasks: Vec<Task>) -> Vec<BoardColumn> {
+    let mut columns: Vec<BoardColumn> = statuses
+        .into_iter()
+        .map(|status| BoardColumn {
+            status,
+            tasks: Vec::new(),
+        })
+        .collect();
+
+    for task in tasks {
+        match columns.iter_mut().find(|c| c.status.id == task.status.id) {
+            Some(column) => column.tasks.push(task),
+            None => columns.push(BoardColumn {
+                status: task.status.clone(),
+                tasks: vec![task],
+            }),
+        }
+    }
+
+    for column in &mut columns {
+        column.tasks.sort_by_key(|t| (t.sort_order, t.id));
+    }
+    columns
+}
+
+/// Initials of a user's display name, e.g. "Jane Doe" -> "JD"
+pub fn initials(user: &User) -> String {
+    let name = if user.full_name.trim().is_empty() {
+        &user.name
+    } else {
+        &user.full_name
+    };
+    name.split_whitespace()
+        .take(2)
+        .filter_map(|w| w.chars().next())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// A pre-styled line with its visible width
+struct Line {
+    text: String,
+    width: usize,
+}
+
+impl Line {
+    fn plain(text: String) -> Self {
+        let width = text.width();
+        Line { text, width }
+    }
+}
+
+fn card_lines(task: &Task, width: usize, compact: bool, now: u64) -> Vec<Line> {
+    let title = format!("#{} {}", task.id, task.name);
+
+    let mut meta = Vec::new();
+    if let Some(user) = &task.responsible_user {
+        meta.push(initials(user).cyan().to_string());
+    }
+    if task.priority > 0 {
+        meta.push(format!("P{}", task.priority).yellow().to_string());
+    }
+    if let Some(due) = task.due_date {
+        let label = format_date(due);
+        if due < now && !task.status.is_closed {
+            meta.push(label.red().bold().to_string());
+        } else {
+            meta.push(label.dimmed().to_string());
+        }
+    }
+    let meta_width: usize =
+        meta.iter().map(|m| console_width(m)).sum::<usize>() + meta.len().saturating_sub(1);
+
+    if compact {
+        let room = width.saturating_sub(if meta.is_empty() { 0 } else { meta_width + 1 });
+        if room >= 8 && !meta.is_empty() {
+            let head = pad_to_width(&truncate_to_width(&title, room), room);
+            return vec![Line {
+                text: format!("{} {}", head, meta.join(" ")),
+                width: room + 1 + meta_width,
+            }];
+        }
+        return vec![Line::plain(truncate_to_width(&title, width))];
+    }
+
+    let (first, rest) = split_at_width(&title, width);
+    let mut lines = vec![Line::plain(first)];
+    if !rest.is_empty() {
+        lines.push(Line::plain(truncate_to_width(&rest, width)));
+    }
+    if !meta.is_empty() && meta_width <= width {
+        lines.push(Line {
+            text: meta.join(" "),
+            width: meta_width,
+        });
+    }
+    lines
+}
+
+/// Split text at the last space that fits the width (or mid-word when none does)
+fn split_at_width(text: &str, width: usize) -> (String, String) {
+    if text.width() <= width {
+        return (text.to_string(), String::new());
+    }
+
+    let mut used = 0;
+    let mut cut = 0;
+    let mut last_space = None;
+    for (idx, ch) in text.char_indices() {
+        let ch_width = ch.to_string().width();
+        if used + ch_width > width {
+            break;
+        }
+        if ch == ' ' {
+            last_space = Some(idx);
+        }
+        used += ch_width;
+        cut = idx + ch.len_utf8();
+    }
+
+    let split = last_space.filter(|&idx| idx > 0).unwrap_or(cut);
+    (
+        text[..split].trim_end().to_string(),
+        text[split..].trim_start().to_string(),
+    )
+}
+
+/// Visible width of a string that may contain ANSI escape sequences
+fn console_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for ch in s.chars() {
+        if in_escape {
+            if ch.is_ascii_alphabetic() {
+                in_escape = false;
+            }
+        } else if ch == '\u{1b}' {
+            in_escape = true;
+        } else {
+            width += ch.to_string().width();
+        }
+    }
+    width
+}
+
+fn column_lines(column: &BoardColumn, width: usize, compact: bool, now: u64) -> Vec<Line> {
+    let mut lines = Vec::new();
+    for (i, task) in column.tasks.iter().enumerate() {
+        if i > 0 && !compact {
+            lines.push(Line::plain(String::new()));
+        }
+        lines.extend(card_lines(task, width, compact, now));
+    }
+    lines
+}
+
+/// Lay out the board as side-by-side columns, wrapping into bands when
+/// the terminal is too narrow to show every status at once
+pub fn render_board(board: &Board, term_width: usize, compact: bool, now: u64) -> String {
+    if board.columns.is_empty() {
+        return "No statuses defined for this project".dimmed().to_string();
+    }
+
+    let gap = COLUMN_GAP.width();
+    let count = board.columns.len();
+    let fit = term_width.saturating_sub(gap * (count - 1)) / count;
+    let column_width = fit.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+    let per_band = ((term_width + gap) / (column_width + gap)).max(1);
+
+    let mut out = Vec::new();
+    for band in board.columns.chunks(per_band) {
+        if !out.is_empty() {
+            out.push(String::new());
+        }
+
+        let headers: Vec<String> = band
+            .iter()
+            .map(|c| {
+                let label = format!("{} ({})", c.status.name, c.tasks.len());
+                let label = truncate_to_width(&label, column_width);
+                let padded = pad_to_width(&label, column_width);
+                if c.status.is_closed {
+                    padded.green().bold().to_string()
+                } else {
+                    padded.bold().to_string()
+                }
+            })
+            .collect();
+        out.push(headers.join(&COLUMN_GAP.dimmed().to_string()));
+
+        let rules: Vec<String> = band.iter().map(|_| "─".repeat(column_width)).collect();
+        out.push(rules.join("─┼─").dimmed().to_string());
+
+        let bodies: Vec<Vec<Line>> = band
+            .iter()
+            .map(|c| column_lines(c, column_width, compact, now))
+            .collect();
+        let height = bodies.iter().map(Vec::len).max().unwrap_or(0);
+        for row in 0..height {
+            let cells: Vec<String> = bodies
+                .iter()
+                .map(|lines| match lines.get(row) {
+                    Some(line) => format!(
+                        "{}{}",
+                        line.text,
+                        " ".repeat(column_width.saturating_sub(line.width))
+                    ),
+                    None => " ".repeat(column_width),
+                })
+                .collect();
+            out.push(
+                cells
+                    .join(&COLUMN_GAP.dimmed().to_string())
+                    .trim_end()
+                    .to_string(),
+            );
+        }
+    }
+
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn status(id: u64, name: &str) -> Status {
+        Status {
+            id,
+            name: name.to_string(),
+            is_closed: false,
+            color: None,
+        }
+    }
+
+    fn user(id: u64, name: &str, full_name: &str) -> User {
+        User {
+            id,
+            email: format!("{}@example.com", name),
+            name: name.to_string(),
+            full_name: full_name.to_string(),
+            avatar_url: None,
+            role: "member".to_string(),
+            billing_status: "active".to_string(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn task(id: u64, status_id: u64, name: &str) -> Task {
+        Task {
+            status: status(status_id, "Unknown"),
+            sort_order: id as u32,
+            ..test_support::task(id).named(name).in_project(1, "p1")
+        }
+    }
+
+    fn options() -> BoardOptions {
+        BoardOptions {
+            project_id: 1,
+            assignee: None,
+            tag: None,
+            milestone: None,
+            compact: false,
+        }
+    }
+
+    #[test]
+    fn test_group_by_status_keeps_status_order() {
+        let statuses = vec![status(1, "Todo"), status(2, "Doing"), status(3, "Done")];
+        let tasks = vec![task(10, 3, "c"), task(11, 1, "a"), task(12, 1, "b")];
+
+        let columns = group_by_status(statuses, tasks);
+
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns[0].status.name, "Todo");
+        assert_eq!(
+            columns[0].tasks.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![11, 12]
+        );
+        assert!(columns[1].tasks.is_empty());
+        assert_eq!(columns[2].tasks[0].id, 10);
+    }
+
+    #[test]
+    fn test_group_by_status_adds_unknown_status_column() {
+        let columns = group_by_status(vec![status(1, "Todo")], vec![task(10, 99, "orphan")]);
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[1].status.id, 99);
+    }
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials(&user(1, "jdoe", "Jane Doe")), "JD");
+        assert_eq!(initials(&user(1, "alice", "")), "A");
+        assert_eq!(initials(&user(1, "x", "Anne Marie Smith")), "AM");
+    }
+
+    #[test]
+    fn test_filters_by_assignee_name_or_id() {
+        let mut t = task(1, 1, "a");
+        t.responsible_user = Some(user(7, "alice", "Alice Liddell"));
+
+        let mut opts = options();
+        opts.assignee = Some("alice".to_string());
+        assert!(matches_filters(&t, &opts));
+        opts.assignee = Some("7".to_string());
+        assert!(matches_filters(&t, &opts));
+        opts.assignee = Some("bob".to_string());
+        assert!(!matches_filters(&t, &opts));
+    }
+
+    #[test]
+    fn test_filters_by_tag_and_milestone() {
+        let mut t = task(1, 1, "a");
+        t.tags = vec![Tag {
+            id: 3,
+            name: "bug".to_string(),
+            color: "#f00".to_string(),
+        }];
+        t.milestone = Some(Milestone {
+            id: 5,
+            name: "v1".to_string(),
+            due_date: None,
+            is_closed: false,
+        });
+
+        let mut opts = options();
+        opts.tag = Some("BUG".to_string());
+        opts.milestone = Some("v1".to_string());
+        assert!(matches_filters(&t, &opts));
+        opts.milestone = Some("6".to_string());
+        assert!(!matches_filters(&t, &opts));
+    }
+
+    #[test]
+    fn test_render_board_fits_width() {
+        let board = Board {
+            project_id: 1,
+            columns: group_by_status(
+                vec![status(1, "Todo"), status(2, "Doing")],
+                vec![
+                    task(1, 1, "A fairly long task title that needs wrapping"),
+                    task(2, 2, "Short"),
+                ],
+            ),
+        };
+
+        let out = render_board(&board, 60, false, 0);
+
+        assert!(out.contains("Todo (1)"));
+        assert!(out.contains("#2 Short"));
+        assert!(out.lines().all(|l| console_width(l) <= 60));
+    }
+
+    #[test]
+    fn test_render_board_wraps_columns_into_bands() {
+        let statuses = (1..=6).map(|i| status(i, &format!("S{}", i))).collect();
+        let board = Board {
+            project_id: 1,
+            columns: group_by_status(statuses, Vec::new()),
+        };
+
+        let out = render_board(&board, 50, true, 0);
+
+        let header_lines = out.lines().filter(|l| l.contains("(0)")).count();
+        assert!(header_lines > 1);
+    }
+
+    #[test]
+    fn test_split_at_width_prefers_spaces() {
+        assert_eq!(
+            split_at_width("#1 fix the login page", 12),
+            ("#1 fix the".to_string(), "login page".to_string())
+        );
+        assert_eq!(
+            split_at_width("abcdefghij", 4),
+            ("abcd".to_string(), "efghij".to_string())
+        );
+    }
+
+    #[test]
+    fn test_console_width_ignores_ansi() {
+        assert_eq!(console_width("\u{1b}[31mabc\u{1b}[0m"), 3);
+    }
+}
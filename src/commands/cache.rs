@@ -0,0 +1,44 @@
+use crate::api::RepsonaClient;
+use crate::cache::Cache;
+use crate::cli::CacheCommands;
+use crate::output::{print_item, print_success, OutputFormat};
+use anyhow::Result;
+use std::fs;
+
+pub async fn handle(client: &RepsonaClient, command: CacheCommands, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    match command {
+        CacheCommands::Stats => {
+            let entries = match client.cache() {
+                Some(cache) => cache.stats()?,
+                None => Vec::new(),
+            };
+            print_item("cache_entries", &entries, format)?;
+        }
+        CacheCommands::Clear { all } => {
+            if all {
+                let root = Cache::root_dir()?;
+                if root.exists() {
+                    fs::remove_dir_all(&root)?;
+                }
+                print_success(&format!("Removed cache directory {}", root.display()));
+            } else {
+                if let Some(cache) = client.cache() {
+                    let removed = cache.clear()?;
+                    print_success(&format!(
+                        "Removed {} cached entries from {}",
+                        removed,
+                        cache.dir().display()
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,373 @@
+use crate::api::{types::*, RepsonaClient};
+use crate::cli::{CalendarCommands, CalendarSelection};
+use crate::commands::task_query::find_tasks;
+use crate::ical::{self, Entry, Kind};
+use crate::output::print_success;
+use crate::query::TaskQuery;
+use crate::telemetry_span;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, TimeZone};
+use colored::Colorize;
+use futures::{stream, StreamExt};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+
+const CONCURRENCY: usize = 4;
+/// Largest request head the feed server reads
+const MAX_REQUEST: usize = 8 * 1024;
+/// How long a connection may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "calendar".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub async fn handle(client: &RepsonaClient, command: CalendarCommands) -> Result<()> {
+    match command {
+        CalendarCommands::Export { selection, out } => {
+            let exec_attrs = phase_attrs("execute_operation");
+            let ics = telemetry_span::with_span_async_result("build_calendar", &exec_attrs, || {
+                build(client, &selection)
+            })
+            .await?;
+            let render_attrs = phase_attrs("render_output");
+            telemetry_span::with_span_result("render_output", &render_attrs, || match &out {
+                Some(path) => {
+                    std::fs::write(path, &ics)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    print_success(&format!("Calendar written to {}", path.display()));
+                    Ok(())
+                }
+                None => {
+                    print!("{}", ics);
+                    Ok(())
+                }
+            })
+        }
+        CalendarCommands::Serve {
+            selection,
+            port,
+            bind,
+            refresh,
+        } => {
+            serve(
+                client,
+                &selection,
+                &bind,
+                port,
+                Duration::from_secs(refresh),
+            )
+            .await
+        }
+    }
+}
+
+/// Local calendar date of a timestamp
+pub(crate) fn local_date(timestamp: u64) -> NaiveDate {
+    Local
+        .timestamp_opt(timestamp as i64, 0)
+        .earliest()
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default()
+}
+
+fn task_entry(task: &Task, space: &str, kind: Kind) -> Option<Entry> {
+    let due = task.due_date.or(task.start_date).map(local_date)?;
+    let mut categories = vec![task.project.name.clone()];
+    categories.extend(task.tags.iter().map(|t| t.name.clone()));
+    Some(Entry {
+        uid: format!("task-{}@{}.repsona", task.id, space),
+        kind,
+        summary: task.name.clone(),
+        description: task.description.clone(),
+        categories,
+        start: task.start_date.map(local_date),
+        due,
+        completed: task.status.is_closed,
+        modified: task.updated_at,
+    })
+}
+
+fn milestone_entry(milestone: &Milestone, project: &str, space: &str) -> Option<Entry> {
+    Some(Entry {
+        uid: format!("milestone-{}@{}.repsona", milestone.id, space),
+        kind: Kind::Event,
+        summary: format!("{}: {}", project, milestone.name),
+        description: None,
+        categories: vec![project.to_string(), "milestone".to_string()],
+        start: None,
+        due: local_date(milestone.due_date?),
+        completed: milestone.is_closed,
+        // Milestones carry no update time; stamping them with the due date keeps the output
+        // stable while still marking the entry as changed when the date moves
+        modified: milestone.due_date?,
+    })
+}
+
+fn selection_query(selection: &CalendarSelection) -> Result<TaskQuery> {
+    let query = match &selection.filter {
+        Some(expr) => TaskQuery::parse_where(expr)?,
+        None => TaskQuery::default(),
+    };
+    Ok(query.merged_with(TaskQuery {
+        projects: selection.projects.clone(),
+        assignee: selection.assignee.clone(),
+        include_closed: selection.include_closed,
+        ..Default::default()
+    }))
+}
+
+/// Load the selected tasks and milestones and render them as an iCalendar document
+async fn build(client: &RepsonaClient, selection: &CalendarSelection) -> Result<String> {
+    let query = selection_query(selection)?;
+    let tasks = find_tasks(client, &query, CONCURRENCY).await?;
+    let space = client.space_id();
+    let kind = if selection.todo {
+        Kind::Todo
+    } else {
+        Kind::Event
+    };
+    let mut entries: Vec<Entry> = tasks
+        .iter()
+        .filter_map(|t| task_entry(t, space, kind))
+        .collect();
+
+    if !selection.no_milestones {
+        let projects: Vec<Project> = client
+            .list_projects()
+            .await?
+            .data
+            .projects
+            .into_iter()
+            .filter(|p| {
+                if query.projects.is_empty() {
+                    !p.is_closed
+                } else {
+                    query.projects.contains(&p.id)
+                }
+            })
+            .collect();
+        let milestones: Vec<(String, Result<Vec<Milestone>>)> = stream::iter(projects)
+            .map(|project| async move {
+                let milestones = client
+                    .list_project_milestones(project.id)
+                    .await
+                    .map(|r| r.data.milestones);
+                (project.name, milestones)
+            })
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+        for (project, result) in milestones {
+            match result {
+                Ok(list) => entries.extend(
+                    list.iter()
+                        .filter_map(|m| milestone_entry(m, &project, space)),
+                ),
+                Err(e) => eprintln!(
+                    "{}",
+                    format!("Skipping milestones of {}: {}", project, e).yellow()
+                ),
+            }
+        }
+    }
+
+    Ok(ical::render(&format!("Repsona {}", space), &entries))
+}
+
+/// The path of an HTTP request, if it is a GET or HEAD
+fn request_path(head: &str) -> Option<(bool, &str)> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let path = parts.next()?.split('?').next()?;
+    match method {
+        "GET" => Some((false, path)),
+        "HEAD" => Some((true, path)),
+        _ => None,
+    }
+}
+
+fn response(status: &str, content_type: &str, body: &str, head_only: bool) -> Vec<u8> {
+    let mut out = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )
+    .into_bytes();
+    if !head_only {
+        out.extend_from_slice(body.as_bytes());
+    }
+    out
+}
+
+async fn read_head(stream: &mut TcpStream) -> Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+/// Serve the calendar at `/` and any `*.ics` path, reloading it at most every `refresh`
+async fn serve(
+    client: &RepsonaClient,
+    selection: &CalendarSelection,
+    bind: &str,
+    port: u16,
+    refresh: Duration,
+) -> Result<()> {
+    // Fail on a bad filter before listening
+    selection_query(selection)?;
+    let listener = TcpListener::bind((bind, port))
+        .await
+        .with_context(|| format!("Failed to listen on {}:{}", bind, port))?;
+    let address = listener.local_addr()?;
+    eprintln!(
+        "Serving calendar at http://{}/calendar.ics (Ctrl-C to stop)",
+        address
+    );
+    if !address.ip().is_loopback() {
+        eprintln!(
+            "{}",
+            "The feed has no authentication; anyone who can reach this address can read it"
+                .yellow()
+        );
+    }
+
+    // Connections are read and answered in their own tasks; only building the calendar,
+    // which needs the client, happens here
+    let (sender, mut requests) = mpsc::channel::<FeedRequest>(16);
+    let mut cached: Option<(Instant, String)> = None;
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                tokio::spawn(handle_connection(stream, peer, sender.clone()));
+            }
+            Some(request) = requests.recv() => {
+                let fresh = cached
+                    .as_ref()
+                    .is_some_and(|(at, _)| at.elapsed() < refresh);
+                if !fresh {
+                    match build(client, selection).await {
+                        Ok(ics) => cached = Some((Instant::now(), ics)),
+                        Err(e) => {
+                            eprintln!("{}", format!("Failed to load calendar: {:#}", e).red())
+                        }
+                    }
+                }
+                let reply = match &cached {
+                    Some((_, ics)) => response(
+                        "200 OK",
+                        "text/calendar; charset=utf-8",
+                        ics,
+                        request.head_only,
+                    ),
+                    None => response(
+                        "502 Bad Gateway",
+                        "text/plain",
+                        "Failed to load the calendar from Repsona\n",
+                        request.head_only,
+                    ),
+                };
+                let _ = request.reply.send(reply);
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// A calendar request from a connection task, answered by the serve loop
+struct FeedRequest {
+    head_only: bool,
+    reply: oneshot::Sender<Vec<u8>>,
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    requests: mpsc::Sender<FeedRequest>,
+) {
+    let Ok(Ok(head)) = tokio::time::timeout(READ_TIMEOUT, read_head(&mut stream)).await else {
+        return;
+    };
+    let reply = match request_path(&head) {
+        None => response("405 Method Not Allowed", "text/plain", "", false),
+        Some((head_only, path)) if path == "/" || path.ends_with(".ics") => {
+            eprintln!("{} {}", peer, path);
+            let (reply, answer) = oneshot::channel();
+            if requests
+                .send(FeedRequest { head_only, reply })
+                .await
+                .is_err()
+            {
+                return;
+            }
+            match answer.await {
+                Ok(reply) => reply,
+                Err(_) => return,
+            }
+        }
+        Some((head_only, _)) => response("404 Not Found", "text/plain", "Not found\n", head_only),
+    };
+    let _ = stream.write_all(&reply).await;
+    let _ = stream.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_path_and_selection() {
+        assert_eq!(
+            request_path("GET /calendar.ics?x=1 HTTP/1.1\r\nHost: a\r\n\r\n"),
+            Some((false, "/calendar.ics"))
+        );
+        assert_eq!(request_path("HEAD / HTTP/1.1\r\n"), Some((true, "/")));
+        assert_eq!(request_path("POST / HTTP/1.1\r\n"), None);
+
+        let selection = CalendarSelection {
+            projects: vec![12],
+            assignee: Some("me".to_string()),
+            filter: Some("tag=release,assignee=bob".to_string()),
+            include_closed: false,
+            no_milestones: false,
+            todo: false,
+        };
+        let query = selection_query(&selection).unwrap();
+        assert_eq!(query.projects, vec![12]);
+        assert_eq!(query.assignee.as_deref(), Some("me"));
+        assert_eq!(query.tag.as_deref(), Some("release"));
+    }
+
+    #[test]
+    fn test_milestone_entry_is_stamped_with_due_date() {
+        let milestone = Milestone {
+            id: 3,
+            name: "Beta".to_string(),
+            due_date: Some(1_790_000_000),
+            is_closed: false,
+        };
+        let entry = milestone_entry(&milestone, "Web", "acme").unwrap();
+        assert_eq!(entry.uid, "milestone-3@acme.repsona");
+        assert_eq!(entry.modified, 1_790_000_000);
+        let undated = Milestone {
+            due_date: None,
+            ..milestone
+        };
+        assert!(milestone_entry(&undated, "Web", "acme").is_none());
+    }
+}
@@ -0,0 +1,283 @@
+use crate::api::{types::*, RepsonaClient};
+use crate::commands::calendar::local_date;
+use crate::commands::task_query::find_tasks;
+use crate::markdown::RenderOptions;
+use crate::output::{pad_to_width, print, truncate_to_width, OutputFormat};
+use crate::query::TaskQuery;
+use crate::telemetry_span;
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const CONCURRENCY: usize = 4;
+const MIN_CELL_WIDTH: usize = 6;
+const MAX_CELL_WIDTH: usize = 24;
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "calendar".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct MonthOptions {
+    /// Project ID or "me"
+    pub target: String,
+    pub month: Option<String>,
+    pub lines: usize,
+    pub include_closed: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct MonthDay<'a> {
+    date: String,
+    tasks: Vec<&'a Task>,
+}
+
+#[derive(Debug, Serialize)]
+struct Month<'a> {
+    month: String,
+    days: Vec<MonthDay<'a>>,
+}
+
+pub async fn handle(client: &RepsonaClient, options: MonthOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let today = Local::now().date_naive();
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let (first, query) =
+        telemetry_span::with_span_result("prepare_request", &prepare_attrs, || {
+            let first = parse_month(options.month.as_deref(), today)?;
+            let mut query = target_query(&options.target)?;
+            query.include_closed = options.include_closed;
+            Ok::<_, anyhow::Error>((first, query))
+        })?;
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let tasks = telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || {
+        find_tasks(client, &query, CONCURRENCY)
+    })
+    .await?;
+
+    let days = by_due_date(&tasks, first);
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => {
+            let month = Month {
+                month: first.format("%Y-%m").to_string(),
+                days: days
+                    .iter()
+                    .map(|(date, tasks)| MonthDay {
+                        date: date.to_string(),
+                        tasks: tasks.clone(),
+                    })
+                    .collect(),
+            };
+            print(&month, format)
+        }
+        OutputFormat::Human => {
+            let width = RenderOptions::for_stdout().width;
+            print!(
+                "{}",
+                render_month(first, &days, today, width, options.lines.max(1))
+            );
+            Ok(())
+        }
+    })
+}
+
+/// Tasks for `rpsn calendar <project|me>`
+fn target_query(target: &str) -> Result<TaskQuery> {
+    let target = target.trim();
+    if target.eq_ignore_ascii_case("me") {
+        return Ok(TaskQuery {
+            assignee: Some("me".to_string()),
+            ..Default::default()
+        });
+    }
+    match target.parse::<u64>() {
+        Ok(project_id) => Ok(TaskQuery {
+            projects: vec![project_id],
+            ..Default::default()
+        }),
+        Err(_) => bail!("Expected a project ID or \"me\", got \"{}\"", target),
+    }
+}
+
+/// First day of a `YYYY-MM` month, or of the current month
+fn parse_month(value: Option<&str>, today: NaiveDate) -> Result<NaiveDate> {
+    match value {
+        None => Ok(today.with_day(1).unwrap_or(today)),
+        Some(value) => NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d")
+            .with_context(|| format!("Invalid month \"{}\" (expected YYYY-MM)", value)),
+    }
+}
+
+fn next_month(first: NaiveDate) -> NaiveDate {
+    first
+        .checked_add_months(chrono::Months::new(1))
+        .unwrap_or(first)
+}
+
+/// Tasks due within the month, keyed by local due date
+fn by_due_date(tasks: &[Task], first: NaiveDate) -> BTreeMap<NaiveDate, Vec<&Task>> {
+    let end = next_month(first);
+    let mut days: BTreeMap<NaiveDate, Vec<&Task>> = BTreeMap::new();
+    for task in tasks {
+        if let Some(due) = task.due_date.map(local_date) {
+            if due >= first && due < end {
+                days.entry(due).or_default().push(task);
+            }
+        }
+    }
+    days
+}
+
+fn task_line(task: &Task, today: NaiveDate, width: usize) -> String {
+    let due = task.due_date.map(local_date);
+    let overdue = !task.status.is_closed && due.is_some_and(|d| d < today);
+    let marker = if task.status.is_closed {
+        "✓"
+    } else if overdue {
+        "!"
+    } else {
+        "•"
+    };
+    let text = pad_to_width(
+        &truncate_to_width(&format!("{}{}", marker, task.name), width),
+        width,
+    );
+    if task.status.is_closed {
+        text.dimmed().to_string()
+    } else if overdue {
+        text.red().to_string()
+    } else {
+        text
+    }
+}
+
+/// Month grid, Monday first, with up to `lines` task lines per day
+fn render_month(
+    first: NaiveDate,
+    days: &BTreeMap<NaiveDate, Vec<&Task>>,
+    today: NaiveDate,
+    width: usize,
+    lines: usize,
+) -> String {
+    let cell = (width.saturating_sub(6) / 7).clamp(MIN_CELL_WIDTH, MAX_CELL_WIDTH);
+    let end = next_month(first);
+    let mut out = format!("{}\n", first.format("%B %Y").to_string().bold());
+    let header: Vec<String> = WEEKDAYS
+        .iter()
+        .map(|d| pad_to_width(d, cell).bold().to_string())
+        .collect();
+    out.push_str(&header.join("│"));
+    out.push('\n');
+
+    let mut week_start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    while week_start < end {
+        let week: Vec<Option<NaiveDate>> = (0..7)
+            .map(|i| week_start + Duration::days(i))
+            .map(|d| (d >= first && d < end).then_some(d))
+            .collect();
+        out.push_str(&vec!["─".repeat(cell); 7].join("┼"));
+        out.push('\n');
+
+        let numbers: Vec<String> = week
+            .iter()
+            .map(|day| match day {
+                Some(d) if *d == today => pad_to_width(&format!("[{}]", d.day()), cell)
+                    .reversed()
+                    .to_string(),
+                Some(d) => pad_to_width(&d.day().to_string(), cell),
+                None => " ".repeat(cell),
+            })
+            .collect();
+        out.push_str(&numbers.join("│"));
+        out.push('\n');
+
+        let busiest = week
+            .iter()
+            .flatten()
+            .map(|d| days.get(d).map_or(0, |t| t.len()))
+            .max()
+            .unwrap_or(0);
+        let rows = busiest.min(lines);
+        for row in 0..rows {
+            let cells: Vec<String> = week
+                .iter()
+                .map(|day| {
+                    let tasks = day.and_then(|d| days.get(&d)).map_or(&[][..], |t| &t[..]);
+                    let collapsed = tasks.len() > rows;
+                    if collapsed && row == rows - 1 {
+                        let more = format!("+{} more", tasks.len() - row);
+                        pad_to_width(&truncate_to_width(&more, cell), cell)
+                    } else {
+                        match tasks.get(row) {
+                            Some(task) => task_line(task, today, cell),
+                            None => " ".repeat(cell),
+                        }
+                    }
+                })
+                .collect();
+            out.push_str(&cells.join("│"));
+            out.push('\n');
+        }
+        week_start += Duration::days(7);
+    }
+
+    let count: usize = days.values().map(Vec::len).sum();
+    out.push_str(&format!(
+        "\n{} task(s) due in {}\n",
+        count,
+        first.format("%B %Y")
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::test_support::color_override;
+
+    #[test]
+    fn test_parse_month_and_target() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        assert_eq!(
+            parse_month(None, today).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 1).unwrap()
+        );
+        assert_eq!(
+            parse_month(Some("2026-11"), today).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 11, 1).unwrap()
+        );
+        assert!(parse_month(Some("November"), today).is_err());
+        assert_eq!(target_query("12").unwrap().projects, vec![12]);
+        assert_eq!(target_query("me").unwrap().assignee.as_deref(), Some("me"));
+        assert!(target_query("web").is_err());
+    }
+
+    #[test]
+    fn test_render_month_grid() {
+        let _color = color_override(false);
+        let first = NaiveDate::from_ymd_opt(2026, 11, 1).unwrap();
+        let days = BTreeMap::new();
+        let today = NaiveDate::from_ymd_opt(2026, 11, 18).unwrap();
+        let grid = render_month(first, &days, today, 76, 3);
+        let lines: Vec<&str> = grid.lines().collect();
+        assert_eq!(lines[0], "November 2026");
+        assert!(lines[1].starts_with("Mon       │Tue"));
+        // November 2026 starts on a Sunday and spans six weeks
+        assert!(lines[3].ends_with("│1         "));
+        assert_eq!(lines.iter().filter(|l| l.starts_with('─')).count(), 6);
+        assert!(grid.contains("│[18]      │"));
+        assert!(grid.ends_with("0 task(s) due in November 2026\n"));
+    }
+}
@@ -0,0 +1,179 @@
+use anyhow::{bail, Result};
+use clap::CommandFactory;
+
+use crate::api::{endpoints::me::TaskFilter, RepsonaClient};
+use crate::cache::{Cache, CacheMode, Resource};
+use crate::cli::{Cli, Shell};
+use crate::completion::{analyze, matching_entries, matching_words, Completion, Entry, Source};
+use crate::config;
+
+const BASH_SCRIPT: &str = r#"# rpsn dynamic completion for bash
+_rpsn() {
+    local IFS=$'\n'
+    local candidates
+    candidates=$(rpsn complete-words -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null) || return
+    COMPREPLY=($(printf '%s\n' "$candidates" | cut -f1))
+}
+complete -o default -F _rpsn rpsn
+"#;
+
+const ZSH_SCRIPT: &str = r#"#compdef rpsn
+# rpsn dynamic completion for zsh
+_rpsn() {
+    local -a values displays
+    local line value
+    for line in "${(@f)$(rpsn complete-words -- "${(@)words[2,CURRENT]}" 2>/dev/null)}"; do
+        [[ -z $line ]] && continue
+        value=${line%%$'\t'*}
+        values+=("$value")
+        if [[ $line == *$'\t'* ]]; then
+            displays+=("$value -- ${line#*$'\t'}")
+        else
+            displays+=("$value")
+        fi
+    done
+    (( ${#values} )) && compadd -U -l -d displays -- "${values[@]}"
+}
+compdef _rpsn rpsn
+"#;
+
+const FISH_SCRIPT: &str = r#"# rpsn dynamic completion for fish
+function __rpsn_complete
+    set -l tokens (commandline -opc) (commandline -ct)
+    rpsn complete-words -- $tokens[2..-1] 2>/dev/null
+end
+complete -c rpsn -f -a '(__rpsn_complete)'
+"#;
+
+/// Completion script that delegates to `rpsn complete-words`, if the shell is supported
+pub fn dynamic_script(shell: Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Bash => Some(BASH_SCRIPT),
+        Shell::Zsh => Some(ZSH_SCRIPT),
+        Shell::Fish => Some(FISH_SCRIPT),
+        Shell::Elvish | Shell::Powershell => None,
+    }
+}
+
+/// Print candidates for the last of `words` (the rest are the words before it).
+///
+/// One candidate per line, with an optional tab-separated description.
+/// Errors are swallowed so a failed lookup never breaks the user's shell.
+pub async fn handle_complete(words: Vec<String>) -> Result<()> {
+    let (partial, prior) = match words.split_last() {
+        Some((last, rest)) => (last.as_str(), rest),
+        None => ("", &[][..]),
+    };
+    let prior: Vec<&str> = prior.iter().map(String::as_str).collect();
+
+    let mut command = Cli::command();
+    command.build();
+    let candidates = match analyze(&command, &prior, partial, None) {
+        Completion::Words(words) => matching_words(&words, partial),
+        Completion::Dynamic(source) => {
+            matching_entries(&load_entries(source).await.unwrap_or_default(), partial)
+        }
+        Completion::Nothing => Vec::new(),
+    };
+
+    for candidate in candidates {
+        match candidate.description {
+            Some(description) => println!("{}\t{}", candidate.value, description),
+            None => println!("{}", candidate.value),
+        }
+    }
+    Ok(())
+}
+
+/// Cache key for task and note titles; projects and users share the client's metadata cache
+fn cache_key(source: Source) -> Option<String> {
+    match source {
+        Source::Projects | Source::Users => None,
+        Source::Tasks(project_id) => Some(format!("completion/project/{}/tasks", project_id)),
+        Source::Notes(project_id) => Some(format!("completion/project/{}/notes", project_id)),
+    }
+}
+
+async fn load_entries(source: Source) -> Result<Vec<Entry>> {
+    let (space_id, api_token) = config::load_credentials()?;
+    if space_id.is_empty() || api_token.is_empty() {
+        bail!("No credentials configured");
+    }
+
+    let cache = Cache::for_space(&config::current_profile_name()?, &space_id)?
+        .with_ttls(config::load_cache_config().ttl);
+    let key = cache_key(source);
+    if let Some(key) = &key {
+        if let Some(entries) = cache.get(key, cache.ttl(Resource::Completion)) {
+            return Ok(entries);
+        }
+    }
+
+    let client =
+        RepsonaClient::new(space_id, api_token, false, false).with_cache(cache, CacheMode::Normal);
+    client.set_quiet(true);
+    let entries = fetch_entries(&client, source).await?;
+    if let (Some(key), Some(cache)) = (&key, client.cache()) {
+        let _ = cache.put(key, &entries);
+    }
+    Ok(entries)
+}
+
+async fn fetch_entries(client: &RepsonaClient, source: Source) -> Result<Vec<Entry>> {
+    let entries = match source {
+        Source::Projects => client
+            .list_projects()
+            .await?
+            .data
+            .projects
+            .into_iter()
+            .map(|p| Entry::new(p.id, p.name))
+            .collect(),
+        Source::Users => client
+            .list_users()
+            .await?
+            .data
+            .users
+            .into_iter()
+            .map(|u| Entry::new(u.id, format!("{} ({})", u.full_name, u.name)))
+            .collect(),
+        Source::Tasks(project_id) => client
+            .list_tasks(project_id, &TaskFilter::default())
+            .await?
+            .data
+            .tasks
+            .into_iter()
+            .map(|t| Entry::new(t.id, t.name))
+            .collect(),
+        Source::Notes(project_id) => client
+            .list_notes(project_id)
+            .await?
+            .data
+            .notes
+            .into_iter()
+            .map(|n| Entry::new(n.id, n.name))
+            .collect(),
+    };
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_scripts_call_complete() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let script = dynamic_script(shell).unwrap();
+            assert!(script.contains("rpsn complete-words --"));
+        }
+        assert!(dynamic_script(Shell::Powershell).is_none());
+    }
+
+    #[test]
+    fn test_cache_keys_are_distinct() {
+        assert_ne!(cache_key(Source::Tasks(1)), cache_key(Source::Notes(1)));
+        assert_ne!(cache_key(Source::Tasks(1)), cache_key(Source::Tasks(2)));
+        assert_eq!(cache_key(Source::Projects), None);
+    }
+}
@@ -1,12 +1,15 @@
+use crate::alias;
 use crate::api::RepsonaClient;
-use crate::cli::ConfigCommands;
+use crate::cli::{AliasCommands, Cli, ConfigCommands};
 use crate::config::{Config, Profile};
-use crate::output::{print, OutputFormat};
-use anyhow::Result;
+use crate::output::{print, print_success, OutputFormat};
+use anyhow::{bail, Result};
+use clap::CommandFactory;
 use colored::Colorize;
 
-pub async fn handle(command: ConfigCommands) -> Result<()> {
+pub async fn handle(command: ConfigCommands, json: bool) -> Result<()> {
     match command {
+        ConfigCommands::Alias(cmd) => handle_alias(cmd, json),
         ConfigCommands::Init => handle_init(),
         ConfigCommands::Get => handle_get(),
         ConfigCommands::Set { space, token } => handle_set(space, token),
@@ -122,3 +125,77 @@ async fn handle_whoami() -> Result<()> {
 
     Ok(())
 }
+
+fn handle_alias(command: AliasCommands, json: bool) -> Result<()> {
+    match command {
+        AliasCommands::Add { name, command } => {
+            if alias::builtin_commands(&Cli::command()).contains(&name) {
+                bail!("'{}' is a built-in command and cannot be an alias", name);
+            }
+            let expansion = alias_expansion(&command)?;
+            let mut config = Config::load()?;
+            config.alias.insert(name.clone(), expansion.clone());
+            config.save()?;
+            print_success(&format!("Alias '{}' = {}", name, expansion));
+        }
+        AliasCommands::List => {
+            let config = Config::load()?;
+            if json {
+                print(&config.alias, OutputFormat::Json)?;
+            } else if config.alias.is_empty() {
+                println!("{}", "No aliases defined".dimmed());
+            } else {
+                for (name, expansion) in &config.alias {
+                    println!("{} = {}", name.cyan().bold(), expansion);
+                }
+            }
+        }
+        AliasCommands::Remove { name } => {
+            let mut config = Config::load()?;
+            if config.alias.remove(&name).is_none() {
+                bail!("No alias named '{}'", name);
+            }
+            config.save()?;
+            print_success(&format!("Alias '{}' removed", name));
+        }
+    }
+    Ok(())
+}
+
+/// Store a single argument verbatim (`"task get $1 $2"`); quote several so they split back the same
+fn alias_expansion(command: &[String]) -> Result<String> {
+    let expansion = match command {
+        [single] => single.clone(),
+        words => shlex::try_join(words.iter().map(String::as_str))?,
+    };
+    if shlex::split(&expansion).is_none_or(|words| words.is_empty()) {
+        bail!("Alias command is empty or has unbalanced quotes");
+    }
+    Ok(expansion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alias_expansion_quoting() {
+        let single = vec!["task query overdue --assignee $1".to_string()];
+        assert_eq!(
+            alias_expansion(&single).unwrap(),
+            "task query overdue --assignee $1"
+        );
+
+        let words = vec![
+            "task".to_string(),
+            "create".to_string(),
+            "$1".to_string(),
+            "--title".to_string(),
+            "Weekly review".to_string(),
+        ];
+        let expansion = alias_expansion(&words).unwrap();
+        assert_eq!(shlex::split(&expansion).unwrap(), words);
+
+        assert!(alias_expansion(&["task 'open".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,525 @@
+use crate::api::{
+    endpoints::{note::UpdateNoteRequest, task::UpdateTaskRequest},
+    types::*,
+    RepsonaClient,
+};
+use crate::commands::board::matches_ref;
+use crate::editor;
+use crate::output::{format_date, print, print_success, OutputFormat};
+use crate::query;
+use crate::telemetry_span;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+fn phase_attrs(group: &str, phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", group.to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+/// Task fields shown above the description
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskHeader {
+    title: String,
+    status: String,
+    priority: u32,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    assignee: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoteHeader {
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Statuses, tags and members the header's names are resolved against
+struct Names {
+    statuses: Vec<Status>,
+    tags: Vec<Tag>,
+    users: Vec<User>,
+    today: NaiveDate,
+}
+
+impl Names {
+    fn status(&self, name: &str) -> Result<u64> {
+        self.statuses
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name.trim()))
+            .map(|s| s.id)
+            .ok_or_else(|| {
+                let known: Vec<&str> = self.statuses.iter().map(|s| s.name.as_str()).collect();
+                anyhow!("Unknown status '{}' (one of: {})", name, known.join(", "))
+            })
+    }
+
+    fn tags(&self, names: &[String]) -> Result<Vec<u64>> {
+        names
+            .iter()
+            .map(|name| {
+                self.tags
+                    .iter()
+                    .find(|t| t.name.eq_ignore_ascii_case(name.trim()))
+                    .map(|t| t.id)
+                    .ok_or_else(|| anyhow!("Unknown tag '{}'", name))
+            })
+            .collect()
+    }
+
+    fn user(&self, name: &str) -> Result<u64> {
+        self.users
+            .iter()
+            .find(|u| matches_ref(name.trim(), u.id, &[&u.name, &u.full_name, &u.email]))
+            .map(|u| u.id)
+            .ok_or_else(|| anyhow!("No project member matches '{}'", name))
+    }
+}
+
+fn task_document(task: &Task) -> Result<String> {
+    let header = TaskHeader {
+        title: task.name.clone(),
+        status: task.status.name.clone(),
+        priority: task.priority,
+        due: task.due_date.map(format_date),
+        tags: task.tags.iter().map(|t| t.name.clone()).collect(),
+        assignee: task.responsible_user.as_ref().map(|u| u.name.clone()),
+    };
+    Ok(editor::join(
+        &serde_yaml::to_string(&header)?,
+        task.description.as_deref().unwrap_or_default(),
+    ))
+}
+
+fn note_document(note: &Note) -> Result<String> {
+    let header = NoteHeader {
+        title: note.name.clone(),
+        tags: note.tags.iter().map(|t| t.name.clone()).collect(),
+    };
+    Ok(editor::join(
+        &serde_yaml::to_string(&header)?,
+        note.description.as_deref().unwrap_or_default(),
+    ))
+}
+
+fn parse_header<T: for<'de> Deserialize<'de>>(text: &str) -> Result<(T, &str)> {
+    let (header, body) = editor::split(text)?;
+    let header = header.context("The front matter block is missing")?;
+    let header = serde_yaml::from_str(header).context("Invalid front matter")?;
+    Ok((header, body))
+}
+
+fn same_tags(names: &[String], tags: &[Tag]) -> bool {
+    let mut a: Vec<String> = names.iter().map(|n| n.trim().to_lowercase()).collect();
+    let mut b: Vec<String> = tags.iter().map(|t| t.name.to_lowercase()).collect();
+    a.sort();
+    b.sort();
+    a == b
+}
+
+fn changed_text(new: &str, old: Option<&str>) -> Option<String> {
+    (new.trim_end() != old.unwrap_or_default().trim_end()).then(|| new.trim_end().to_string())
+}
+
+/// The fields of the edited document that differ from the task
+fn task_changes(task: &Task, text: &str, names: &Names) -> Result<UpdateTaskRequest> {
+    let (header, body): (TaskHeader, &str) = parse_header(text)?;
+    let title = header.title.trim();
+    if title.is_empty() {
+        bail!("The title cannot be empty");
+    }
+
+    let mut request = UpdateTaskRequest {
+        name: (title != task.name).then(|| title.to_string()),
+        description: changed_text(body, task.description.as_deref()),
+        ..Default::default()
+    };
+    if !header.status.trim().eq_ignore_ascii_case(&task.status.name) {
+        request.status = Some(names.status(&header.status)?);
+    }
+    if header.priority != task.priority {
+        if !(1..=5).contains(&header.priority) {
+            bail!("Priority must be between 1 and 5");
+        }
+        request.priority = Some(header.priority);
+    }
+    let due = header
+        .due
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    if due.map(str::to_string) != task.due_date.map(format_date) {
+        let due = due.context("The API cannot clear a due date; set a new one instead")?;
+        request.due_date = Some(query::parse_date(due, names.today)?);
+    }
+    if !same_tags(&header.tags, &task.tags) {
+        request.tags = Some(names.tags(&header.tags)?);
+    }
+    let assignee = header
+        .assignee
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty());
+    let current = task.responsible_user.as_ref();
+    let unchanged = match (assignee, current) {
+        (Some(name), Some(user)) => {
+            matches_ref(name, user.id, &[&user.name, &user.full_name, &user.email])
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    if !unchanged {
+        let assignee = assignee.context("The API cannot unassign a task; name another member")?;
+        request.responsible_user = Some(names.user(assignee)?);
+    }
+    Ok(request)
+}
+
+fn note_changes(note: &Note, text: &str, tags: &[Tag]) -> Result<UpdateNoteRequest> {
+    let (header, body): (NoteHeader, &str) = parse_header(text)?;
+    let title = header.title.trim();
+    if title.is_empty() {
+        bail!("The title cannot be empty");
+    }
+    let names = Names {
+        statuses: Vec::new(),
+        tags: tags.to_vec(),
+        users: Vec::new(),
+        today: chrono::Local::now().date_naive(),
+    };
+    Ok(UpdateNoteRequest {
+        name: (title != note.name).then(|| title.to_string()),
+        description: changed_text(body, note.description.as_deref()),
+        parent: None,
+        tags: if same_tags(&header.tags, &note.tags) {
+            None
+        } else {
+            Some(names.tags(&header.tags)?)
+        },
+    })
+}
+
+fn is_empty<T: Serialize>(request: &T) -> Result<bool> {
+    Ok(serde_json::to_value(request)? == serde_json::json!({}))
+}
+
+/// Run `apply` on the edited text; if it fails, save the text so the edit is not lost
+async fn keep_on_error<T, F>(text: &str, apply: F) -> Result<T>
+where
+    F: std::future::Future<Output = Result<T>>,
+{
+    match apply.await {
+        Ok(value) => Ok(value),
+        Err(e) => match editor::keep(text) {
+            Ok(path) => Err(anyhow!(
+                "{:#}\nYour edit was saved to {}",
+                e,
+                path.display()
+            )),
+            Err(_) => Err(e),
+        },
+    }
+}
+
+fn conflict(kind: &str, id: u64) -> anyhow::Error {
+    anyhow!(
+        "{} {} was changed by someone else while you were editing; nothing was sent",
+        kind,
+        id
+    )
+}
+
+pub async fn edit_task(
+    client: &RepsonaClient,
+    project_id: u64,
+    task_id: u64,
+    json: bool,
+) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let exec_attrs = phase_attrs("task", "execute_operation");
+    let task = telemetry_span::with_span_async_result("load_task", &exec_attrs, || async {
+        client.get_task(project_id, task_id).await
+    })
+    .await?
+    .data
+    .task;
+    let original = task_document(&task)?;
+
+    let edit_attrs = phase_attrs("task", "interactive_edit");
+    let text = telemetry_span::with_span_result("interactive_edit", &edit_attrs, || {
+        editor::edit(&original)
+    })?;
+    if text == original {
+        print_success("No changes");
+        return Ok(());
+    }
+
+    let updated = keep_on_error(&text, async {
+        let (statuses, tags, users) = tokio::try_join!(
+            client.list_project_statuses(project_id),
+            client.list_tags(),
+            client.list_project_members(project_id),
+        )?;
+        let names = Names {
+            statuses: statuses.data.statuses,
+            tags: tags.data.tags,
+            users: users.data.users,
+            today: chrono::Local::now().date_naive(),
+        };
+        let request = task_changes(&task, &text, &names)?;
+        if is_empty(&request)? {
+            return Ok(None);
+        }
+        let latest = client.get_task(project_id, task_id).await?.data.task;
+        if latest.updated_at != task.updated_at {
+            return Err(conflict("Task", task_id));
+        }
+        let response = client.update_task(project_id, task_id, &request).await?;
+        Ok(Some(response.data.task))
+    })
+    .await?;
+
+    let render_attrs = phase_attrs("task", "render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match updated {
+        None => {
+            print_success("No changes");
+            Ok(())
+        }
+        Some(task) => {
+            print(&task, format)?;
+            print_success(&format!("Task '{}' updated", task.name));
+            Ok(())
+        }
+    })
+}
+
+pub async fn edit_note(
+    client: &RepsonaClient,
+    project_id: u64,
+    note_id: u64,
+    json: bool,
+) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let exec_attrs = phase_attrs("note", "execute_operation");
+    let note = telemetry_span::with_span_async_result("load_note", &exec_attrs, || async {
+        client.get_note(project_id, note_id).await
+    })
+    .await?
+    .data
+    .note;
+    let original = note_document(&note)?;
+
+    let edit_attrs = phase_attrs("note", "interactive_edit");
+    let text = telemetry_span::with_span_result("interactive_edit", &edit_attrs, || {
+        editor::edit(&original)
+    })?;
+    if text == original {
+        print_success("No changes");
+        return Ok(());
+    }
+
+    let updated = keep_on_error(&text, async {
+        let tags = client.list_tags().await?.data.tags;
+        let request = note_changes(&note, &text, &tags)?;
+        if is_empty(&request)? {
+            return Ok(None);
+        }
+        let latest = client.get_note(project_id, note_id).await?.data.note;
+        if latest.updated_at != note.updated_at {
+            return Err(conflict("Note", note_id));
+        }
+        let response = client.update_note(project_id, note_id, &request).await?;
+        Ok(Some(response.data.note))
+    })
+    .await?;
+
+    let render_attrs = phase_attrs("note", "render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match updated {
+        None => {
+            print_success("No changes");
+            Ok(())
+        }
+        Some(note) => {
+            print(&note, format)?;
+            print_success(&format!("Note '{}' updated", note.name));
+            Ok(())
+        }
+    })
+}
+
+/// Edit a task comment. Comments are only listed per task, so the task is needed to load it.
+pub async fn edit_task_comment(
+    client: &RepsonaClient,
+    project_id: u64,
+    task_id: u64,
+    comment_id: u64,
+    json: bool,
+) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let load = || async {
+        client
+            .list_task_comments(project_id, task_id)
+            .await?
+            .data
+            .task_comments
+            .into_iter()
+            .find(|c| c.id == comment_id)
+            .with_context(|| format!("Task {} has no comment {}", task_id, comment_id))
+    };
+    let exec_attrs = phase_attrs("task", "execute_operation");
+    let comment = telemetry_span::with_span_async_result("load_comment", &exec_attrs, load).await?;
+
+    let edit_attrs = phase_attrs("task", "interactive_edit");
+    let text = telemetry_span::with_span_result("interactive_edit", &edit_attrs, || {
+        editor::edit(&comment.comment)
+    })?;
+    let Some(body) = changed_text(&text, Some(&comment.comment)) else {
+        print_success("No changes");
+        return Ok(());
+    };
+    if body.trim().is_empty() {
+        bail!("The comment is empty; use comment-delete to remove it");
+    }
+
+    let updated = keep_on_error(&text, async {
+        // Comments carry no update time, so compare the text itself
+        if load().await?.comment != comment.comment {
+            return Err(conflict("Comment", comment_id));
+        }
+        client
+            .update_task_comment(project_id, comment_id, body)
+            .await
+            .map(|r| r.data.task_comment)
+    })
+    .await?;
+
+    let render_attrs = phase_attrs("task", "render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || {
+        print(&updated, format)?;
+        print_success("Comment updated");
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn user(id: u64, name: &str) -> User {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "email": format!("{}@example.com", name), "name": name,
+            "fullName": name, "role": "member", "billingStatus": "active",
+            "createdAt": 0, "updatedAt": 0
+        }))
+        .unwrap()
+    }
+
+    fn status(id: u64, name: &str) -> Status {
+        Status {
+            id,
+            name: name.to_string(),
+            is_closed: false,
+            color: None,
+        }
+    }
+
+    fn tag(id: u64, name: &str) -> Tag {
+        Tag {
+            id,
+            name: name.to_string(),
+            color: "red".to_string(),
+        }
+    }
+
+    fn task() -> Task {
+        Task {
+            description: Some("Steps\n".to_string()),
+            status: status(1, "Open"),
+            priority: 3,
+            responsible_user: Some(user(10, "alice")),
+            tags: vec![tag(100, "bug")],
+            ..test_support::task(7)
+                .named("Fix login")
+                .in_project(1, "web")
+        }
+    }
+
+    fn names() -> Names {
+        Names {
+            statuses: vec![status(1, "Open"), status(2, "Review")],
+            tags: vec![tag(100, "bug"), tag(101, "ui")],
+            users: vec![user(10, "alice"), user(11, "bob")],
+            today: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_task_changes_only_include_edited_fields() {
+        let task = task();
+        let document = task_document(&task).unwrap();
+        let unchanged = task_changes(&task, &document, &names()).unwrap();
+        assert!(is_empty(&unchanged).unwrap());
+
+        let edited = document
+            .replace("status: Open", "status: review")
+            .replace("- bug", "- bug\n- UI")
+            .replace("assignee: alice", "assignee: bob")
+            .replace("due: null", "due: 2024-06-03")
+            .replace("Steps\n", "Steps\n\n1. Open the page\n");
+        let request = task_changes(&task, &edited, &names()).unwrap();
+        assert_eq!(request.name, None);
+        assert_eq!(request.status, Some(2));
+        assert_eq!(request.priority, None);
+        assert_eq!(request.tags, Some(vec![100, 101]));
+        assert_eq!(request.responsible_user, Some(11));
+        assert_eq!(
+            request.due_date,
+            Some(query::start_of_day(
+                NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()
+            ))
+        );
+        assert_eq!(
+            request.description.as_deref(),
+            Some("Steps\n\n1. Open the page")
+        );
+    }
+
+    #[test]
+    fn test_task_changes_reject_unknown_names_and_clearing() {
+        let task = task();
+        let document = task_document(&task).unwrap();
+        let cases = [
+            ("status: Open", "status: Gone"),
+            ("- bug", "- nope"),
+            ("assignee: alice", "assignee: null"),
+            ("priority: 3", "priority: 9"),
+            ("title: Fix login", "title: ''"),
+            ("title: Fix login", "title: Fix login\nowner: bob"),
+        ];
+        for (from, to) in cases {
+            let edited = document.replace(from, to);
+            assert!(task_changes(&task, &edited, &names()).is_err(), "{}", to);
+        }
+    }
+}
@@ -0,0 +1,572 @@
+use crate::api::{
+    endpoints::{file::AttachModel, project::CreateProjectRequest, task::CreateTaskRequest},
+    types::*,
+    RepsonaClient,
+};
+use crate::cli::ImportSource;
+use crate::commands::board::matches_ref;
+use crate::commands::project_archive::attributed;
+use crate::importers::{self, Export, ImportedAttachment, ImportedTask};
+use crate::output::{confirm, print, print_success, OutputFormat};
+use crate::telemetry_span;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "import".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct ImportOptions {
+    pub source: ImportSource,
+    pub file: PathBuf,
+    /// Existing project to import into; a new one is created otherwise
+    pub project: Option<u64>,
+    /// Name for the new project (default: the board or project name in the export)
+    pub name: Option<String>,
+    /// Directory holding downloaded attachments (default: next to the export file)
+    pub attachments: Option<PathBuf>,
+    /// Import without asking (`--yes`)
+    pub yes: bool,
+}
+
+/// What the export file contains, before anything is sent
+#[derive(Debug, Default, Serialize)]
+pub struct ExportSummary {
+    pub tasks: usize,
+    pub subtasks: usize,
+    pub comments: usize,
+    pub attachments: usize,
+    /// Attachments with no local copy, which are skipped
+    pub missing_attachments: Vec<String>,
+    /// Source status, list or section → number of tasks
+    pub statuses: BTreeMap<String, usize>,
+    /// Source label → number of tasks
+    pub labels: BTreeMap<String, usize>,
+    pub assignees: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub project_id: u64,
+    pub tasks: usize,
+    pub comments: usize,
+    pub attachments: usize,
+    /// Source statuses with no status of the same name; those tasks keep the default
+    pub unmatched_statuses: Vec<String>,
+    /// Labels with no tag of the same name (the API cannot create tags); kept in the description
+    pub missing_tags: Vec<String>,
+    pub unmatched_assignees: Vec<String>,
+    pub missing_attachments: Vec<String>,
+}
+
+/// Names in the target resolved to IDs
+struct Target {
+    statuses: Vec<Status>,
+    tags: Vec<Tag>,
+    users: Vec<User>,
+}
+
+impl Target {
+    fn status(&self, name: &str) -> Option<u64> {
+        self.statuses
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+            .map(|s| s.id)
+    }
+
+    /// A closed status of the project, or the generic done status
+    fn done(&self) -> u64 {
+        self.statuses
+            .iter()
+            .find(|s| s.is_closed)
+            .map(|s| s.id)
+            .unwrap_or(TaskStatus::Done.id())
+    }
+
+    fn tag(&self, name: &str) -> Option<u64> {
+        self.tags
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .map(|t| t.id)
+    }
+
+    fn user(&self, name: &str) -> Option<u64> {
+        self.users
+            .iter()
+            .find(|u| matches_ref(name, u.id, &[&u.name, &u.full_name, &u.email]))
+            .map(|u| u.id)
+    }
+}
+
+pub async fn handle(client: &RepsonaClient, options: ImportOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let validate_attrs = phase_attrs("validate_input");
+    let (export, summary) =
+        telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+            let export = importers::read(options.source, &options.file)?;
+            let summary = summarize(&export, &attachment_dir(&options));
+            Ok::<_, anyhow::Error>((export, summary))
+        })?;
+
+    let render_attrs = phase_attrs("render_output");
+    if !json {
+        telemetry_span::with_span("render_output", &render_attrs, || {
+            print_summary(&options, &export, &summary)
+        });
+    }
+    if client.is_dry_run() {
+        if json {
+            print(&summary, format)?;
+        }
+        return Ok(());
+    }
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let (tags, users) =
+        telemetry_span::with_span_async_result("load_target", &exec_attrs, || async {
+            tokio::try_join!(client.list_tags(), client.list_users())
+        })
+        .await?;
+    let mut target = Target {
+        statuses: Vec::new(),
+        tags: tags.data.tags,
+        users: users.data.users,
+    };
+    if let Some(project_id) = options.project {
+        target.statuses = client
+            .list_project_statuses(project_id)
+            .await?
+            .data
+            .statuses;
+    }
+    let missing_tags: Vec<String> = summary
+        .labels
+        .keys()
+        .filter(|l| target.tag(l).is_none())
+        .cloned()
+        .collect();
+    let unmatched_assignees: Vec<String> = summary
+        .assignees
+        .iter()
+        .filter(|a| target.user(a).is_none())
+        .cloned()
+        .collect();
+    if !json {
+        print_matches(
+            &target,
+            options.project,
+            &summary,
+            &missing_tags,
+            &unmatched_assignees,
+        );
+    }
+
+    if !options.yes {
+        if json {
+            bail!("Pass --yes to import with --json");
+        }
+        let prompt = format!(
+            "Import {} task(s)? [y/N]: ",
+            summary.tasks + summary.subtasks
+        );
+        let proceed =
+            telemetry_span::with_span_result("interactive_confirm", &validate_attrs, || {
+                confirm(&prompt)
+            })?;
+        if !proceed {
+            eprintln!("{}", "Cancelled".yellow());
+            return Ok(());
+        }
+    }
+
+    let project_id = match options.project {
+        Some(id) => id,
+        None => {
+            let name = options
+                .name
+                .clone()
+                .or_else(|| export.name.clone())
+                .unwrap_or_else(|| "Imported".to_string());
+            let request = CreateProjectRequest {
+                name,
+                ..Default::default()
+            };
+            let project = client.create_project(&request).await?.data.project;
+            target.statuses = client
+                .list_project_statuses(project.id)
+                .await?
+                .data
+                .statuses;
+            project.id
+        }
+    };
+
+    let total = summary.tasks + summary.subtasks;
+    let dir = attachment_dir(&options);
+    let mut report = ImportReport {
+        project_id,
+        tasks: 0,
+        comments: 0,
+        attachments: 0,
+        unmatched_statuses: summary
+            .statuses
+            .keys()
+            .filter(|s| target.status(s).is_none())
+            .cloned()
+            .collect(),
+        missing_tags,
+        unmatched_assignees,
+        missing_attachments: summary.missing_attachments.clone(),
+    };
+
+    // Depth first, so each parent exists before its subtasks
+    let mut stack: Vec<(&ImportedTask, Option<u64>)> =
+        export.tasks.iter().rev().map(|t| (t, None)).collect();
+    while let Some((task, parent)) = stack.pop() {
+        let id = telemetry_span::with_span_async_result("import_task", &exec_attrs, || {
+            import_task(client, project_id, task, parent, &target, &dir, &mut report)
+        })
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to import '{}' ({} of {} task(s) imported into project {})",
+                task.title, report.tasks, total, project_id
+            )
+        })?;
+        stack.extend(task.subtasks.iter().rev().map(|t| (t, Some(id))));
+    }
+
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&report, format),
+        OutputFormat::Human => {
+            print_success(&format!(
+                "Imported {} task(s), {} comment(s) and {} attachment(s) into project {}",
+                report.tasks, report.comments, report.attachments, project_id
+            ));
+            Ok(())
+        }
+    })
+}
+
+fn attachment_dir(options: &ImportOptions) -> PathBuf {
+    options.attachments.clone().unwrap_or_else(|| {
+        options
+            .file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    })
+}
+
+/// The file name of an export path, when it is a relative path without `..`.
+///
+/// Exports are untrusted: an absolute path or `..` must not reach files outside the
+/// attachments directory.
+fn plain_file_name(value: &str) -> Option<&OsStr> {
+    let path = Path::new(value);
+    if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    path.file_name()
+}
+
+/// The local copy of an attachment: `DIR/<name>` or `DIR/attachments/<name>`, also trying the
+/// file name of the recorded location when it is a path rather than a URL
+fn local_attachment(dir: &Path, attachment: &ImportedAttachment) -> Option<PathBuf> {
+    let mut names = vec![plain_file_name(&attachment.name)];
+    if !attachment.location.contains("://") {
+        names.push(plain_file_name(&attachment.location));
+    }
+    let root = dir.canonicalize().ok()?;
+    names
+        .into_iter()
+        .flatten()
+        .flat_map(|name| [dir.join(name), dir.join("attachments").join(name)])
+        .filter_map(|p| p.canonicalize().ok())
+        // A symlink inside the directory may still point elsewhere
+        .find(|p| p.starts_with(&root) && p.is_file())
+}
+
+fn summarize(export: &Export, dir: &Path) -> ExportSummary {
+    let mut summary = ExportSummary {
+        tasks: export.tasks.len(),
+        ..Default::default()
+    };
+    let all = export.all_tasks();
+    summary.subtasks = all.len() - summary.tasks;
+    for task in all {
+        if let Some(status) = &task.status {
+            *summary.statuses.entry(status.clone()).or_default() += 1;
+        }
+        for label in &task.labels {
+            *summary.labels.entry(label.clone()).or_default() += 1;
+        }
+        if let Some(assignee) = &task.assignee {
+            if !summary.assignees.contains(assignee) {
+                summary.assignees.push(assignee.clone());
+            }
+        }
+        summary.comments += task.comments.len();
+        for attachment in &task.attachments {
+            summary.attachments += 1;
+            if local_attachment(dir, attachment).is_none() {
+                summary.missing_attachments.push(attachment.name.clone());
+            }
+        }
+    }
+    summary
+}
+
+fn print_summary(options: &ImportOptions, export: &Export, summary: &ExportSummary) {
+    let into = match options.project {
+        Some(id) => format!("project {}", id),
+        None => format!(
+            "new project '{}'",
+            options
+                .name
+                .as_deref()
+                .or(export.name.as_deref())
+                .unwrap_or("Imported")
+        ),
+    };
+    eprintln!(
+        "\n{}",
+        format!("Import {} into {}", options.file.display(), into).bold()
+    );
+    eprintln!(
+        "  {} task(s), {} subtask(s), {} comment(s), {} attachment(s)",
+        summary.tasks, summary.subtasks, summary.comments, summary.attachments
+    );
+    let counts = |map: &BTreeMap<String, usize>| {
+        map.iter()
+            .map(|(name, n)| format!("{} ({})", name, n))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if !summary.statuses.is_empty() {
+        eprintln!("  Statuses: {}", counts(&summary.statuses));
+    }
+    if !summary.labels.is_empty() {
+        eprintln!("  Labels: {}", counts(&summary.labels));
+    }
+    if !summary.missing_attachments.is_empty() {
+        eprintln!(
+            "  {} {} attachment(s) have no local copy and will be skipped: {}",
+            "!".yellow(),
+            summary.missing_attachments.len(),
+            summary.missing_attachments.join(", ")
+        );
+    }
+}
+
+fn print_matches(
+    target: &Target,
+    project: Option<u64>,
+    summary: &ExportSummary,
+    missing_tags: &[String],
+    unmatched_assignees: &[String],
+) {
+    if project.is_some() {
+        for status in summary.statuses.keys() {
+            if target.status(status).is_none() {
+                eprintln!(
+                    "  {} No status named '{}'; those tasks get the default status",
+                    "!".yellow(),
+                    status
+                );
+            }
+        }
+    }
+    if !missing_tags.is_empty() {
+        eprintln!(
+            "  {} No tag for label(s) {}; the API cannot create tags, so they are noted in the description",
+            "!".yellow(),
+            missing_tags.join(", ")
+        );
+    }
+    if !unmatched_assignees.is_empty() {
+        eprintln!(
+            "  {} No user matches {}; those tasks are left unassigned",
+            "!".yellow(),
+            unmatched_assignees.join(", ")
+        );
+    }
+    eprintln!();
+}
+
+/// Keep labels that match no tag in the description, since tags cannot be created
+fn with_labels(description: Option<&str>, labels: &[&str]) -> Option<String> {
+    if labels.is_empty() {
+        return description.map(str::to_string);
+    }
+    let line = format!("Labels: {}", labels.join(", "));
+    Some(match description.filter(|d| !d.trim().is_empty()) {
+        Some(description) => format!("{}\n\n{}", description.trim_end(), line),
+        None => line,
+    })
+}
+
+async fn import_task(
+    client: &RepsonaClient,
+    project_id: u64,
+    task: &ImportedTask,
+    parent: Option<u64>,
+    target: &Target,
+    dir: &Path,
+    report: &mut ImportReport,
+) -> Result<u64> {
+    let tags: Vec<u64> = task.labels.iter().filter_map(|l| target.tag(l)).collect();
+    let unmatched: Vec<&str> = task
+        .labels
+        .iter()
+        .filter(|l| target.tag(l).is_none())
+        .map(String::as_str)
+        .collect();
+    let status = if task.closed {
+        Some(target.done())
+    } else {
+        task.status.as_deref().and_then(|s| target.status(s))
+    };
+    let request = CreateTaskRequest {
+        name: task.title.clone(),
+        description: with_labels(task.description.as_deref(), &unmatched),
+        status,
+        priority: task.priority,
+        due_date: task.due,
+        responsible_user: task.assignee.as_deref().and_then(|a| target.user(a)),
+        parent,
+        tags: (!tags.is_empty()).then_some(tags),
+        add_to_bottom: Some(true),
+        ..Default::default()
+    };
+    let id = client.create_task(project_id, &request).await?.data.task.id;
+    report.tasks += 1;
+
+    for comment in &task.comments {
+        let body = attributed(&comment.author, comment.created_at, &comment.body);
+        client.add_task_comment(project_id, id, body, None).await?;
+        report.comments += 1;
+    }
+    for attachment in &task.attachments {
+        let Some(path) = local_attachment(dir, attachment) else {
+            continue;
+        };
+        let file_id = client
+            .upload_file(project_id, &path)
+            .await?
+            .data
+            .files
+            .first()
+            .map(|f| f.id)
+            .with_context(|| format!("Upload of {} returned no file", path.display()))?;
+        client
+            .attach_file(project_id, AttachModel::Task, id, file_id)
+            .await?;
+        report.attachments += 1;
+    }
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::ImportedComment;
+
+    #[test]
+    fn test_summarize_counts_nested_tasks_and_local_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("spec.pdf"), b"pdf").unwrap();
+        let attachment = |name: &str| ImportedAttachment {
+            name: name.to_string(),
+            location: format!("https://example.com/{}", name),
+        };
+        let export = Export {
+            name: Some("Board".to_string()),
+            tasks: vec![ImportedTask {
+                title: "Epic".to_string(),
+                status: Some("Doing".to_string()),
+                labels: vec!["bug".to_string()],
+                assignee: Some("alice".to_string()),
+                comments: vec![ImportedComment {
+                    author: "alice".to_string(),
+                    created_at: None,
+                    body: "hi".to_string(),
+                }],
+                attachments: vec![attachment("spec.pdf"), attachment("gone.png")],
+                subtasks: vec![ImportedTask {
+                    title: "Step".to_string(),
+                    status: Some("Doing".to_string()),
+                    assignee: Some("alice".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let summary = summarize(&export, dir.path());
+        assert_eq!((summary.tasks, summary.subtasks), (1, 1));
+        assert_eq!(summary.statuses["Doing"], 2);
+        assert_eq!(summary.labels["bug"], 1);
+        assert_eq!(summary.assignees, vec!["alice"]);
+        assert_eq!(summary.comments, 1);
+        assert_eq!(summary.attachments, 2);
+        assert_eq!(summary.missing_attachments, vec!["gone.png"]);
+    }
+
+    #[test]
+    fn test_with_labels_keeps_unmatched_labels() {
+        assert_eq!(with_labels(Some("Body"), &[]), Some("Body".to_string()));
+        assert_eq!(
+            with_labels(Some("Body\n"), &["bug", "ui"]),
+            Some("Body\n\nLabels: bug, ui".to_string())
+        );
+        assert_eq!(with_labels(None, &["bug"]), Some("Labels: bug".to_string()));
+    }
+
+    #[test]
+    fn test_local_attachment_stays_inside_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("files");
+        std::fs::create_dir_all(dir.join("attachments")).unwrap();
+        std::fs::write(root.path().join("secret"), b"key").unwrap();
+        std::fs::write(dir.join("attachments").join("ok.txt"), b"ok").unwrap();
+        let attachment = |name: &str, location: &str| ImportedAttachment {
+            name: name.to_string(),
+            location: location.to_string(),
+        };
+        let secret = root.path().join("secret").display().to_string();
+
+        assert_eq!(local_attachment(&dir, &attachment("../secret", "")), None);
+        assert_eq!(local_attachment(&dir, &attachment(&secret, "")), None);
+        assert_eq!(local_attachment(&dir, &attachment("x", &secret)), None);
+        assert_eq!(local_attachment(&dir, &attachment("x", "../secret")), None);
+        assert_eq!(
+            local_attachment(&dir, &attachment("ok.txt", "")),
+            Some(
+                dir.join("attachments")
+                    .join("ok.txt")
+                    .canonicalize()
+                    .unwrap()
+            )
+        );
+        // A relative location contributes only its file name
+        assert!(local_attachment(&dir, &attachment("x", "export/ok.txt")).is_some());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.path().join("secret"), dir.join("link")).unwrap();
+            assert_eq!(local_attachment(&dir, &attachment("link", "")), None);
+        }
+    }
+}
@@ -0,0 +1,616 @@
+use crate::api::{types::*, RepsonaClient};
+use crate::cli::MilestoneCommands;
+use crate::commands::board::matches_ref;
+use crate::commands::calendar::local_date;
+use crate::commands::report_flow::{transitions, Transition};
+use crate::commands::task_query::find_tasks;
+use crate::markdown::RenderOptions;
+use crate::output::{print, print_success, OutputFormat};
+use crate::query::{parse_date, start_of_day, TaskQuery};
+use crate::telemetry_span;
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration, Local, NaiveDate};
+use colored::Colorize;
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+const CONCURRENCY: usize = 4;
+const CHART_HEIGHT: usize = 15;
+const SVG_WIDTH: f64 = 720.0;
+const SVG_HEIGHT: f64 = 360.0;
+const SVG_PADDING: f64 = 48.0;
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "milestone".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct BurndownOptions {
+    pub project_id: u64,
+    pub milestone: String,
+    pub points: bool,
+    pub burnup: bool,
+    pub from: Option<String>,
+    pub svg: Option<PathBuf>,
+    pub csv: Option<PathBuf>,
+}
+
+/// Work at the end of one day; `ideal` is remaining work (or, for burnup, completed work) on
+/// a straight line to the due date
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BurnDay {
+    pub date: String,
+    pub scope: f64,
+    pub done: f64,
+    pub remaining: f64,
+    pub ideal: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Burndown {
+    pub project_id: u64,
+    pub milestone: Milestone,
+    pub unit: &'static str,
+    pub days: Vec<BurnDay>,
+}
+
+/// When a task existed and whether it was open, as (time, open) changes in time order
+type Timeline = Vec<(u64, bool)>;
+
+pub async fn handle(client: &RepsonaClient, command: MilestoneCommands, json: bool) -> Result<()> {
+    match command {
+        MilestoneCommands::Burndown {
+            project_id,
+            milestone,
+            points,
+            burnup,
+            from,
+            svg,
+            csv,
+        } => {
+            let options = BurndownOptions {
+                project_id,
+                milestone,
+                points,
+                burnup,
+                from,
+                svg,
+                csv,
+            };
+            handle_burndown(client, options, json).await
+        }
+    }
+}
+
+async fn handle_burndown(
+    client: &RepsonaClient,
+    options: BurndownOptions,
+    json: bool,
+) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let today = Local::now().date_naive();
+    let from = options
+        .from
+        .as_deref()
+        .map(|v| parse_date(v, today).map(local_date))
+        .transpose()?;
+    let query = TaskQuery {
+        projects: vec![options.project_id],
+        include_closed: true,
+        ..Default::default()
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let (milestone, statuses, tasks, histories) =
+        telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || async {
+            let (milestones, statuses, tasks) = tokio::join!(
+                client.list_project_milestones(options.project_id),
+                client.list_project_statuses(options.project_id),
+                find_tasks(client, &query, CONCURRENCY)
+            );
+            let milestone = milestones?
+                .data
+                .milestones
+                .into_iter()
+                .find(|m| matches_ref(&options.milestone, m.id, &[&m.name]))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Milestone '{}' not found in project {}",
+                        options.milestone,
+                        options.project_id
+                    )
+                })?;
+            let tasks: Vec<Task> = tasks?
+                .into_iter()
+                .filter(|t| t.milestone.as_ref().is_some_and(|m| m.id == milestone.id))
+                .collect();
+            let histories: Vec<(u64, Result<Vec<History>>)> = stream::iter(&tasks)
+                .map(|task| async move {
+                    let history = client
+                        .get_task_history(options.project_id, task.id)
+                        .await
+                        .map(|r| r.data.history);
+                    (task.id, history)
+                })
+                .buffer_unordered(CONCURRENCY)
+                .collect()
+                .await;
+            Ok::<_, anyhow::Error>((milestone, statuses?.data.statuses, tasks, histories))
+        })
+        .await?;
+
+    let mut failed = 0;
+    let histories: HashMap<u64, Vec<Transition>> = histories
+        .into_iter()
+        .filter_map(|(id, result)| match result {
+            Ok(history) => Some((id, transitions(&history))),
+            Err(_) => {
+                failed += 1;
+                None
+            }
+        })
+        .collect();
+    if failed > 0 {
+        eprintln!(
+            "{}",
+            format!(
+                "Could not load the history of {} task(s); counting closed ones as closed at \
+                 their last update",
+                failed
+            )
+            .yellow()
+        );
+    }
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let burndown = telemetry_span::with_span("prepare_request", &prepare_attrs, || {
+        let closed: HashSet<&str> = statuses
+            .iter()
+            .filter(|s| s.is_closed)
+            .map(|s| s.name.as_str())
+            .collect();
+        let work: Vec<(f64, Timeline)> = tasks
+            .iter()
+            .map(|task| {
+                let weight = if options.points {
+                    task.priority.max(1) as f64
+                } else {
+                    1.0
+                };
+                let changes = histories.get(&task.id).map_or(&[][..], |c| &c[..]);
+                (weight, timeline(task, changes, &closed))
+            })
+            .collect();
+        let due = milestone.due_date.map(local_date);
+        Burndown {
+            project_id: options.project_id,
+            days: replay(&work, from, due, today, options.burnup),
+            unit: if options.points { "points" } else { "tasks" },
+            milestone,
+        }
+    });
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || {
+        if let Some(path) = &options.svg {
+            std::fs::write(path, to_svg(&burndown, options.burnup))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            print_success(&format!("Chart written to {}", path.display()));
+        }
+        if let Some(path) = &options.csv {
+            std::fs::write(path, to_csv(&burndown)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            print_success(&format!("Series written to {}", path.display()));
+        }
+        match format {
+            OutputFormat::Json => print(&burndown, format),
+            OutputFormat::Human => {
+                let width = RenderOptions::for_stdout().width;
+                print!("{}", render(&burndown, options.burnup, width));
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Open/closed changes of a task from its status history.
+///
+/// A closed task without a recorded close (e.g. history that could not be loaded) is taken to
+/// have closed at its last update.
+fn timeline(task: &Task, changes: &[Transition], closed: &HashSet<&str>) -> Timeline {
+    let initially_open = match changes.first() {
+        Some(first) => !first.from.as_deref().is_some_and(|s| closed.contains(s)),
+        None => true,
+    };
+    let mut events = vec![(task.created_at, initially_open)];
+    let mut open = initially_open;
+    for change in changes {
+        let now_open = !closed.contains(change.to.as_str());
+        if now_open != open {
+            events.push((change.at.max(task.created_at), now_open));
+            open = now_open;
+        }
+    }
+    if open && task.status.is_closed {
+        events.push((task.updated_at.max(task.created_at), false));
+    }
+    events
+}
+
+/// Whether the task existed and was open at `at`
+fn state_at(events: &Timeline, at: u64) -> Option<bool> {
+    events
+        .iter()
+        .take_while(|(t, _)| *t < at)
+        .last()
+        .map(|(_, open)| *open)
+}
+
+/// Daily scope and completed work from the first day to today, extended with the ideal
+/// line up to the due date when it lies ahead
+fn replay(
+    work: &[(f64, Timeline)],
+    from: Option<NaiveDate>,
+    due: Option<NaiveDate>,
+    today: NaiveDate,
+    burnup: bool,
+) -> Vec<BurnDay> {
+    let first_created = work
+        .iter()
+        .filter_map(|(_, events)| events.first().map(|(t, _)| local_date(*t)))
+        .min()
+        .unwrap_or(today);
+    let first = from.unwrap_or(first_created).min(today);
+    let last = due.map_or(today, |d| d.max(today));
+
+    let mut days = Vec::new();
+    let mut date = first;
+    while date <= last {
+        let end = start_of_day(date + Duration::days(1));
+        let (mut scope, mut done) = (0.0, 0.0);
+        if date <= today {
+            for (weight, events) in work {
+                match state_at(events, end) {
+                    Some(true) => scope += weight,
+                    Some(false) => {
+                        scope += weight;
+                        done += weight;
+                    }
+                    None => {}
+                }
+            }
+        }
+        days.push((date, scope, done));
+        date += Duration::days(1);
+    }
+
+    // The ideal line runs from the scope on the first day to nothing left on the due date
+    let start_scope = days.first().map_or(0.0, |d| d.1);
+    let span = due.map(|d| (d - first).num_days());
+    days.into_iter()
+        .map(|(date, scope, done)| {
+            let ideal = span
+                .filter(|s| *s > 0 && date <= due.unwrap_or(date))
+                .map(|s| {
+                    let left = start_scope * (1.0 - (date - first).num_days() as f64 / s as f64);
+                    if burnup {
+                        start_scope - left
+                    } else {
+                        left
+                    }
+                });
+            let past = date <= today;
+            BurnDay {
+                date: date.to_string(),
+                scope: if past { scope } else { f64::NAN },
+                done: if past { done } else { f64::NAN },
+                remaining: if past { scope - done } else { f64::NAN },
+                ideal,
+            }
+        })
+        .collect()
+}
+
+fn actual(day: &BurnDay, burnup: bool) -> f64 {
+    if burnup {
+        day.done
+    } else {
+        day.remaining
+    }
+}
+
+/// Terminal chart: ● actual, · ideal, and for burnup ○ total scope
+fn render(burndown: &Burndown, burnup: bool, width: usize) -> String {
+    let mut out = format!(
+        "{} {} ({})\n",
+        if burnup { "Burnup:" } else { "Burndown:" }.bold(),
+        burndown.milestone.name,
+        burndown.unit
+    );
+    let days = &burndown.days;
+    if days.is_empty() {
+        out.push_str("No days to chart.\n");
+        return out;
+    }
+    let max = days
+        .iter()
+        .flat_map(|d| [d.scope, d.ideal.unwrap_or(0.0)])
+        .filter(|v| v.is_finite())
+        .fold(1.0_f64, f64::max);
+    let label_width = format!("{}", max.ceil()).len();
+    let columns = days
+        .len()
+        .min(width.saturating_sub(label_width + 3).max(10));
+    // Several days per column for long milestones: show the last day of each column
+    let day_at = |column: usize| &days[((column + 1) * days.len()).div_ceil(columns) - 1];
+    let row_of = |value: f64| ((value / max) * (CHART_HEIGHT - 1) as f64).round() as usize;
+
+    // One cell per row for each column, bottom row first
+    let grid: Vec<[char; CHART_HEIGHT]> = (0..columns)
+        .map(|column| {
+            let day = day_at(column);
+            let mut cells = [' '; CHART_HEIGHT];
+            if let Some(ideal) = day.ideal {
+                cells[row_of(ideal)] = '·';
+            }
+            if burnup && day.scope.is_finite() {
+                cells[row_of(day.scope)] = '○';
+            }
+            let value = actual(day, burnup);
+            if value.is_finite() {
+                cells[row_of(value)] = '●';
+            }
+            cells
+        })
+        .collect();
+    for i in (0..CHART_HEIGHT).rev() {
+        let label = if i == CHART_HEIGHT - 1 {
+            format!("{}", max.ceil())
+        } else if i == 0 {
+            "0".to_string()
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            out,
+            "{:>w$} ┤{}",
+            label,
+            grid.iter().map(|cells| cells[i]).collect::<String>(),
+            w = label_width
+        );
+    }
+    let _ = writeln!(out, "{:>w$} └{}", "", "─".repeat(columns), w = label_width);
+    let first = &days[0].date;
+    let last = &days[days.len() - 1].date;
+    let gap = columns.saturating_sub(first.len() + last.len()).max(1);
+    let _ = writeln!(
+        out,
+        "{:>w$}  {}{}{}",
+        "",
+        first,
+        " ".repeat(gap),
+        last,
+        w = label_width
+    );
+
+    let today = days.iter().rev().find(|d| d.scope.is_finite());
+    if let Some(day) = today {
+        let _ = writeln!(
+            out,
+            "\n{}: {} of {} {} done, {} remaining",
+            day.date, day.done, day.scope, burndown.unit, day.remaining
+        );
+    }
+    let legend = if burnup {
+        "● done  ○ scope  · ideal"
+    } else {
+        "● remaining  · ideal"
+    };
+    let _ = writeln!(out, "{}", legend.dimmed());
+    if burndown.milestone.due_date.is_none() {
+        let _ = writeln!(
+            out,
+            "{}",
+            "The milestone has no due date, so there is no ideal line".yellow()
+        );
+    }
+    out
+}
+
+fn polyline(points: &[(usize, f64)], len: usize, max: f64, style: &str) -> String {
+    if points.is_empty() {
+        return String::new();
+    }
+    let x = |i: usize| {
+        SVG_PADDING + (SVG_WIDTH - 2.0 * SVG_PADDING) * i as f64 / (len.max(2) - 1) as f64
+    };
+    let y = |v: f64| SVG_HEIGHT - SVG_PADDING - (SVG_HEIGHT - 2.0 * SVG_PADDING) * v / max;
+    let coords: Vec<String> = points
+        .iter()
+        .map(|(i, v)| format!("{:.1},{:.1}", x(*i), y(*v)))
+        .collect();
+    format!(
+        "<polyline fill=\"none\" {} points=\"{}\"/>\n",
+        style,
+        coords.join(" ")
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_svg(burndown: &Burndown, burnup: bool) -> String {
+    let days = &burndown.days;
+    let max = days
+        .iter()
+        .flat_map(|d| [d.scope, d.ideal.unwrap_or(0.0)])
+        .filter(|v| v.is_finite())
+        .fold(1.0_f64, f64::max);
+    let series = |f: &dyn Fn(&BurnDay) -> Option<f64>| -> Vec<(usize, f64)> {
+        days.iter()
+            .enumerate()
+            .filter_map(|(i, d)| f(d).filter(|v| v.is_finite()).map(|v| (i, v)))
+            .collect()
+    };
+    let bottom = SVG_HEIGHT - SVG_PADDING;
+    let right = SVG_WIDTH - SVG_PADDING;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+         font-family=\"sans-serif\" font-size=\"12\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n\
+         <text x=\"{p}\" y=\"24\" font-size=\"14\" font-weight=\"bold\">{title}</text>\n\
+         <line x1=\"{p}\" y1=\"{p}\" x2=\"{p}\" y2=\"{b}\" stroke=\"#999\"/>\n\
+         <line x1=\"{p}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"#999\"/>\n\
+         <text x=\"{lx}\" y=\"{ty}\" text-anchor=\"end\">{max}</text>\n\
+         <text x=\"{lx}\" y=\"{b}\" text-anchor=\"end\">0</text>\n",
+        w = SVG_WIDTH,
+        h = SVG_HEIGHT,
+        p = SVG_PADDING,
+        b = bottom,
+        r = right,
+        lx = SVG_PADDING - 6.0,
+        ty = SVG_PADDING + 4.0,
+        max = max.ceil(),
+        title = escape(&format!(
+            "{} {} ({})",
+            if burnup { "Burnup" } else { "Burndown" },
+            burndown.milestone.name,
+            burndown.unit
+        )),
+    );
+    if let (Some(first), Some(last)) = (days.first(), days.last()) {
+        let _ = write!(
+            out,
+            "<text x=\"{}\" y=\"{}\">{}</text>\n<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            SVG_PADDING,
+            bottom + 18.0,
+            first.date,
+            right,
+            bottom + 18.0,
+            last.date
+        );
+    }
+    out.push_str(&polyline(
+        &series(&|d| d.ideal),
+        days.len(),
+        max,
+        "stroke=\"#999\" stroke-dasharray=\"4 4\"",
+    ));
+    if burnup {
+        out.push_str(&polyline(
+            &series(&|d| Some(d.scope)),
+            days.len(),
+            max,
+            "stroke=\"#e67e22\" stroke-width=\"2\"",
+        ));
+    }
+    out.push_str(&polyline(
+        &series(&|d| Some(actual(d, burnup))),
+        days.len(),
+        max,
+        "stroke=\"#2c7be5\" stroke-width=\"2\"",
+    ));
+    out.push_str("</svg>\n");
+    out
+}
+
+fn to_csv(burndown: &Burndown) -> Result<String> {
+    let number = |v: f64| {
+        if v.is_finite() {
+            v.to_string()
+        } else {
+            String::new()
+        }
+    };
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["date", "scope", "done", "remaining", "ideal"])?;
+    for day in &burndown.days {
+        writer.write_record([
+            day.date.clone(),
+            number(day.scope),
+            number(day.done),
+            number(day.remaining),
+            day.ideal.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 11, d).unwrap()
+    }
+
+    fn noon(d: u32) -> u64 {
+        start_of_day(day(d)) + 12 * 3600
+    }
+
+    #[test]
+    fn test_replay_burndown_with_ideal_line() {
+        let closed = HashSet::from(["Done"]);
+        let mut task = Task {
+            priority: 3,
+            created_at: noon(1),
+            updated_at: noon(4),
+            ..test_support::task(1)
+                .named("A")
+                .with_status(4, "Done", true)
+                .in_project(7, "Web")
+        };
+        let change = |at, from: &str, to: &str| Transition {
+            at,
+            from: Some(from.to_string()),
+            to: to.to_string(),
+        };
+        // Closed on the 2nd, reopened on the 3rd, closed again on the 4th
+        let a = timeline(
+            &task,
+            &[
+                change(noon(2), "Todo", "Done"),
+                change(noon(3), "Done", "Todo"),
+                change(noon(4), "Todo", "Done"),
+            ],
+            &closed,
+        );
+        assert_eq!(
+            a,
+            vec![
+                (noon(1), true),
+                (noon(2), false),
+                (noon(3), true),
+                (noon(4), false)
+            ]
+        );
+        // Closed without history: closed at its last update
+        task.created_at = noon(2);
+        task.updated_at = noon(5);
+        let b = timeline(&task, &[], &closed);
+
+        let days = replay(&[(1.0, a), (2.0, b)], None, Some(day(5)), day(4), false);
+        let remaining: Vec<f64> = days.iter().take(4).map(|d| d.remaining).collect();
+        assert_eq!(remaining, vec![1.0, 2.0, 3.0, 2.0]);
+        assert_eq!(days.len(), 5);
+        assert!(days[4].remaining.is_nan());
+        // From 1 task on the first day down to 0 on the due date
+        assert_eq!(days[0].ideal, Some(1.0));
+        assert_eq!(days[2].ideal, Some(0.5));
+        assert_eq!(days[4].ideal, Some(0.0));
+    }
+}
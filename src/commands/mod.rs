@@ -1,14 +1,204 @@
+use anyhow::{bail, Result};
+
+use crate::api::RepsonaClient;
+use crate::cli::{Commands, ReportCommands, UtilCommands};
+
+pub mod apply;
+pub mod board;
+pub mod cache;
+pub mod calendar;
+pub mod calendar_month;
+pub mod complete;
 pub mod config;
+pub mod edit;
 pub mod file;
 pub mod idlink;
+pub mod import;
 pub mod inbox;
 pub mod me;
+pub mod milestone;
 pub mod note;
+pub mod note_sync;
 pub mod project;
+pub mod project_archive;
+pub mod project_clone;
+pub mod project_publish;
+pub mod project_sync;
 pub mod report;
+pub mod report_digest;
+pub mod report_flow;
+pub mod search;
+pub mod shell;
 pub mod space;
+pub mod sync;
 pub mod tag;
 pub mod task;
+pub mod task_bulk;
+pub mod task_import;
+pub mod task_query;
+pub mod timeline;
 pub mod user;
 pub mod util;
 pub mod webhook;
+
+/// Run a parsed command against an authenticated client.
+///
+/// Shared by `main` and the interactive shell so both accept the same commands.
+///
+/// `yes` skips confirmation prompts (`--yes`).
+pub async fn dispatch(
+    client: &RepsonaClient,
+    command: Commands,
+    json: bool,
+    yes: bool,
+) -> Result<()> {
+    match command {
+        Commands::Util(UtilCommands::Version) => util::handle_version(),
+        Commands::Util(UtilCommands::Ping) => util::handle_ping(client).await?,
+        Commands::Config(cmd) => config::handle(cmd, json).await?,
+        Commands::Me(cmd) => me::handle(client, cmd, json).await?,
+        Commands::Project(cmd) => project::handle(client, cmd, json, yes).await?,
+        Commands::Task(cmd) => task::handle(client, cmd, json, yes).await?,
+        Commands::Note(cmd) => note::handle(client, cmd, json, yes).await?,
+        Commands::Board {
+            project_id,
+            assignee,
+            tag,
+            milestone,
+            compact,
+        } => {
+            let options = board::BoardOptions {
+                project_id,
+                assignee,
+                tag,
+                milestone,
+                compact,
+            };
+            board::handle(client, options, json).await?
+        }
+        Commands::Tui { refresh } => crate::tui::run(client, refresh).await?,
+        Commands::Shell => bail!("Already inside rpsn shell"),
+        Commands::File(cmd) => file::handle(client, cmd, json).await?,
+        Commands::Tag(cmd) => tag::handle(client, cmd, json).await?,
+        Commands::Inbox(cmd) => inbox::handle(client, cmd, json).await?,
+        Commands::Space(cmd) => space::handle(client, cmd, json).await?,
+        Commands::User(cmd) => user::handle(client, cmd, json).await?,
+        Commands::Webhook(cmd) => webhook::handle(client, cmd, json).await?,
+        Commands::Idlink(cmd) => idlink::handle(client, cmd, json).await?,
+        Commands::Sync {
+            full,
+            projects,
+            concurrency,
+        } => {
+            let options = sync::SyncOptions {
+                full,
+                projects,
+                concurrency,
+            };
+            sync::handle(client, options, json).await?
+        }
+        Commands::Search {
+            query,
+            project,
+            kind,
+            limit,
+        } => {
+            let options = search::SearchOptions {
+                query,
+                project,
+                kinds: kind,
+                limit,
+            };
+            search::handle(client, options, json).await?
+        }
+        Commands::Apply { file, output } => {
+            let options = apply::ApplyOptions { file, output, yes };
+            apply::handle(client, options, json).await?
+        }
+        Commands::Import {
+            source,
+            file,
+            project,
+            name,
+            attachments,
+        } => {
+            let options = import::ImportOptions {
+                source,
+                file,
+                project,
+                name,
+                attachments,
+                yes,
+            };
+            import::handle(client, options, json).await?
+        }
+        Commands::Calendar(args) => match args.command {
+            Some(cmd) => calendar::handle(client, cmd).await?,
+            None => {
+                let options = calendar_month::MonthOptions {
+                    target: args.target.unwrap_or_default(),
+                    month: args.month,
+                    lines: args.lines,
+                    include_closed: args.include_closed,
+                };
+                calendar_month::handle(client, options, json).await?
+            }
+        },
+        Commands::Timeline {
+            project_id,
+            from,
+            to,
+            include_closed,
+        } => {
+            let options = timeline::TimelineOptions {
+                project_id,
+                from,
+                to,
+                include_closed,
+            };
+            timeline::handle(client, options, json).await?
+        }
+        Commands::Milestone(cmd) => milestone::handle(client, cmd, json).await?,
+        Commands::Cache(cmd) => cache::handle(client, cmd, json).await?,
+        Commands::Report(ReportCommands::Digest {
+            projects,
+            user,
+            since,
+            upcoming,
+            format,
+            out,
+        }) => {
+            let options = report_digest::DigestOptions {
+                projects,
+                user,
+                since,
+                upcoming,
+                format,
+                out,
+            };
+            report_digest::handle(client, options, json).await?
+        }
+        Commands::Report(ReportCommands::Flow {
+            project_id,
+            since,
+            format,
+            out,
+        }) => {
+            let options = report_flow::FlowOptions {
+                project_id,
+                since,
+                format,
+                out,
+            };
+            report_flow::handle(client, options, json).await?
+        }
+        Commands::Report(cmd) => report::handle(cmd).await?,
+        Commands::Skills { output } => crate::skills::emit(output)?,
+        Commands::Complete { words } => complete::handle_complete(words).await?,
+        Commands::Completion { .. } => {
+            bail!("Run 'rpsn completion <shell>' from your login shell instead")
+        }
+    }
+
+    Ok(())
+}
@@ -1,9 +1,17 @@
 use crate::api::{endpoints::note::*, RepsonaClient};
 use crate::cli::NoteCommands;
-use crate::output::{print, print_success, OutputFormat};
-use anyhow::Result;
+use crate::commands::edit;
+use crate::commands::note_sync;
+use crate::commands::util::read_body;
+use crate::output::{print, print_success, print_text_item, OutputFormat};
+use anyhow::{Context, Result};
 
-pub async fn handle(client: &RepsonaClient, command: NoteCommands, json: bool) -> Result<()> {
+pub async fn handle(
+    client: &RepsonaClient,
+    command: NoteCommands,
+    json: bool,
+    yes: bool,
+) -> Result<()> {
     let format = if json {
         OutputFormat::Json
     } else {
@@ -18,18 +26,21 @@ pub async fn handle(client: &RepsonaClient, command: NoteCommands, json: bool) -
         NoteCommands::Get {
             project_id,
             note_id,
+            raw,
         } => {
             let response = client.get_note(project_id, note_id).await?;
-            print(&response.data.note, format)?;
+            print_text_item("note", &response.data.note, format, raw)?;
         }
         NoteCommands::Create {
             project_id,
             name,
             description,
+            body_file,
             parent,
             tags,
             add_to_bottom,
         } => {
+            let description = read_body(description, body_file)?;
             let tags_vec =
                 tags.map(|t| t.split(',').filter_map(|s| s.trim().parse().ok()).collect());
             let request = CreateNoteRequest {
@@ -48,19 +59,51 @@ pub async fn handle(client: &RepsonaClient, command: NoteCommands, json: bool) -
             note_id,
             name,
             description,
+            body_file,
             tags,
         } => {
+            let description = read_body(description, body_file)?;
             let tags_vec =
                 tags.map(|t| t.split(',').filter_map(|s| s.trim().parse().ok()).collect());
             let request = UpdateNoteRequest {
                 name,
                 description,
+                parent: None,
                 tags: tags_vec,
             };
             let response = client.update_note(project_id, note_id, &request).await?;
             print(&response.data.note, format)?;
             print_success(&format!("Note '{}' updated", response.data.note.name));
         }
+        NoteCommands::Edit {
+            project_id,
+            note_id,
+        } => edit::edit_note(client, project_id, note_id, json).await?,
+        NoteCommands::Pull {
+            project_id,
+            dir,
+            force,
+        } => {
+            let options = note_sync::PullOptions {
+                project_id,
+                dir,
+                force,
+            };
+            note_sync::handle_pull(client, options, json).await?
+        }
+        NoteCommands::Push {
+            project_id,
+            dir,
+            force,
+        } => {
+            let options = note_sync::PushOptions {
+                project_id,
+                dir,
+                force,
+                yes,
+            };
+            note_sync::handle_push(client, options, json).await?
+        }
         NoteCommands::Delete {
             project_id,
             note_id,
@@ -78,15 +121,19 @@ pub async fn handle(client: &RepsonaClient, command: NoteCommands, json: bool) -
         NoteCommands::CommentList {
             project_id,
             note_id,
+            raw,
         } => {
             let response = client.list_note_comments(project_id, note_id).await?;
-            print(&response.data.note_comments, format)?;
+            print_text_item("note_comments", &response.data.note_comments, format, raw)?;
         }
         NoteCommands::CommentAdd {
             project_id,
             note_id,
             comment,
+            body_file,
         } => {
+            let comment =
+                read_body(comment, body_file)?.context("--comment or --body-file is required")?;
             let response = client
                 .add_note_comment(project_id, note_id, comment)
                 .await?;
@@ -98,7 +145,10 @@ pub async fn handle(client: &RepsonaClient, command: NoteCommands, json: bool) -
             note_id,
             comment_id,
             comment,
+            body_file,
         } => {
+            let comment =
+                read_body(comment, body_file)?.context("--comment or --body-file is required")?;
             let response = client
                 .update_note_comment(project_id, note_id, comment_id, comment)
                 .await?;
@@ -0,0 +1,861 @@
+//! `rpsn note pull` / `rpsn note push`: a project's note tree as a directory of Markdown files.
+//!
+//! Each note is `<slug>.md` with its subnotes in a `<slug>/` directory beside it. The front
+//! matter records the note ID, title, tags and the `updated_at` it was pulled at, which push
+//! compares against Repsona to detect notes changed by someone else in the meantime. It also
+//! holds a hash of the content as pulled, which pull uses to leave unpushed local edits alone.
+
+use crate::api::{
+    endpoints::note::{CreateNoteRequest, UpdateNoteRequest},
+    types::*,
+    RepsonaClient,
+};
+use crate::editor;
+use crate::output::{confirm, print, print_success, OutputFormat};
+use crate::telemetry_span;
+use anyhow::{anyhow, bail, Context, Result};
+use colored::Colorize;
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const CONCURRENCY: usize = 4;
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "note".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct PullOptions {
+    pub project_id: u64,
+    pub dir: PathBuf,
+    /// Overwrite files edited locally since they were pulled
+    pub force: bool,
+}
+
+pub struct PushOptions {
+    pub project_id: u64,
+    pub dir: PathBuf,
+    /// Overwrite notes changed in Repsona since they were pulled
+    pub force: bool,
+    pub yes: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Header {
+    /// Absent for notes written locally and not yet pushed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<u64>,
+    /// `content_hash` of the note as last pulled or pushed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+}
+
+/// A Markdown file in the directory
+struct LocalNote {
+    /// Relative to the directory
+    path: PathBuf,
+    header: Header,
+    body: String,
+    /// Index of the note whose directory holds this one
+    parent: Option<usize>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PullReport {
+    pub project_id: u64,
+    pub written: usize,
+    /// Files renamed or moved to follow the note tree
+    pub moved: Vec<String>,
+    /// Files whose note no longer exists in Repsona (left in place)
+    pub stale: Vec<String>,
+    /// Files edited locally since they were pulled (left in place unless forced)
+    pub modified: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PushReport {
+    pub project_id: u64,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    /// Changed in Repsona since the last pull; not pushed
+    pub conflicts: Vec<String>,
+    /// Changes the API cannot make, with the reason
+    pub skipped: Vec<String>,
+    /// Notes in Repsona with no file in the directory (never deleted by push)
+    pub not_local: Vec<String>,
+}
+
+enum Change {
+    Create,
+    /// The request, and whether the note moves under its local parent
+    Update(UpdateNoteRequest, bool),
+}
+
+struct Step {
+    local: usize,
+    change: Change,
+}
+
+/// A file name for a note title: letters and digits, other runs become `-`
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').chars().take(80).collect()
+}
+
+/// Relative file path for each note, keeping subnotes in their parent's directory.
+///
+/// Notes whose parent is missing are placed at the top level. Sibling slugs that collide
+/// get the note ID appended.
+fn layout(notes: &[Note]) -> Vec<PathBuf> {
+    let known: HashSet<u64> = notes.iter().map(|n| n.id).collect();
+    let parent_of = |note: &Note| note.parent.filter(|p| known.contains(p));
+    let mut paths: HashMap<u64, PathBuf> = HashMap::new();
+    let mut used: HashSet<PathBuf> = HashSet::new();
+
+    let mut order: Vec<usize> = (0..notes.len()).collect();
+    order.sort_by_key(|&i| (notes[i].sort_order, notes[i].id));
+    let mut pending = order;
+    while !pending.is_empty() {
+        let before = pending.len();
+        pending.retain(|&i| {
+            let note = &notes[i];
+            let dir = match parent_of(note) {
+                None => PathBuf::new(),
+                Some(p) => match paths.get(&p) {
+                    Some(path) => path.with_extension(""),
+                    None => return true,
+                },
+            };
+            let base = match slug(&note.name) {
+                s if s.is_empty() => format!("note-{}", note.id),
+                s => s,
+            };
+            let mut path = dir.join(format!("{}.md", base));
+            if !used.insert(path.clone()) {
+                path = dir.join(format!("{}-{}.md", base, note.id));
+                used.insert(path.clone());
+            }
+            paths.insert(note.id, path);
+            false
+        });
+        if pending.len() == before {
+            // A parent cycle; place what is left at the top level
+            for &i in &pending {
+                let path = PathBuf::from(format!("note-{}.md", notes[i].id));
+                paths.insert(notes[i].id, path);
+            }
+            break;
+        }
+    }
+    notes.iter().map(|n| paths[&n.id].clone()).collect()
+}
+
+/// FNV-1a over the title, tags and body, stable across builds so it can be stored in files
+fn content_hash(title: &str, tags: &[String], body: &str) -> String {
+    let text = [title.trim(), &tags.join("\n"), body.trim_end()].join("\0");
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+impl LocalNote {
+    /// Whether the file differs from what was last pulled or pushed.
+    ///
+    /// Files without a hash (pulled by an older version) count as unmodified only when they
+    /// still match the remote note at the same `updated_at`.
+    fn is_modified(&self, remote: Option<&Note>) -> bool {
+        let current = content_hash(&self.header.title, &self.header.tags, &self.body);
+        match &self.header.hash {
+            Some(hash) => *hash != current,
+            None => remote
+                .filter(|r| Some(r.updated_at) == self.header.updated_at)
+                .is_none_or(|r| remote_hash(r) != current),
+        }
+    }
+}
+
+fn remote_hash(note: &Note) -> String {
+    let tags: Vec<String> = note.tags.iter().map(|t| t.name.clone()).collect();
+    content_hash(
+        &note.name,
+        &tags,
+        note.description.as_deref().unwrap_or_default(),
+    )
+}
+
+fn render(note: &Note) -> Result<String> {
+    let header = Header {
+        id: Some(note.id),
+        title: note.name.clone(),
+        tags: note.tags.iter().map(|t| t.name.clone()).collect(),
+        updated_at: Some(note.updated_at),
+        hash: Some(remote_hash(note)),
+    };
+    let mut body = note.description.clone().unwrap_or_default();
+    if !body.is_empty() && !body.ends_with('\n') {
+        body.push('\n');
+    }
+    Ok(editor::join(&serde_yaml::to_string(&header)?, &body))
+}
+
+fn markdown_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if name.starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                walk(root, &path, out)?;
+            } else if path.extension().is_some_and(|e| e == "md") {
+                out.push(path.strip_prefix(root)?.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+    let mut files = Vec::new();
+    if dir.exists() {
+        walk(dir, dir, &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read every note file, linking each to the file of the directory it sits in
+fn read_local(dir: &Path) -> Result<Vec<LocalNote>> {
+    let mut notes = Vec::new();
+    for path in markdown_files(dir)? {
+        let text = std::fs::read_to_string(dir.join(&path))
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let (header, body) = editor::split(&text)
+            .and_then(|(header, body)| {
+                let header = header.context("The front matter block is missing")?;
+                Ok((serde_yaml::from_str::<Header>(header)?, body.to_string()))
+            })
+            .with_context(|| format!("Invalid note file {}", path.display()))?;
+        notes.push(LocalNote {
+            path,
+            header,
+            body,
+            parent: None,
+        });
+    }
+    let index: HashMap<PathBuf, usize> = notes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.path.clone(), i))
+        .collect();
+    for note in &mut notes {
+        let Some(dir) = note.path.parent().filter(|d| !d.as_os_str().is_empty()) else {
+            continue;
+        };
+        let parent = dir.with_extension("md");
+        note.parent = Some(*index.get(&parent).ok_or_else(|| {
+            anyhow!(
+                "{} is in directory {}, but there is no {} for its parent note",
+                note.path.display(),
+                dir.display(),
+                parent.display()
+            )
+        })?);
+    }
+    let mut seen = HashMap::new();
+    for note in &notes {
+        if let Some(id) = note.header.id {
+            if let Some(other) = seen.insert(id, &note.path) {
+                bail!(
+                    "{} and {} both have id {}",
+                    other.display(),
+                    note.path.display(),
+                    id
+                );
+            }
+        }
+    }
+    Ok(notes)
+}
+
+async fn load_notes(
+    client: &RepsonaClient,
+    project_id: u64,
+    ids: Option<&HashSet<u64>>,
+) -> Result<Vec<Note>> {
+    let notes = client.list_notes(project_id).await?.data.notes;
+    stream::iter(notes)
+        .map(|note| async move {
+            if ids.is_some_and(|ids| !ids.contains(&note.id)) {
+                return Ok(note);
+            }
+            client
+                .get_note(project_id, note.id)
+                .await
+                .map(|r| r.data.note)
+                .with_context(|| format!("Failed to load note {}", note.id))
+        })
+        .buffered(CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+/// Write the notes into the directory, keeping files with local edits unless `force` is set
+fn write_notes(dir: &Path, notes: &[Note], force: bool, report: &mut PullReport) -> Result<()> {
+    let local = read_local(dir)
+        .with_context(|| format!("Failed to read the notes in {}", dir.display()))?;
+    let remote: HashMap<u64, &Note> = notes.iter().map(|n| (n.id, n)).collect();
+    let existing: HashMap<u64, &LocalNote> = local
+        .iter()
+        .filter_map(|n| n.header.id.map(|id| (id, n)))
+        .collect();
+    let kept: HashSet<&Path> = if force {
+        HashSet::new()
+    } else {
+        local
+            .iter()
+            .filter(|n| match n.header.id {
+                Some(id) => n.is_modified(remote.get(&id).copied()),
+                // Written locally and not pushed yet
+                None => true,
+            })
+            .map(|n| n.path.as_path())
+            .collect()
+    };
+
+    let paths = layout(notes);
+    for (note, path) in notes.iter().zip(&paths) {
+        let old = existing.get(&note.id).map(|n| n.path.as_path());
+        if let Some(old) = old.filter(|old| kept.contains(old)) {
+            report.modified.push(old.display().to_string());
+            continue;
+        }
+        if kept.contains(path.as_path()) {
+            report.modified.push(format!(
+                "{} (would be overwritten by note {})",
+                path.display(),
+                note.id
+            ));
+            continue;
+        }
+        let target = dir.join(path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&target, render(note)?)
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+        report.written += 1;
+        if let Some(old) = old.filter(|old| *old != path) {
+            if !paths.iter().any(|p| p == old) {
+                std::fs::remove_file(dir.join(old))?;
+            }
+            // Leave directories that still hold files; only tidy up empty ones
+            let _ = std::fs::remove_dir(dir.join(old.with_extension("")));
+            report
+                .moved
+                .push(format!("{} → {}", old.display(), path.display()));
+        }
+    }
+    report.stale = existing
+        .iter()
+        .filter(|(id, _)| !remote.contains_key(id))
+        .map(|(_, n)| n.path.display().to_string())
+        .collect();
+    report.stale.sort();
+    report.modified.sort();
+    Ok(())
+}
+
+pub async fn handle_pull(client: &RepsonaClient, options: PullOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let project_id = options.project_id;
+    let dir = options.dir.as_path();
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let notes = telemetry_span::with_span_async_result("load_notes", &exec_attrs, || {
+        load_notes(client, project_id, None)
+    })
+    .await?;
+
+    let mut report = PullReport {
+        project_id,
+        ..Default::default()
+    };
+    telemetry_span::with_span_result("write_files", &exec_attrs, || {
+        write_notes(dir, &notes, options.force, &mut report)
+    })?;
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&report, format),
+        OutputFormat::Human => {
+            for moved in &report.moved {
+                eprintln!("  {} {}", "moved".cyan(), moved);
+            }
+            for stale in &report.stale {
+                eprintln!(
+                    "  {} {} (note no longer exists; left in place)",
+                    "!".yellow(),
+                    stale
+                );
+            }
+            for modified in &report.modified {
+                eprintln!(
+                    "  {} {} has local changes; push them or use --force",
+                    "!".yellow(),
+                    modified
+                );
+            }
+            print_success(&format!(
+                "Pulled {} note(s) into {}",
+                report.written,
+                dir.display()
+            ));
+            Ok(())
+        }
+    })
+}
+
+fn same_tags(names: &[String], tags: &[Tag]) -> bool {
+    let mut a: Vec<String> = names.iter().map(|n| n.trim().to_lowercase()).collect();
+    let mut b: Vec<String> = tags.iter().map(|t| t.name.to_lowercase()).collect();
+    a.sort();
+    b.sort();
+    a == b
+}
+
+fn tag_ids(names: &[String], tags: &[Tag], path: &Path) -> Result<Vec<u64>> {
+    names
+        .iter()
+        .map(|name| {
+            tags.iter()
+                .find(|t| t.name.eq_ignore_ascii_case(name.trim()))
+                .map(|t| t.id)
+                .ok_or_else(|| anyhow!("Unknown tag '{}' in {}", name, path.display()))
+        })
+        .collect()
+}
+
+/// Work out what push would send. Pure, so the plan can be tested and shown first.
+fn plan(
+    local: &[LocalNote],
+    remote: &[Note],
+    tags: &[Tag],
+    force: bool,
+    report: &mut PushReport,
+) -> Result<Vec<Step>> {
+    let remote_by_id: HashMap<u64, &Note> = remote.iter().map(|n| (n.id, n)).collect();
+    let mut steps = Vec::new();
+
+    // Parents sit in shorter paths, so this order creates them before their subnotes
+    let mut order: Vec<usize> = (0..local.len()).collect();
+    order.sort_by_key(|&i| local[i].path.components().count());
+    for i in order {
+        let note = &local[i];
+        let path = note.path.display().to_string();
+        let title = note.header.title.trim();
+        if title.is_empty() {
+            bail!("{} has an empty title", path);
+        }
+        let Some(id) = note.header.id else {
+            tag_ids(&note.header.tags, tags, &note.path)?;
+            steps.push(Step {
+                local: i,
+                change: Change::Create,
+            });
+            continue;
+        };
+        let Some(current) = remote_by_id.get(&id) else {
+            report
+                .skipped
+                .push(format!("{}: note {} no longer exists", path, id));
+            continue;
+        };
+        let local_parent = note.parent.map(|p| local[p].header.id);
+        let moved = match (local_parent, current.parent) {
+            (None, None) => false,
+            (None, Some(_)) => {
+                report.skipped.push(format!(
+                    "{}: moving a note to the top level is not supported by the API",
+                    path
+                ));
+                false
+            }
+            (Some(new), old) => new.is_none() || new != old,
+        };
+        let description = note.body.trim_end();
+        let request = UpdateNoteRequest {
+            name: (title != current.name).then(|| title.to_string()),
+            description: (description
+                != current
+                    .description
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim_end())
+            .then(|| description.to_string()),
+            parent: None,
+            tags: if same_tags(&note.header.tags, &current.tags) {
+                None
+            } else {
+                Some(tag_ids(&note.header.tags, tags, &note.path)?)
+            },
+        };
+        let unchanged = request.name.is_none()
+            && request.description.is_none()
+            && request.tags.is_none()
+            && !moved;
+        if unchanged {
+            continue;
+        }
+        if !force && note.header.updated_at != Some(current.updated_at) {
+            report.conflicts.push(path);
+            continue;
+        }
+        steps.push(Step {
+            local: i,
+            change: Change::Update(request, moved),
+        });
+    }
+
+    let local_ids: HashSet<u64> = local.iter().filter_map(|n| n.header.id).collect();
+    report.not_local = remote
+        .iter()
+        .filter(|n| !local_ids.contains(&n.id))
+        .map(|n| format!("{} ({})", n.name, n.id))
+        .collect();
+    Ok(steps)
+}
+
+fn describe(local: &[LocalNote], step: &Step) -> String {
+    let note = &local[step.local];
+    match &step.change {
+        Change::Create => format!("create {}", note.path.display()),
+        Change::Update(request, moved) => {
+            let mut fields = Vec::new();
+            if request.name.is_some() {
+                fields.push("title");
+            }
+            if request.description.is_some() {
+                fields.push("body");
+            }
+            if request.tags.is_some() {
+                fields.push("tags");
+            }
+            if *moved {
+                fields.push("parent");
+            }
+            format!("update {} ({})", note.path.display(), fields.join(", "))
+        }
+    }
+}
+
+pub async fn handle_push(client: &RepsonaClient, options: PushOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let project_id = options.project_id;
+
+    let validate_attrs = phase_attrs("validate_input");
+    let mut local = telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+        if !options.dir.is_dir() {
+            bail!("{} is not a directory", options.dir.display());
+        }
+        read_local(&options.dir)
+    })?;
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let ids: HashSet<u64> = local.iter().filter_map(|n| n.header.id).collect();
+    let (remote, tags) =
+        telemetry_span::with_span_async_result("load_notes", &exec_attrs, || async {
+            tokio::try_join!(load_notes(client, project_id, Some(&ids)), async {
+                Ok(client.list_tags().await?.data.tags)
+            })
+        })
+        .await?;
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let mut report = PushReport {
+        project_id,
+        ..Default::default()
+    };
+    let steps = telemetry_span::with_span_result("prepare_request", &prepare_attrs, || {
+        plan(&local, &remote, &tags, options.force, &mut report)
+    })?;
+
+    let render_attrs = phase_attrs("render_output");
+    if !json {
+        telemetry_span::with_span("render_output", &render_attrs, || {
+            eprintln!(
+                "\n{}",
+                format!("Push {} to project {}", options.dir.display(), project_id).bold()
+            );
+            for step in &steps {
+                eprintln!("  {} {}", "~".cyan(), describe(&local, step));
+            }
+            for path in &report.conflicts {
+                eprintln!(
+                    "  {} {} changed in Repsona since it was pulled; pull again or use --force",
+                    "!".yellow(),
+                    path
+                );
+            }
+            for skipped in &report.skipped {
+                eprintln!("  {} {}", "!".yellow(), skipped);
+            }
+            if steps.is_empty() {
+                eprintln!("  {}", "No changes".dimmed());
+            }
+            eprintln!();
+        });
+    }
+
+    if !steps.is_empty() && !options.yes {
+        if json {
+            bail!("Pass --yes to push notes with --json");
+        }
+        let proceed =
+            telemetry_span::with_span_result("interactive_confirm", &validate_attrs, || {
+                confirm(&format!("Push {} change(s)? [y/N]: ", steps.len()))
+            })?;
+        if !proceed {
+            eprintln!("{}", "Cancelled".yellow());
+            return Ok(());
+        }
+    }
+
+    let total = steps.len();
+    for (done, step) in steps.into_iter().enumerate() {
+        let summary = describe(&local, &step);
+        let path = local[step.local].path.clone();
+        let parent = local[step.local].parent.and_then(|p| local[p].header.id);
+        let note = &local[step.local];
+        let note = telemetry_span::with_span_async_result("apply_change", &exec_attrs, || async {
+            match step.change {
+                Change::Create => {
+                    let request = CreateNoteRequest {
+                        name: note.header.title.trim().to_string(),
+                        description: Some(note.body.trim_end().to_string()),
+                        parent,
+                        tags: Some(tag_ids(&note.header.tags, &tags, &note.path)?),
+                        add_to_bottom: Some(true),
+                    };
+                    client.create_note(project_id, &request).await
+                }
+                Change::Update(mut request, moved) => {
+                    if moved {
+                        request.parent = parent;
+                    }
+                    let id = note.header.id.context("Note has no id")?;
+                    client.update_note(project_id, id, &request).await
+                }
+            }
+            .map(|r| r.data.note)
+        })
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to {} ({} of {} change(s) pushed)",
+                summary, done, total
+            )
+        })?;
+
+        // Record the new ID and version so the next push starts from here
+        let local_note = &mut local[step.local];
+        if local_note.header.id.is_none() {
+            report.created.push(path.display().to_string());
+        } else {
+            report.updated.push(path.display().to_string());
+        }
+        local_note.header.id = Some(note.id);
+        local_note.header.updated_at = Some(note.updated_at);
+        local_note.header.hash = Some(content_hash(
+            &local_note.header.title,
+            &local_note.header.tags,
+            &local_note.body,
+        ));
+        let text = editor::join(
+            &serde_yaml::to_string(&local_note.header)?,
+            &local_note.body,
+        );
+        std::fs::write(options.dir.join(&path), text)
+            .with_context(|| format!("Failed to update {}", path.display()))?;
+    }
+
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&report, format),
+        OutputFormat::Human => {
+            if !report.not_local.is_empty() {
+                eprintln!(
+                    "{} note(s) in Repsona have no file here and were left alone",
+                    report.not_local.len()
+                );
+            }
+            print_success(&format!(
+                "Pushed {} new and {} updated note(s)",
+                report.created.len(),
+                report.updated.len()
+            ));
+            Ok(())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: u64, name: &str, parent: Option<u64>, sort_order: u32) -> Note {
+        Note {
+            id,
+            name: name.to_string(),
+            description: Some(format!("About {}", name)),
+            tags: Vec::new(),
+            parent,
+            project: ProjectSummary {
+                id: 1,
+                name: "docs".to_string(),
+            },
+            files: Vec::new(),
+            sort_order,
+            created_at: 0,
+            updated_at: 100 + id,
+        }
+    }
+
+    #[test]
+    fn test_layout_nests_subnotes_and_dedupes_slugs() {
+        let notes = vec![
+            note(3, "Setup / Install", Some(1), 0),
+            note(1, "Guide", None, 0),
+            note(2, "Guide", None, 1),
+            note(4, "設計メモ", Some(3), 0),
+            note(5, "Orphan", Some(99), 2),
+        ];
+        let paths: Vec<String> = layout(&notes)
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "guide/setup-install.md",
+                "guide.md",
+                "guide-2.md",
+                "guide/setup-install/設計メモ.md",
+                "orphan.md",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pull_then_push_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let remote = vec![note(1, "Guide", None, 0), note(2, "Install", Some(1), 0)];
+        let mut report = PullReport::default();
+        write_notes(dir.path(), &remote, false, &mut report).unwrap();
+        assert_eq!(report.written, 2);
+
+        let local = read_local(dir.path()).unwrap();
+        let mut report = PushReport::default();
+        assert!(plan(&local, &remote, &[], false, &mut report)
+            .unwrap()
+            .is_empty());
+
+        // A local edit, a new subnote, and a note someone else changed
+        let guide = dir.path().join("guide.md");
+        let text = std::fs::read_to_string(&guide).unwrap();
+        std::fs::write(&guide, text.replace("About Guide", "Rewritten")).unwrap();
+        let install = dir.path().join("guide/install.md");
+        let text = std::fs::read_to_string(&install).unwrap();
+        std::fs::write(&install, text.replace("About Install", "Edited")).unwrap();
+        std::fs::write(
+            dir.path().join("guide/faq.md"),
+            editor::join("title: FAQ\n", "Questions\n"),
+        )
+        .unwrap();
+        let mut remote = remote;
+        remote[1].updated_at += 1;
+
+        let local = read_local(dir.path()).unwrap();
+        let mut report = PushReport::default();
+        let steps = plan(&local, &remote, &[], false, &mut report).unwrap();
+        let described: Vec<String> = steps.iter().map(|s| describe(&local, s)).collect();
+        assert_eq!(
+            described,
+            vec!["update guide.md (body)", "create guide/faq.md"]
+        );
+        assert_eq!(report.conflicts, vec!["guide/install.md"]);
+        let faq = steps
+            .iter()
+            .find(|s| matches!(s.change, Change::Create))
+            .unwrap();
+        assert_eq!(
+            local[faq.local].parent.map(|p| local[p].header.id),
+            Some(Some(1))
+        );
+
+        std::fs::create_dir_all(dir.path().join("lost")).unwrap();
+        std::fs::write(dir.path().join("lost/x.md"), editor::join("title: X\n", "")).unwrap();
+        assert!(read_local(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_pull_keeps_local_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut remote = vec![note(1, "Guide", None, 0), note(2, "Install", Some(1), 0)];
+        write_notes(dir.path(), &remote, false, &mut PullReport::default()).unwrap();
+
+        let guide = dir.path().join("guide.md");
+        let text = std::fs::read_to_string(&guide).unwrap();
+        std::fs::write(&guide, text.replace("About Guide", "Local edit")).unwrap();
+        remote[0].description = Some("Remote edit".to_string());
+        remote[0].updated_at += 1;
+        remote[1].name = "Setup".to_string();
+        remote[1].updated_at += 1;
+
+        let mut report = PullReport::default();
+        write_notes(dir.path(), &remote, false, &mut report).unwrap();
+        assert_eq!(report.written, 1);
+        assert_eq!(report.modified, vec!["guide.md"]);
+        assert_eq!(report.moved, vec!["guide/install.md → guide/setup.md"]);
+        assert!(std::fs::read_to_string(&guide)
+            .unwrap()
+            .contains("Local edit"));
+        assert!(!dir.path().join("guide/install.md").exists());
+
+        let mut report = PullReport::default();
+        write_notes(dir.path(), &remote, true, &mut report).unwrap();
+        assert_eq!(report.written, 2);
+        assert!(report.modified.is_empty());
+        assert!(std::fs::read_to_string(&guide)
+            .unwrap()
+            .contains("Remote edit"));
+    }
+}
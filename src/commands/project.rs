@@ -1,5 +1,9 @@
 use crate::api::{endpoints::project::*, RepsonaClient};
 use crate::cli::ProjectCommands;
+use crate::commands::project_archive;
+use crate::commands::project_clone::{self, CloneOptions};
+use crate::commands::project_publish::{self, PublishOptions};
+use crate::commands::project_sync::{self, SyncOptions};
 use crate::output::{print, print_success, OutputFormat};
 use crate::telemetry_span;
 use anyhow::Result;
@@ -11,7 +15,12 @@ fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
     ]
 }
 
-pub async fn handle(client: &RepsonaClient, command: ProjectCommands, json: bool) -> Result<()> {
+pub async fn handle(
+    client: &RepsonaClient,
+    command: ProjectCommands,
+    json: bool,
+    yes: bool,
+) -> Result<()> {
     let format = if json {
         OutputFormat::Json
     } else {
@@ -188,6 +197,66 @@ pub async fn handle(client: &RepsonaClient, command: ProjectCommands, json: bool
                 print(&response.data.milestones, format)
             })?;
         }
+        ProjectCommands::Sync {
+            project_id,
+            file,
+            prune,
+        } => {
+            let options = SyncOptions {
+                project_id,
+                file,
+                prune,
+                yes,
+            };
+            project_sync::handle_sync(client, options, json).await?;
+        }
+        ProjectCommands::Clone {
+            source,
+            name,
+            full_name,
+            tasks,
+            no_notes,
+            start_on,
+            to_profile,
+        } => {
+            let options = CloneOptions {
+                source,
+                name,
+                full_name,
+                tasks,
+                notes: !no_notes,
+                start_on,
+                to_profile,
+            };
+            project_clone::handle(client, options, json).await?;
+        }
+        ProjectCommands::Export { project_id, out } => {
+            project_archive::handle_export(client, project_id, out, json).await?;
+        }
+        ProjectCommands::Publish {
+            project_id,
+            out,
+            format,
+            days,
+        } => {
+            let options = PublishOptions {
+                project_id,
+                out,
+                format,
+                days,
+            };
+            project_publish::handle(client, options, json).await?;
+        }
+        ProjectCommands::Import { archive, name } => {
+            project_archive::handle_import(client, archive, name, json).await?;
+        }
+        ProjectCommands::ExportConfig {
+            project_id,
+            output,
+            include_space,
+        } => {
+            project_sync::handle_export(client, project_id, output, include_space).await?;
+        }
     }
 
     Ok(())
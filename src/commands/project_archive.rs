@@ -0,0 +1,544 @@
+use crate::api::{
+    endpoints::{
+        file::AttachModel, me::TaskFilter, note::CreateNoteRequest, project::CreateProjectRequest,
+        task::CreateTaskRequest,
+    },
+    types::*,
+    RepsonaClient,
+};
+use crate::archive::{Manifest, ProjectArchive};
+use crate::commands::project_clone::{find_by_name, parents_first};
+use crate::output::{format_date, print, print_success, OutputFormat};
+use crate::telemetry_span;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "project".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+/// Requests in flight while fetching per-task and per-note details
+const CONCURRENCY: usize = 4;
+
+#[derive(Debug, Serialize)]
+pub struct ImportSummary {
+    pub project_id: u64,
+    pub tasks: usize,
+    pub notes: usize,
+    pub comments: usize,
+    pub files: usize,
+    /// Old ID to new ID
+    pub ids: IdMap,
+    /// Members, statuses, milestones and tags that could not be restored
+    pub manual: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct IdMap {
+    pub tasks: BTreeMap<u64, u64>,
+    pub notes: BTreeMap<u64, u64>,
+}
+
+pub async fn handle_export(
+    client: &RepsonaClient,
+    project_id: u64,
+    out: PathBuf,
+    json: bool,
+) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let archive = telemetry_span::with_span_async_result("load_project", &exec_attrs, || {
+        load_archive(client, project_id)
+    })
+    .await?;
+
+    let write_attrs = phase_attrs("write_output");
+    let manifest = telemetry_span::with_span_result("write_archive", &write_attrs, || {
+        archive.write(&out, client.space_id())
+    })?;
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&manifest, format),
+        OutputFormat::Human => {
+            print_success(&format!(
+                "Exported project {} to {}: {}",
+                project_id,
+                out.display(),
+                describe_counts(&manifest)
+            ));
+            Ok(())
+        }
+    })
+}
+
+fn describe_counts(manifest: &Manifest) -> String {
+    let count = |name: &str| manifest.counts.get(name).copied().unwrap_or(0);
+    format!(
+        "{} task(s), {} note(s), {} comment(s), {} file(s)",
+        count("tasks"),
+        count("notes"),
+        count("task_comments") + count("note_comments"),
+        manifest.files.len()
+    )
+}
+
+async fn load_archive(client: &RepsonaClient, project_id: u64) -> Result<ProjectArchive> {
+    let filter = TaskFilter::default();
+    let (project, members, statuses, milestones, tags, tasks, notes) = tokio::try_join!(
+        client.get_project(project_id),
+        client.list_project_members(project_id),
+        client.list_project_statuses(project_id),
+        client.list_project_milestones(project_id),
+        client.list_tags(),
+        client.list_tasks(project_id, &filter),
+        client.list_notes(project_id),
+    )?;
+
+    let mut archive = ProjectArchive::new(project.data.project);
+    archive.members = members.data.users;
+    archive.statuses = statuses.data.statuses;
+    archive.milestones = milestones.data.milestones;
+    archive.tags = tags.data.tags;
+
+    let tasks: Vec<(Task, Vec<TaskComment>, Vec<History>)> = stream::iter(tasks.data.tasks)
+        .map(|task| async move {
+            let (detail, comments, history) = tokio::try_join!(
+                client.get_task(project_id, task.id),
+                client.list_task_comments(project_id, task.id),
+                client.get_task_history(project_id, task.id),
+            )
+            .with_context(|| format!("Failed to export task {}", task.id))?;
+            Ok::<_, anyhow::Error>((
+                detail.data.task,
+                comments.data.task_comments,
+                history.data.history,
+            ))
+        })
+        .buffered(CONCURRENCY)
+        .try_collect()
+        .await?;
+    for (task, comments, history) in tasks {
+        archive.task_comments.insert(task.id, comments);
+        archive.task_history.insert(task.id, history);
+        archive.tasks.push(task);
+    }
+
+    let notes: Vec<(Note, Vec<NoteComment>, Vec<History>)> = stream::iter(notes.data.notes)
+        .map(|note| async move {
+            let (detail, comments, history) = tokio::try_join!(
+                client.get_note(project_id, note.id),
+                client.list_note_comments(project_id, note.id),
+                client.get_note_history(project_id, note.id),
+            )
+            .with_context(|| format!("Failed to export note {}", note.id))?;
+            Ok::<_, anyhow::Error>((
+                detail.data.note,
+                comments.data.note_comments,
+                history.data.history,
+            ))
+        })
+        .buffered(CONCURRENCY)
+        .try_collect()
+        .await?;
+    for (note, comments, history) in notes {
+        archive.note_comments.insert(note.id, comments);
+        archive.note_history.insert(note.id, history);
+        archive.notes.push(note);
+    }
+
+    let hashes: Vec<String> = archive
+        .attachments()
+        .into_iter()
+        .map(|f| f.hash.clone())
+        .collect();
+    let files: Vec<(String, Vec<u8>)> = stream::iter(hashes)
+        .map(|hash| async move {
+            let data = client
+                .download_file_bytes(&hash)
+                .await
+                .with_context(|| format!("Failed to download attachment {}", hash))?;
+            Ok::<_, anyhow::Error>((hash, data))
+        })
+        .buffered(CONCURRENCY)
+        .try_collect()
+        .await?;
+    archive.files.extend(files);
+
+    Ok(archive)
+}
+
+pub async fn handle_import(
+    client: &RepsonaClient,
+    file: PathBuf,
+    name: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let validate_attrs = phase_attrs("validate_input");
+    let (_, archive) = telemetry_span::with_span_result("read_archive", &validate_attrs, || {
+        ProjectArchive::read(&file)
+    })?;
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let project = telemetry_span::with_span_async_result("create_project", &exec_attrs, || {
+        let request = CreateProjectRequest {
+            name: name.clone().unwrap_or_else(|| archive.project.name.clone()),
+            full_name: Some(archive.project.full_name.clone()),
+            purpose: archive.project.purpose.clone(),
+        };
+        async move { client.create_project(&request).await }
+    })
+    .await?
+    .data
+    .project;
+
+    let summary = telemetry_span::with_span_async_result("restore_contents", &exec_attrs, || {
+        restore(client, project.id, &archive)
+    })
+    .await
+    .with_context(|| {
+        format!(
+            "Project {} was created but only partly restored; delete it with `rpsn project delete {}` before retrying",
+            project.id, project.id
+        )
+    })?;
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&summary, format),
+        OutputFormat::Human => {
+            print_success(&format!(
+                "Imported {} as project {} ({}): {} task(s), {} note(s), {} comment(s), {} file(s)",
+                display(&file),
+                project.name,
+                project.id,
+                summary.tasks,
+                summary.notes,
+                summary.comments,
+                summary.files
+            ));
+            if !summary.manual.is_empty() {
+                eprintln!(
+                    "{}",
+                    "Not restored (add these in the Repsona web app):".yellow()
+                );
+                for item in &summary.manual {
+                    eprintln!("  {} {}", "!".yellow(), item);
+                }
+            }
+            Ok(())
+        }
+    })
+}
+
+fn display(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Recreate members, notes, tasks, comments and attachments in `project_id`
+async fn restore(
+    client: &RepsonaClient,
+    project_id: u64,
+    archive: &ProjectArchive,
+) -> Result<ImportSummary> {
+    let mut manual = Vec::new();
+    let (users, members, statuses, milestones, tags) = tokio::try_join!(
+        client.list_users(),
+        client.list_project_members(project_id),
+        client.list_project_statuses(project_id),
+        client.list_project_milestones(project_id),
+        client.list_tags(),
+    )?;
+    let users = users.data.users;
+    let statuses = statuses.data.statuses;
+    let milestones = milestones.data.milestones;
+    let tags = tags.data.tags;
+
+    let user_ids = map_users(&archive.members, &archive.tasks, &users, &mut manual);
+    for member in &archive.members {
+        match user_ids.get(&member.id) {
+            Some(&id) if !members.data.users.iter().any(|m| m.id == id) => {
+                client.add_project_member(project_id, id).await?;
+            }
+            Some(_) => {}
+            None => manual.push(format!("member {} ({})", member.name, member.email)),
+        }
+    }
+    for status in &archive.statuses {
+        if find_by_name(&statuses, |s| (s.id, &s.name), &status.name).is_none() {
+            manual.push(format!("status '{}'", status.name));
+        }
+    }
+    for milestone in &archive.milestones {
+        if find_by_name(&milestones, |m| (m.id, &m.name), &milestone.name).is_none() {
+            manual.push(format!("milestone '{}'", milestone.name));
+        }
+    }
+    let mut tag_ids: HashMap<u64, u64> = HashMap::new();
+    for tag in &archive.tags {
+        let used = archive
+            .tasks
+            .iter()
+            .flat_map(|t| &t.tags)
+            .chain(archive.notes.iter().flat_map(|n| &n.tags))
+            .any(|t| t.id == tag.id);
+        match find_by_name(&tags, |t| (t.id, &t.name), &tag.name) {
+            Some(id) => {
+                tag_ids.insert(tag.id, id);
+            }
+            None if used => manual.push(format!("tag '{}'", tag.name)),
+            None => {}
+        }
+    }
+    let map_tags = |from: &[Tag]| -> Option<Vec<u64>> {
+        let ids: Vec<u64> = from
+            .iter()
+            .filter_map(|t| tag_ids.get(&t.id).copied())
+            .collect();
+        (!ids.is_empty()).then_some(ids)
+    };
+
+    let mut ids = IdMap::default();
+    let mut uploaded: HashMap<String, u64> = HashMap::new();
+    let mut comments = 0;
+
+    for i in parents_first(&archive.notes, |n| (n.id, n.parent, n.sort_order)) {
+        let note = &archive.notes[i];
+        let request = CreateNoteRequest {
+            name: note.name.clone(),
+            description: note.description.clone(),
+            parent: note.parent.and_then(|p| ids.notes.get(&p).copied()),
+            tags: map_tags(&note.tags),
+            add_to_bottom: Some(true),
+        };
+        let new_id = client
+            .create_note(project_id, &request)
+            .await
+            .with_context(|| format!("Failed to restore note '{}'", note.name))?
+            .data
+            .note
+            .id;
+        ids.notes.insert(note.id, new_id);
+        for comment in sorted(archive.note_comments.get(&note.id), |c| c.created_at) {
+            let body = attributed(
+                &comment.user.name,
+                Some(comment.created_at),
+                &comment.comment,
+            );
+            client.add_note_comment(project_id, new_id, body).await?;
+            comments += 1;
+        }
+        for file in &note.files {
+            let file_id = upload(client, project_id, archive, file, &mut uploaded).await?;
+            client
+                .attach_file(project_id, AttachModel::Note, new_id, file_id)
+                .await?;
+        }
+    }
+
+    for i in parents_first(&archive.tasks, |t| (t.id, t.parent, t.sort_order)) {
+        let task = &archive.tasks[i];
+        let user = |u: &Option<User>| u.as_ref().and_then(|u| user_ids.get(&u.id).copied());
+        let request = CreateTaskRequest {
+            name: task.name.clone(),
+            description: task.description.clone(),
+            status: find_by_name(&statuses, |s| (s.id, &s.name), &task.status.name),
+            priority: Some(task.priority),
+            due_date: task.due_date,
+            start_date: task.start_date,
+            responsible_user: user(&task.responsible_user),
+            ball_holding_user: user(&task.ball_holding_user),
+            parent: task.parent.and_then(|p| ids.tasks.get(&p).copied()),
+            milestone: task
+                .milestone
+                .as_ref()
+                .and_then(|m| find_by_name(&milestones, |m| (m.id, &m.name), &m.name)),
+            tags: map_tags(&task.tags),
+            add_to_bottom: Some(true),
+        };
+        let new_id = client
+            .create_task(project_id, &request)
+            .await
+            .with_context(|| format!("Failed to restore task '{}'", task.name))?
+            .data
+            .task
+            .id;
+        ids.tasks.insert(task.id, new_id);
+        for comment in sorted(archive.task_comments.get(&task.id), |c| c.created_at) {
+            let body = attributed(
+                &comment.user.name,
+                Some(comment.created_at),
+                &comment.comment,
+            );
+            client
+                .add_task_comment(project_id, new_id, body, None)
+                .await?;
+            comments += 1;
+        }
+        for file in &task.files {
+            let file_id = upload(client, project_id, archive, file, &mut uploaded).await?;
+            client
+                .attach_file(project_id, AttachModel::Task, new_id, file_id)
+                .await?;
+        }
+    }
+
+    Ok(ImportSummary {
+        project_id,
+        tasks: ids.tasks.len(),
+        notes: ids.notes.len(),
+        comments,
+        files: uploaded.len(),
+        ids,
+        manual,
+    })
+}
+
+/// Map archived user IDs to users of this space, by email, the one identity that carries
+/// across spaces. Task assignees and ball holders who are not members and have no match are
+/// added to `manual`; unmatched members are reported when adding members.
+fn map_users(
+    members: &[User],
+    tasks: &[Task],
+    users: &[User],
+    manual: &mut Vec<String>,
+) -> HashMap<u64, u64> {
+    let assigned = tasks
+        .iter()
+        .flat_map(|t| [&t.responsible_user, &t.ball_holding_user])
+        .flatten();
+    let mut ids = HashMap::new();
+    let mut missing: BTreeMap<u64, (&User, usize)> = BTreeMap::new();
+    for old in members.iter().chain(assigned) {
+        match users
+            .iter()
+            .find(|u| u.email.eq_ignore_ascii_case(&old.email))
+        {
+            Some(user) => {
+                ids.insert(old.id, user.id);
+            }
+            None if !members.iter().any(|m| m.id == old.id) => {
+                missing.entry(old.id).or_insert((old, 0)).1 += 1;
+            }
+            None => {}
+        }
+    }
+    for (user, count) in missing.into_values() {
+        manual.push(format!(
+            "user {} ({}), left off {} task assignment(s)",
+            user.name, user.email, count
+        ));
+    }
+    ids
+}
+
+/// Upload an archived attachment once and return its new file ID
+async fn upload(
+    client: &RepsonaClient,
+    project_id: u64,
+    archive: &ProjectArchive,
+    file: &File,
+    uploaded: &mut HashMap<String, u64>,
+) -> Result<u64> {
+    if let Some(&id) = uploaded.get(&file.hash) {
+        return Ok(id);
+    }
+    let data = archive
+        .files
+        .get(&file.hash)
+        .with_context(|| format!("Archive is missing attachment {}", file.filename))?;
+    let id = client
+        .upload_file_bytes(project_id, &file.filename, data.clone())
+        .await?
+        .data
+        .files
+        .first()
+        .map(|f| f.id)
+        .with_context(|| format!("Upload of {} returned no file", file.filename))?;
+    uploaded.insert(file.hash.clone(), id);
+    Ok(id)
+}
+
+fn sorted<T>(items: Option<&Vec<T>>, key: impl Fn(&T) -> u64) -> Vec<&T> {
+    let mut items: Vec<&T> = items.into_iter().flatten().collect();
+    items.sort_by_key(|c| key(c));
+    items
+}
+
+/// Comment text recording the original author and date, since the import posts as you
+pub(crate) fn attributed(author: &str, created_at: Option<u64>, body: &str) -> String {
+    match created_at {
+        Some(at) => format!("> {} ({})\n\n{}", author, format_date(at), body),
+        None => format!("> {}\n\n{}", author, body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    #[test]
+    fn test_attributed_keeps_author_and_body() {
+        let body = attributed("alice", Some(86_400 * 365 + 43_200), "Looks good");
+        assert!(body.starts_with("> alice (1971-01-01)"), "{}", body);
+        assert!(body.ends_with("\n\nLooks good"));
+        assert_eq!(attributed("bob", None, "Hi"), "> bob\n\nHi");
+    }
+
+    fn user(id: u64, email: &str) -> User {
+        User {
+            id,
+            email: email.to_string(),
+            name: email.split('@').next().unwrap_or_default().to_string(),
+            full_name: String::new(),
+            avatar_url: None,
+            role: "member".to_string(),
+            billing_status: "active".to_string(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_map_users_covers_ball_holders() {
+        let task = Task {
+            responsible_user: Some(user(10, "alice@example.com")),
+            ball_holding_user: Some(user(11, "bob@example.com")),
+            ..test_support::task(1).named("Review").in_project(1, "old")
+        };
+        let mut other = task.clone();
+        other.responsible_user = Some(user(12, "carol@example.com"));
+        other.ball_holding_user = Some(user(12, "carol@example.com"));
+        let members = vec![user(10, "alice@example.com"), user(13, "dave@example.com")];
+        let users = vec![user(1, "Alice@example.com"), user(2, "bob@example.com")];
+
+        let mut manual = Vec::new();
+        let ids = map_users(&members, &[task, other], &users, &mut manual);
+        assert_eq!(ids, HashMap::from([(10, 1), (11, 2)]));
+        assert_eq!(
+            manual,
+            vec!["user carol (carol@example.com), left off 2 task assignment(s)"]
+        );
+    }
+}
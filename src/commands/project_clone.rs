@@ -0,0 +1,492 @@
+use crate::api::{
+    endpoints::{note::CreateNoteRequest, project::CreateProjectRequest, task::CreateTaskRequest},
+    types::*,
+    RepsonaClient,
+};
+use crate::config;
+use crate::output::{format_date, print, print_success, OutputFormat};
+use crate::query::{self, TaskQuery};
+use crate::telemetry_span;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "project".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct CloneOptions {
+    pub source: u64,
+    pub name: String,
+    pub full_name: Option<String>,
+    /// `all`, `none`, or a `task query` filter (`key=value,...`)
+    pub tasks: String,
+    pub notes: bool,
+    /// Date the earliest copied date moves to
+    pub start_on: Option<String>,
+    /// Profile whose space receives the new project
+    pub to_profile: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CloneSummary {
+    pub project_id: u64,
+    pub space_id: String,
+    pub notes: usize,
+    pub tasks: usize,
+    /// Statuses, milestones and tags to create by hand (the API cannot)
+    pub manual: Vec<String>,
+}
+
+enum Selection {
+    All,
+    Nothing,
+    Matching(TaskQuery),
+}
+
+impl Selection {
+    fn parse(value: &str) -> Result<Self> {
+        Ok(match value.trim() {
+            v if v.eq_ignore_ascii_case("all") => Selection::All,
+            v if v.eq_ignore_ascii_case("none") => Selection::Nothing,
+            v => Selection::Matching(TaskQuery::parse_where(v)?),
+        })
+    }
+}
+
+/// Everything read from the source project
+struct Source {
+    project: Project,
+    statuses: Vec<Status>,
+    milestones: Vec<Milestone>,
+    notes: Vec<Note>,
+    tasks: Vec<Task>,
+}
+
+pub async fn handle(client: &RepsonaClient, options: CloneOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let today = chrono::Local::now().date_naive();
+
+    let validate_attrs = phase_attrs("validate_input");
+    let (selection, start_on, target) =
+        telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+            let selection = Selection::parse(&options.tasks)?;
+            let start_on = options
+                .start_on
+                .as_deref()
+                .map(|d| query::parse_date(d, today))
+                .transpose()?;
+            let target = match &options.to_profile {
+                Some(profile) => {
+                    let (space_id, api_token) = config::load_profile_credentials(profile)?;
+                    Some(client.for_space(space_id, api_token))
+                }
+                None => None,
+            };
+            Ok::<_, anyhow::Error>((selection, start_on, target))
+        })?;
+    let same_space = target
+        .as_ref()
+        .is_none_or(|t| t.space_id() == client.space_id());
+    let target = target.as_ref().unwrap_or(client);
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let source = telemetry_span::with_span_async_result("load_source", &exec_attrs, || {
+        load_source(client, options.source, &selection, options.notes, today)
+    })
+    .await?;
+
+    let offset = start_on.map_or(0, |day| date_offset(&source, day));
+
+    let project = telemetry_span::with_span_async_result("create_project", &exec_attrs, || {
+        let request = CreateProjectRequest {
+            name: options.name.clone(),
+            full_name: options.full_name.clone(),
+            purpose: source.project.purpose.clone(),
+        };
+        async move { target.create_project(&request).await }
+    })
+    .await?
+    .data
+    .project;
+
+    let copy = telemetry_span::with_span_async_result("copy_contents", &exec_attrs, || {
+        copy_contents(target, project.id, &source, same_space, offset)
+    })
+    .await
+    .with_context(|| {
+        format!(
+            "Project {} was created but only partly copied; delete it with `rpsn project delete {}` before retrying",
+            project.id, project.id
+        )
+    })?;
+
+    let summary = CloneSummary {
+        project_id: project.id,
+        space_id: target.space_id().to_string(),
+        notes: copy.notes,
+        tasks: copy.tasks,
+        manual: copy.manual,
+    };
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&summary, format),
+        OutputFormat::Human => {
+            print_success(&format!(
+                "Cloned project {} into {} ({}): {} note(s), {} task(s)",
+                options.source, project.name, project.id, summary.notes, summary.tasks
+            ));
+            if !summary.manual.is_empty() {
+                eprintln!(
+                    "{}",
+                    "Create these in the Repsona web app (the API cannot):".yellow()
+                );
+                for item in &summary.manual {
+                    eprintln!("  {} {}", "!".yellow(), item);
+                }
+            }
+            Ok(())
+        }
+    })
+}
+
+async fn load_source(
+    client: &RepsonaClient,
+    project_id: u64,
+    selection: &Selection,
+    notes: bool,
+    today: chrono::NaiveDate,
+) -> Result<Source> {
+    let (project, statuses, milestones) = tokio::try_join!(
+        client.get_project(project_id),
+        client.list_project_statuses(project_id),
+        client.list_project_milestones(project_id),
+    )?;
+    let notes = if notes {
+        client.list_notes(project_id).await?.data.notes
+    } else {
+        Vec::new()
+    };
+    let tasks = match selection {
+        Selection::Nothing => Vec::new(),
+        Selection::All | Selection::Matching(_) => {
+            let filter = crate::api::endpoints::me::TaskFilter::default();
+            let tasks = client.list_tasks(project_id, &filter).await?.data.tasks;
+            match selection {
+                Selection::Matching(query) => {
+                    let me = if query.needs_me() {
+                        Some(client.get_me().await?.data.user.id)
+                    } else {
+                        None
+                    };
+                    let matcher = query.compile(today, me)?;
+                    let picked: Vec<u64> = tasks
+                        .iter()
+                        .filter(|t| matcher.matches(t))
+                        .map(|t| t.id)
+                        .collect();
+                    with_ancestors(tasks, &picked)
+                }
+                _ => tasks,
+            }
+        }
+    };
+    Ok(Source {
+        project: project.data.project,
+        statuses: statuses.data.statuses,
+        milestones: milestones.data.milestones,
+        notes,
+        tasks,
+    })
+}
+
+struct Copied {
+    notes: usize,
+    tasks: usize,
+    manual: Vec<String>,
+}
+
+async fn copy_contents(
+    target: &RepsonaClient,
+    project_id: u64,
+    source: &Source,
+    same_space: bool,
+    offset: i64,
+) -> Result<Copied> {
+    let mut manual = Vec::new();
+
+    let (statuses, milestones) = tokio::try_join!(
+        target.list_project_statuses(project_id),
+        target.list_project_milestones(project_id),
+    )?;
+    let statuses = statuses.data.statuses;
+    let milestones = milestones.data.milestones;
+    for status in &source.statuses {
+        if find_by_name(&statuses, |s| (s.id, &s.name), &status.name).is_none() {
+            manual.push(format!("status '{}'", status.name));
+        }
+    }
+    for milestone in &source.milestones {
+        if find_by_name(&milestones, |m| (m.id, &m.name), &milestone.name).is_none() {
+            manual.push(match milestone.due_date {
+                Some(due) => format!(
+                    "milestone '{}' (due {})",
+                    milestone.name,
+                    format_date(shift(due, offset))
+                ),
+                None => format!("milestone '{}'", milestone.name),
+            });
+        }
+    }
+
+    let used_tags: Vec<&Tag> = {
+        let mut seen = HashSet::new();
+        source
+            .notes
+            .iter()
+            .flat_map(|n| &n.tags)
+            .chain(source.tasks.iter().flat_map(|t| &t.tags))
+            .filter(|t| seen.insert(t.id))
+            .collect()
+    };
+    let mut tags: HashMap<u64, u64> = HashMap::new();
+    if same_space {
+        tags.extend(used_tags.iter().map(|t| (t.id, t.id)));
+    } else if !used_tags.is_empty() {
+        let target_tags = target.list_tags().await?.data.tags;
+        for tag in used_tags {
+            match find_by_name(&target_tags, |t| (t.id, &t.name), &tag.name) {
+                Some(id) => {
+                    tags.insert(tag.id, id);
+                }
+                None => manual.push(format!("tag '{}'", tag.name)),
+            }
+        }
+    }
+    let map_tags = |from: &[Tag]| -> Option<Vec<u64>> {
+        let ids: Vec<u64> = from
+            .iter()
+            .filter_map(|t| tags.get(&t.id).copied())
+            .collect();
+        (!ids.is_empty()).then_some(ids)
+    };
+
+    let mut note_ids: HashMap<u64, u64> = HashMap::new();
+    for i in parents_first(&source.notes, |n| (n.id, n.parent, n.sort_order)) {
+        let note = &source.notes[i];
+        let request = CreateNoteRequest {
+            name: note.name.clone(),
+            description: note.description.clone(),
+            parent: note.parent.and_then(|p| note_ids.get(&p).copied()),
+            tags: map_tags(&note.tags),
+            add_to_bottom: Some(true),
+        };
+        let created = target
+            .create_note(project_id, &request)
+            .await
+            .with_context(|| format!("Failed to copy note '{}'", note.name))?;
+        note_ids.insert(note.id, created.data.note.id);
+    }
+
+    let mut task_ids: HashMap<u64, u64> = HashMap::new();
+    for i in parents_first(&source.tasks, |t| (t.id, t.parent, t.sort_order)) {
+        let task = &source.tasks[i];
+        let request = CreateTaskRequest {
+            name: task.name.clone(),
+            description: task.description.clone(),
+            status: find_by_name(&statuses, |s| (s.id, &s.name), &task.status.name),
+            priority: Some(task.priority),
+            due_date: task.due_date.map(|d| shift(d, offset)),
+            start_date: task.start_date.map(|d| shift(d, offset)),
+            parent: task.parent.and_then(|p| task_ids.get(&p).copied()),
+            milestone: task
+                .milestone
+                .as_ref()
+                .and_then(|m| find_by_name(&milestones, |m| (m.id, &m.name), &m.name)),
+            tags: map_tags(&task.tags),
+            add_to_bottom: Some(true),
+            ..Default::default()
+        };
+        let created = target
+            .create_task(project_id, &request)
+            .await
+            .with_context(|| format!("Failed to copy task '{}'", task.name))?;
+        task_ids.insert(task.id, created.data.task.id);
+    }
+
+    Ok(Copied {
+        notes: note_ids.len(),
+        tasks: task_ids.len(),
+        manual,
+    })
+}
+
+/// ID of the item named `name` (ignoring case)
+pub(crate) fn find_by_name<T>(
+    items: &[T],
+    key: impl Fn(&T) -> (u64, &String),
+    name: &str,
+) -> Option<u64> {
+    items
+        .iter()
+        .map(key)
+        .find_map(|(id, n)| n.eq_ignore_ascii_case(name).then_some(id))
+}
+
+/// `tasks` narrowed to `picked` plus every parent above them, so subtasks keep their place
+fn with_ancestors(tasks: Vec<Task>, picked: &[u64]) -> Vec<Task> {
+    let parents: HashMap<u64, Option<u64>> = tasks.iter().map(|t| (t.id, t.parent)).collect();
+    let mut keep: HashSet<u64> = HashSet::new();
+    for &id in picked {
+        let mut next = Some(id);
+        while let Some(id) = next {
+            if !keep.insert(id) {
+                break;
+            }
+            next = parents.get(&id).copied().flatten();
+        }
+    }
+    tasks.into_iter().filter(|t| keep.contains(&t.id)).collect()
+}
+
+/// Indexes of `items` with every parent before its children, siblings in sort order.
+///
+/// Items whose parent is not in the list are treated as top-level.
+pub(crate) fn parents_first<T>(
+    items: &[T],
+    key: impl Fn(&T) -> (u64, Option<u64>, u32),
+) -> Vec<usize> {
+    let keys: Vec<(u64, Option<u64>, u32)> = items.iter().map(key).collect();
+    let ids: HashSet<u64> = keys.iter().map(|k| k.0).collect();
+    let mut children: HashMap<Option<u64>, Vec<usize>> = HashMap::new();
+    for (i, (_, parent, _)) in keys.iter().enumerate() {
+        let parent = parent.filter(|p| ids.contains(p));
+        children.entry(parent).or_default().push(i);
+    }
+    for list in children.values_mut() {
+        list.sort_by_key(|&i| (keys[i].2, keys[i].0));
+    }
+
+    let mut order = Vec::with_capacity(items.len());
+    let mut stack: Vec<usize> = children
+        .get(&None)
+        .into_iter()
+        .flatten()
+        .rev()
+        .copied()
+        .collect();
+    while let Some(i) = stack.pop() {
+        order.push(i);
+        if let Some(list) = children.get(&Some(keys[i].0)) {
+            stack.extend(list.iter().rev());
+        }
+    }
+    order
+}
+
+/// Seconds to add so the earliest task or milestone date lands on `day`
+fn date_offset(source: &Source, day: u64) -> i64 {
+    let earliest = source
+        .tasks
+        .iter()
+        .flat_map(|t| [t.start_date, t.due_date])
+        .chain(source.milestones.iter().map(|m| m.due_date))
+        .flatten()
+        .min();
+    match earliest {
+        Some(earliest) => {
+            let earliest_day = chrono::DateTime::from_timestamp(earliest as i64, 0)
+                .map(|dt| query::start_of_day(dt.with_timezone(&chrono::Local).date_naive()))
+                .unwrap_or(earliest);
+            day as i64 - earliest_day as i64
+        }
+        None => 0,
+    }
+}
+
+fn shift(timestamp: u64, offset: i64) -> u64 {
+    (timestamp as i64 + offset).max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn task(id: u64, parent: Option<u64>, sort_order: u32) -> Task {
+        Task {
+            priority: 3,
+            parent,
+            sort_order,
+            ..test_support::task(id)
+                .named(&format!("task {}", id))
+                .in_project(12, "src")
+        }
+    }
+
+    fn ids(tasks: &[Task], order: &[usize]) -> Vec<u64> {
+        order.iter().map(|&i| tasks[i].id).collect()
+    }
+
+    #[test]
+    fn test_parents_first_keeps_hierarchy_and_sort_order() {
+        let tasks = vec![
+            task(4, Some(2), 0),
+            task(2, None, 1),
+            task(3, Some(1), 0),
+            task(1, None, 0),
+            task(5, Some(99), 2),
+        ];
+        let order = parents_first(&tasks, |t| (t.id, t.parent, t.sort_order));
+        assert_eq!(ids(&tasks, &order), vec![1, 3, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_with_ancestors_pulls_in_parents() {
+        let tasks = vec![
+            task(1, None, 0),
+            task(2, Some(1), 0),
+            task(3, Some(2), 0),
+            task(4, None, 1),
+        ];
+        let kept: Vec<u64> = with_ancestors(tasks, &[3]).iter().map(|t| t.id).collect();
+        assert_eq!(kept, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_date_offset_moves_earliest_date_to_start() {
+        let day = query::start_of_day(chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        let later = query::start_of_day(chrono::NaiveDate::from_ymd_opt(2024, 3, 11).unwrap());
+        let mut first = task(1, None, 0);
+        first.due_date = Some(later + 3600);
+        let source = Source {
+            project: serde_json::from_value(serde_json::json!({
+                "id": 12, "name": "src", "fullName": "Source", "purpose": null,
+                "avatarUrl": null, "isClosed": false, "isPublic": false,
+                "createdAt": 0, "updatedAt": 0
+            }))
+            .unwrap(),
+            statuses: Vec::new(),
+            milestones: vec![Milestone {
+                id: 1,
+                name: "Beta".to_string(),
+                due_date: Some(later + 86_400 * 5),
+                is_closed: false,
+            }],
+            notes: Vec::new(),
+            tasks: vec![first],
+        };
+        let target = query::start_of_day(chrono::NaiveDate::from_ymd_opt(2024, 4, 1).unwrap());
+        let offset = date_offset(&source, target);
+        assert_eq!(shift(later + 3600, offset), target + 3600);
+        assert_eq!(date_offset(&source, day), day as i64 - later as i64);
+    }
+}
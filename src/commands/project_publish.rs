@@ -0,0 +1,542 @@
+//! `rpsn project publish`: a read-only report of a project for people without a Repsona
+//! account, as a static HTML site or a single Markdown document.
+
+use crate::api::{endpoints::me::TaskFilter, types::*, RepsonaClient};
+use crate::cli::PublishFormat;
+use crate::markdown;
+use crate::output::{format_date, print, print_success, OutputFormat};
+use crate::telemetry_span;
+use anyhow::{Context, Result};
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+const CONCURRENCY: usize = 4;
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:0;display:flex;color:#222}\
+nav{width:16rem;padding:1rem;background:#f5f5f5;min-height:100vh;box-sizing:border-box}\
+nav ul{padding-left:1rem}main{flex:1;padding:1rem 2rem;max-width:60rem}\
+table{border-collapse:collapse}td,th{border:1px solid #ddd;padding:.25rem .5rem}\
+.meta{color:#666;font-size:.9em}details{margin:.25rem 0}";
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "project".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct PublishOptions {
+    pub project_id: u64,
+    /// Directory for HTML, file for Markdown
+    pub out: PathBuf,
+    pub format: PublishFormat,
+    /// How many days of activity to include
+    pub days: u64,
+}
+
+/// Everything the report shows, loaded once
+struct Site {
+    project: Project,
+    statuses: Vec<Status>,
+    milestones: Vec<Milestone>,
+    tasks: Vec<Task>,
+    /// With bodies, in sort order
+    notes: Vec<Note>,
+    /// Newest first, within the requested window
+    activity: Vec<Activity>,
+    published_at: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublishReport {
+    pub project_id: u64,
+    pub out: String,
+    pub files: usize,
+    pub tasks: usize,
+    pub notes: usize,
+}
+
+/// One milestone's tasks, by status name
+type MilestoneGroup<'a> = (Option<&'a Milestone>, Vec<(&'a str, Vec<&'a Task>)>);
+
+/// Tasks by milestone (earliest due first, then no milestone), then by status in project order
+fn group_tasks(site: &Site) -> Vec<MilestoneGroup<'_>> {
+    let mut milestones: Vec<Option<&Milestone>> = site.milestones.iter().map(Some).collect();
+    milestones.sort_by_key(|m| m.map(|m| (m.due_date.unwrap_or(u64::MAX), m.id)));
+    milestones.push(None);
+
+    let status_rank: HashMap<u64, usize> = site
+        .statuses
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.id, i))
+        .collect();
+    let mut groups = Vec::new();
+    for milestone in milestones {
+        let mut tasks: Vec<&Task> = site
+            .tasks
+            .iter()
+            .filter(|t| t.milestone.as_ref().map(|m| m.id) == milestone.map(|m| m.id))
+            .collect();
+        if tasks.is_empty() {
+            continue;
+        }
+        tasks.sort_by_key(|t| {
+            (
+                status_rank.get(&t.status.id).copied().unwrap_or(usize::MAX),
+                t.sort_order,
+                t.id,
+            )
+        });
+        let mut by_status: Vec<(&str, Vec<&Task>)> = Vec::new();
+        for task in tasks {
+            match by_status.last_mut() {
+                Some((name, list)) if *name == task.status.name => list.push(task),
+                _ => by_status.push((&task.status.name, vec![task])),
+            }
+        }
+        groups.push((milestone, by_status));
+    }
+    groups
+}
+
+fn milestone_title(milestone: Option<&Milestone>) -> String {
+    match milestone {
+        Some(m) => match m.due_date {
+            Some(due) => format!("{} (due {})", m.name, format_date(due)),
+            None => m.name.clone(),
+        },
+        None => "No milestone".to_string(),
+    }
+}
+
+fn task_meta(task: &Task) -> Vec<String> {
+    let mut meta = Vec::new();
+    if let Some(user) = &task.responsible_user {
+        meta.push(user.name.clone());
+    }
+    if let Some(due) = task.due_date {
+        meta.push(format!("due {}", format_date(due)));
+    }
+    if !task.tags.is_empty() {
+        let tags: Vec<&str> = task.tags.iter().map(|t| t.name.as_str()).collect();
+        meta.push(tags.join(", "));
+    }
+    meta
+}
+
+fn activity_line(activity: &Activity) -> String {
+    let who = activity
+        .user
+        .as_ref()
+        .map(|u| u.name.as_str())
+        .unwrap_or("someone");
+    format!(
+        "{} {}: {}",
+        format_date(activity.created_at),
+        who,
+        activity.action
+    )
+}
+
+/// Notes with their depth, parents before children, in sort order
+fn note_tree(notes: &[Note]) -> Vec<(usize, &Note)> {
+    fn walk<'a>(
+        notes: &'a [Note],
+        parent: Option<u64>,
+        depth: usize,
+        out: &mut Vec<(usize, &'a Note)>,
+    ) {
+        for note in notes.iter().filter(|n| n.parent == parent) {
+            out.push((depth, note));
+            walk(notes, Some(note.id), depth + 1, out);
+        }
+    }
+    let ids: std::collections::HashSet<u64> = notes.iter().map(|n| n.id).collect();
+    let mut out = Vec::new();
+    walk(notes, None, 0, &mut out);
+    // Notes under a parent that is not in the list are shown at the top level
+    for note in notes
+        .iter()
+        .filter(|n| n.parent.is_some_and(|p| !ids.contains(&p)))
+    {
+        out.push((0, note));
+        walk(notes, Some(note.id), 1, &mut out);
+    }
+    out
+}
+
+fn to_markdown(site: &Site) -> String {
+    let mut out = String::new();
+    let project = &site.project;
+    let _ = writeln!(out, "# {}\n", project.full_name);
+    if let Some(purpose) = project.purpose.as_deref().filter(|p| !p.trim().is_empty()) {
+        let _ = writeln!(out, "> {}\n", purpose.trim().replace('\n', "\n> "));
+    }
+    let _ = writeln!(out, "_Published {}_\n", format_date(site.published_at));
+
+    let _ = writeln!(out, "## Tasks\n");
+    for (milestone, statuses) in group_tasks(site) {
+        let _ = writeln!(out, "### {}\n", milestone_title(milestone));
+        for (status, tasks) in statuses {
+            let _ = writeln!(out, "#### {}\n", status);
+            for task in tasks {
+                let check = if task.status.is_closed { "x" } else { " " };
+                let meta = task_meta(task);
+                let meta = if meta.is_empty() {
+                    String::new()
+                } else {
+                    format!(" — {}", meta.join(" · "))
+                };
+                let _ = writeln!(out, "- [{}] {} (#{}){}", check, task.name, task.id, meta);
+            }
+            out.push('\n');
+        }
+    }
+
+    if !site.notes.is_empty() {
+        let _ = writeln!(out, "## Notes\n");
+        for (depth, note) in note_tree(&site.notes) {
+            let level = (depth + 3).min(6);
+            let _ = writeln!(out, "{} {}\n", "#".repeat(level), note.name);
+            if let Some(body) = note.description.as_deref().filter(|b| !b.trim().is_empty()) {
+                let _ = writeln!(out, "{}\n", body.trim_end());
+            }
+        }
+    }
+
+    if !site.activity.is_empty() {
+        let _ = writeln!(out, "## Recent Activity\n");
+        for activity in &site.activity {
+            let _ = writeln!(out, "- {}", activity_line(activity));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn note_file(note: &Note) -> String {
+    format!("note-{}.html", note.id)
+}
+
+fn page(site: &Site, title: &str, content: &str) -> String {
+    let mut nav = String::new();
+    let _ = write!(
+        nav,
+        "<p><strong>{}</strong></p><ul><li><a href=\"index.html\">Overview</a></li>\
+         <li><a href=\"tasks.html\">Tasks</a></li></ul>",
+        escape(&site.project.full_name)
+    );
+    if !site.notes.is_empty() {
+        nav.push_str("<p>Notes</p>");
+        let mut depth = 0;
+        nav.push_str("<ul>");
+        for (level, note) in note_tree(&site.notes) {
+            while depth < level {
+                nav.push_str("<ul>");
+                depth += 1;
+            }
+            while depth > level {
+                nav.push_str("</ul>");
+                depth -= 1;
+            }
+            let _ = write!(
+                nav,
+                "<li><a href=\"{}\">{}</a></li>",
+                note_file(note),
+                escape(&note.name)
+            );
+        }
+        nav.push_str(&"</ul>".repeat(depth + 1));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>{} - {}</title><style>{}</style></head>\n\
+         <body><nav>{}</nav><main>{}</main></body></html>\n",
+        escape(title),
+        escape(&site.project.full_name),
+        STYLE,
+        nav,
+        content
+    )
+}
+
+/// File name and contents of every page
+fn to_html(site: &Site) -> Vec<(String, String)> {
+    let mut pages = Vec::new();
+    let project = &site.project;
+
+    let mut index = format!("<h1>{}</h1>", escape(&project.full_name));
+    if let Some(purpose) = project.purpose.as_deref().filter(|p| !p.trim().is_empty()) {
+        index.push_str(&markdown::to_html(purpose));
+    }
+    let open = site.tasks.iter().filter(|t| !t.status.is_closed).count();
+    let _ = write!(
+        index,
+        "<p class=\"meta\">Published {} · {} open of {} task(s) · {} note(s)</p>",
+        format_date(site.published_at),
+        open,
+        site.tasks.len(),
+        site.notes.len()
+    );
+    if !site.activity.is_empty() {
+        index.push_str("<h2>Recent Activity</h2><ul>");
+        for activity in &site.activity {
+            let _ = write!(index, "<li>{}</li>", escape(&activity_line(activity)));
+        }
+        index.push_str("</ul>");
+    }
+    pages.push(("index.html".to_string(), page(site, "Overview", &index)));
+
+    let mut tasks = "<h1>Tasks</h1>".to_string();
+    for (milestone, statuses) in group_tasks(site) {
+        let _ = write!(tasks, "<h2>{}</h2>", escape(&milestone_title(milestone)));
+        for (status, list) in statuses {
+            let _ = write!(tasks, "<h3>{}</h3>", escape(status));
+            for task in list {
+                let meta = task_meta(task);
+                let meta = if meta.is_empty() {
+                    String::new()
+                } else {
+                    format!(" <span class=\"meta\">{}</span>", escape(&meta.join(" · ")))
+                };
+                let summary = format!(
+                    "{} <span class=\"meta\">#{}</span>{}",
+                    escape(&task.name),
+                    task.id,
+                    meta
+                );
+                match task.description.as_deref().filter(|d| !d.trim().is_empty()) {
+                    Some(description) => {
+                        let _ = write!(
+                            tasks,
+                            "<details><summary>{}</summary>{}</details>",
+                            summary,
+                            markdown::to_html(description)
+                        );
+                    }
+                    None => {
+                        let _ = write!(tasks, "<p>{}</p>", summary);
+                    }
+                }
+            }
+        }
+    }
+    pages.push(("tasks.html".to_string(), page(site, "Tasks", &tasks)));
+
+    for note in &site.notes {
+        let content = format!(
+            "<h1>{}</h1><p class=\"meta\">Updated {}</p>{}",
+            escape(&note.name),
+            format_date(note.updated_at),
+            markdown::to_html(note.description.as_deref().unwrap_or_default())
+        );
+        pages.push((note_file(note), page(site, &note.name, &content)));
+    }
+    pages
+}
+
+async fn load(client: &RepsonaClient, project_id: u64, days: u64) -> Result<Site> {
+    let filter = TaskFilter::default();
+    let (project, statuses, milestones, tasks, notes, activity) = tokio::try_join!(
+        client.get_project(project_id),
+        client.list_project_statuses(project_id),
+        client.list_project_milestones(project_id),
+        client.list_tasks(project_id, &filter),
+        client.list_notes(project_id),
+        client.get_project_activity(project_id),
+    )?;
+    let mut notes: Vec<Note> = stream::iter(notes.data.notes)
+        .map(|note| async move {
+            client
+                .get_note(project_id, note.id)
+                .await
+                .map(|r| r.data.note)
+                .with_context(|| format!("Failed to load note {}", note.id))
+        })
+        .buffered(CONCURRENCY)
+        .try_collect()
+        .await?;
+    notes.sort_by_key(|n| (n.sort_order, n.id));
+
+    let published_at = chrono::Utc::now().timestamp().max(0) as u64;
+    let since = published_at.saturating_sub(days * 86_400);
+    let mut activity: Vec<Activity> = activity
+        .data
+        .activity
+        .into_iter()
+        .filter(|a| a.created_at >= since)
+        .collect();
+    activity.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+
+    Ok(Site {
+        project: project.data.project,
+        statuses: statuses.data.statuses,
+        milestones: milestones.data.milestones,
+        tasks: tasks.data.tasks,
+        notes,
+        activity,
+        published_at,
+    })
+}
+
+pub async fn handle(client: &RepsonaClient, options: PublishOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let site = telemetry_span::with_span_async_result("load_project", &exec_attrs, || {
+        load(client, options.project_id, options.days)
+    })
+    .await?;
+
+    let files = telemetry_span::with_span_result("write_files", &exec_attrs, || {
+        let out = &options.out;
+        match options.format {
+            PublishFormat::Markdown => {
+                if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(out, to_markdown(&site))
+                    .with_context(|| format!("Failed to write {}", out.display()))?;
+                Ok::<_, anyhow::Error>(1)
+            }
+            PublishFormat::Html => {
+                std::fs::create_dir_all(out)
+                    .with_context(|| format!("Failed to create {}", out.display()))?;
+                let pages = to_html(&site);
+                for (name, html) in &pages {
+                    let path = out.join(name);
+                    std::fs::write(&path, html)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                }
+                Ok(pages.len())
+            }
+        }
+    })?;
+
+    let report = PublishReport {
+        project_id: options.project_id,
+        out: options.out.display().to_string(),
+        files,
+        tasks: site.tasks.len(),
+        notes: site.notes.len(),
+    };
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&report, format),
+        OutputFormat::Human => {
+            print_success(&format!(
+                "Published '{}' to {} ({} task(s), {} note(s))",
+                site.project.full_name, report.out, report.tasks, report.notes
+            ));
+            Ok(())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn status(id: u64, name: &str, is_closed: bool) -> Status {
+        Status {
+            id,
+            name: name.to_string(),
+            is_closed,
+            color: None,
+        }
+    }
+
+    fn task(id: u64, name: &str, status: Status, milestone: Option<Milestone>) -> Task {
+        Task {
+            status,
+            priority: 3,
+            milestone,
+            ..test_support::task(id).named(name).in_project(1, "web")
+        }
+    }
+
+    fn note(id: u64, name: &str, parent: Option<u64>) -> Note {
+        Note {
+            id,
+            name: name.to_string(),
+            description: Some(format!("About <{}>", name)),
+            tags: Vec::new(),
+            parent,
+            project: ProjectSummary {
+                id: 1,
+                name: "web".to_string(),
+            },
+            files: Vec::new(),
+            sort_order: 0,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn site() -> Site {
+        let beta = Milestone {
+            id: 9,
+            name: "Beta".to_string(),
+            due_date: None,
+            is_closed: false,
+        };
+        let (open, done) = (status(1, "Open", false), status(2, "Done", true));
+        Site {
+            project: serde_json::from_value(serde_json::json!({
+                "id": 1, "name": "web", "fullName": "Web Site", "purpose": "Ship it",
+                "isClosed": false, "isPublic": false, "createdAt": 0, "updatedAt": 0
+            }))
+            .unwrap(),
+            statuses: vec![open.clone(), done.clone()],
+            milestones: vec![beta.clone()],
+            tasks: vec![
+                task(1, "Loose end", open.clone(), None),
+                task(2, "Launch", done, Some(beta.clone())),
+                task(3, "Polish", open, Some(beta)),
+            ],
+            notes: vec![note(10, "Guide", None), note(11, "Install", Some(10))],
+            activity: Vec::new(),
+            published_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_markdown_groups_tasks_and_nests_notes() {
+        let md = to_markdown(&site());
+        let expected =
+            "### Beta\n\n#### Open\n\n- [ ] Polish (#3)\n\n#### Done\n\n- [x] Launch (#2)\n\n\
+                        ### No milestone\n\n#### Open\n\n- [ ] Loose end (#1)\n";
+        assert!(md.contains(expected), "{}", md);
+        assert!(md.contains("### Guide\n\nAbout <Guide>\n\n#### Install\n"));
+    }
+
+    #[test]
+    fn test_html_site_links_every_note() {
+        let pages = to_html(&site());
+        let names: Vec<&str> = pages.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["index.html", "tasks.html", "note-10.html", "note-11.html"]
+        );
+        let (_, guide) = &pages[2];
+        assert!(guide.contains("<a href=\"note-11.html\">Install</a>"));
+        assert!(guide.contains("About &lt;Guide&gt;"));
+    }
+}
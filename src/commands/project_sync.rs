@@ -0,0 +1,705 @@
+use crate::api::{
+    endpoints::{idlink::CreateIdLinkRequest, project::UpdateProjectRequest, webhook::*},
+    types::*,
+    RepsonaClient,
+};
+use crate::commands::board::matches_ref;
+use crate::output::{confirm, print, print_success, OutputFormat};
+use crate::telemetry_span;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "project".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+/// A project declared in `project.toml`. Sections left out are not managed.
+///
+/// Statuses, milestones and tags have no write endpoints, so they are only compared
+/// and reported. Tags, webhooks and ID links belong to the whole space rather than the project,
+/// so `--prune` leaves them alone: a file only ever adds or updates them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ProjectConfig {
+    /// Project to sync when no project ID is given on the command line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    /// User names, emails or IDs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statuses: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub milestones: Option<Vec<String>>,
+    /// Space-wide tags the project relies on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Space-wide webhooks, matched by name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhooks: Option<Vec<WebhookConfig>>,
+    /// Space-wide ID links, matched by name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idlinks: Option<Vec<IdLinkConfig>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdLinkConfig {
+    pub name: String,
+    pub url: String,
+}
+
+pub struct SyncOptions {
+    pub project_id: Option<u64>,
+    pub file: PathBuf,
+    /// Also remove members missing from the file
+    pub prune: bool,
+    /// Apply without asking (`--yes`)
+    pub yes: bool,
+}
+
+/// Live state of the sections the file manages
+#[derive(Debug, Default)]
+struct Live {
+    project: Option<Project>,
+    users: Vec<User>,
+    members: Vec<User>,
+    statuses: Vec<Status>,
+    milestones: Vec<Milestone>,
+    tags: Vec<Tag>,
+    webhooks: Vec<Webhook>,
+    idlinks: Vec<IdLink>,
+}
+
+#[derive(Debug)]
+enum Step {
+    UpdateProject(UpdateProjectRequest, Vec<String>),
+    AddMember(User),
+    RemoveMember(User),
+    CreateWebhook(CreateWebhookRequest),
+    UpdateWebhook(Webhook, UpdateWebhookRequest),
+    CreateIdLink(CreateIdLinkRequest),
+    DeleteIdLink(IdLink),
+}
+
+/// A difference in a section the API cannot change
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Drift {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub name: String,
+    /// `missing` from the project, or `extra` in it (statuses and milestones, with `--prune`)
+    pub state: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncReport {
+    pub project_id: u64,
+    pub changes: Vec<String>,
+    pub applied: usize,
+    pub drift: Vec<Drift>,
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Step::UpdateProject(_, fields) => format!("update project: {}", fields.join(", ")),
+            Step::AddMember(user) => format!("add member {}", user.name),
+            Step::RemoveMember(user) => format!("remove member {}", user.name),
+            Step::CreateWebhook(hook) => format!("create webhook {} ({})", hook.name, hook.url),
+            Step::UpdateWebhook(hook, _) => format!("update webhook {}", hook.name),
+            Step::CreateIdLink(link) => format!("create idlink {} ({})", link.name, link.url),
+            Step::DeleteIdLink(link) => format!("delete idlink {}", link.name),
+        }
+    }
+
+    fn mark(&self) -> colored::ColoredString {
+        match self {
+            Step::AddMember(_) | Step::CreateWebhook(_) | Step::CreateIdLink(_) => "+".green(),
+            Step::UpdateProject(..) | Step::UpdateWebhook(..) => "~".yellow(),
+            Step::RemoveMember(_) | Step::DeleteIdLink(_) => "-".red(),
+        }
+    }
+}
+
+pub async fn handle_sync(client: &RepsonaClient, options: SyncOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let validate_attrs = phase_attrs("validate_input");
+    let config = telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+        let text = std::fs::read_to_string(&options.file)
+            .with_context(|| format!("Failed to read {}", options.file.display()))?;
+        toml::from_str::<ProjectConfig>(&text)
+            .with_context(|| format!("Invalid project file {}", options.file.display()))
+    })?;
+    let Some(project_id) = options.project_id.or(config.id) else {
+        bail!("No project ID: pass one or set `id` in the file");
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let live = telemetry_span::with_span_async_result("load_live_state", &exec_attrs, || {
+        load_live(client, project_id, &config)
+    })
+    .await?;
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let (steps, drift) =
+        telemetry_span::with_span_result("prepare_request", &prepare_attrs, || {
+            diff(&config, &live, options.prune)
+        })?;
+
+    let render_attrs = phase_attrs("render_output");
+    if !json {
+        telemetry_span::with_span("render_output", &render_attrs, || {
+            print_plan(project_id, &steps, &drift)
+        });
+    }
+
+    let mut applied = 0;
+    if !steps.is_empty() {
+        if !options.yes {
+            if json {
+                bail!("Pass --yes to sync a project with --json");
+            }
+            let proceed =
+                telemetry_span::with_span_result("interactive_confirm", &validate_attrs, || {
+                    confirm(&format!("Apply {} change(s)? [y/N]: ", steps.len()))
+                })?;
+            if !proceed {
+                eprintln!("{}", "Cancelled".yellow());
+                return Ok(());
+            }
+        }
+        for step in &steps {
+            telemetry_span::with_span_async_result("apply_change", &exec_attrs, || {
+                execute(client, project_id, step)
+            })
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to {} ({} of {} change(s) applied)",
+                    step.describe(),
+                    applied,
+                    steps.len()
+                )
+            })?;
+            applied += 1;
+        }
+    }
+
+    let report = SyncReport {
+        project_id,
+        changes: steps.iter().map(Step::describe).collect(),
+        applied,
+        drift,
+    };
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&report, format),
+        OutputFormat::Human => {
+            if report.applied > 0 {
+                print_success(&format!(
+                    "Project {} synced: {} change(s) applied",
+                    project_id, report.applied
+                ));
+            }
+            Ok(())
+        }
+    })
+}
+
+/// Write the live project as a file for `project sync`.
+///
+/// Tags, webhooks and ID links belong to the whole space, and webhook URLs often carry secrets,
+/// so they are only written when `include_space` is set.
+pub async fn handle_export(
+    client: &RepsonaClient,
+    project_id: u64,
+    output: Option<PathBuf>,
+    include_space: bool,
+) -> Result<()> {
+    let everything = ProjectConfig {
+        members: Some(Vec::new()),
+        statuses: Some(Vec::new()),
+        milestones: Some(Vec::new()),
+        tags: include_space.then(Vec::new),
+        webhooks: include_space.then(Vec::new),
+        idlinks: include_space.then(Vec::new),
+        ..Default::default()
+    };
+    let exec_attrs = phase_attrs("execute_operation");
+    let live = telemetry_span::with_span_async_result("load_live_state", &exec_attrs, || {
+        load_live(client, project_id, &everything)
+    })
+    .await?;
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || {
+        let text = toml::to_string_pretty(&export(project_id, &live, include_space))?;
+        match &output {
+            Some(path) => {
+                std::fs::write(path, &text)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                print_success(&format!("Wrote {}", path.display()));
+            }
+            None => print!("{}", text),
+        }
+        Ok(())
+    })
+}
+
+/// Fetch the project and every section `config` manages
+async fn load_live(
+    client: &RepsonaClient,
+    project_id: u64,
+    config: &ProjectConfig,
+) -> Result<Live> {
+    let mut live = Live {
+        project: Some(client.get_project(project_id).await?.data.project),
+        ..Default::default()
+    };
+    if config.members.is_some() {
+        let (users, members) =
+            tokio::join!(client.list_users(), client.list_project_members(project_id));
+        live.users = users?.data.users;
+        live.members = members?.data.users;
+    }
+    if config.statuses.is_some() {
+        live.statuses = client
+            .list_project_statuses(project_id)
+            .await?
+            .data
+            .statuses;
+    }
+    if config.milestones.is_some() {
+        live.milestones = client
+            .list_project_milestones(project_id)
+            .await?
+            .data
+            .milestones;
+    }
+    if config.tags.is_some() {
+        live.tags = client.list_tags().await?.data.tags;
+    }
+    if config.webhooks.is_some() {
+        live.webhooks = client.list_webhooks().await?.data.webhooks;
+    }
+    if config.idlinks.is_some() {
+        live.idlinks = client.list_idlinks().await?.data.idlinks;
+    }
+    Ok(live)
+}
+
+fn export(project_id: u64, live: &Live, include_space: bool) -> ProjectConfig {
+    let project = live.project.as_ref();
+    let config = ProjectConfig {
+        id: Some(project_id),
+        name: project.map(|p| p.name.clone()),
+        full_name: project.map(|p| p.full_name.clone()),
+        purpose: project.and_then(|p| p.purpose.clone()),
+        members: Some(live.members.iter().map(|u| u.name.clone()).collect()),
+        statuses: Some(live.statuses.iter().map(|s| s.name.clone()).collect()),
+        milestones: Some(live.milestones.iter().map(|m| m.name.clone()).collect()),
+        tags: Some(live.tags.iter().map(|t| t.name.clone()).collect()),
+        webhooks: Some(
+            live.webhooks
+                .iter()
+                .map(|w| WebhookConfig {
+                    name: w.name.clone(),
+                    url: w.url.clone(),
+                    events: w.events.clone(),
+                })
+                .collect(),
+        ),
+        idlinks: Some(
+            live.idlinks
+                .iter()
+                .map(|l| IdLinkConfig {
+                    name: l.name.clone(),
+                    url: l.url.clone(),
+                })
+                .collect(),
+        ),
+    };
+    if include_space {
+        config
+    } else {
+        ProjectConfig {
+            tags: None,
+            webhooks: None,
+            idlinks: None,
+            ..config
+        }
+    }
+}
+
+/// Steps that make the live state match `config`, and differences no step can fix
+fn diff(config: &ProjectConfig, live: &Live, prune: bool) -> Result<(Vec<Step>, Vec<Drift>)> {
+    let mut steps = Vec::new();
+    let mut drift = Vec::new();
+
+    if let Some(project) = &live.project {
+        let mut request = UpdateProjectRequest::default();
+        let mut fields = Vec::new();
+        let mut set = |field: &str, want: &Option<String>, have: Option<&str>| {
+            want.as_ref().filter(|w| Some(w.as_str()) != have).map(|w| {
+                fields.push(format!("{} {:?} → {:?}", field, have.unwrap_or(""), w));
+                w.clone()
+            })
+        };
+        request.name = set("name", &config.name, Some(&project.name));
+        request.full_name = set("full-name", &config.full_name, Some(&project.full_name));
+        request.purpose = set("purpose", &config.purpose, project.purpose.as_deref());
+        if !fields.is_empty() {
+            steps.push(Step::UpdateProject(request, fields));
+        }
+    }
+
+    if let Some(members) = &config.members {
+        let mut wanted = Vec::new();
+        for member in members {
+            let user = live
+                .users
+                .iter()
+                .find(|u| matches_ref(member, u.id, &[&u.name, &u.full_name, &u.email]))
+                .with_context(|| format!("User '{}' not found in this space", member))?;
+            wanted.push(user.id);
+            if !live.members.iter().any(|m| m.id == user.id) {
+                steps.push(Step::AddMember(user.clone()));
+            }
+        }
+        if prune {
+            for member in live.members.iter().filter(|m| !wanted.contains(&m.id)) {
+                steps.push(Step::RemoveMember(member.clone()));
+            }
+        }
+    }
+
+    let names =
+        |items: &[(u64, &str)], wanted: &Option<Vec<String>>, kind: &'static str, prune: bool| {
+            let Some(wanted) = wanted else {
+                return Vec::new();
+            };
+            let mut found = Vec::new();
+            let mut drift: Vec<Drift> = Vec::new();
+            for name in wanted {
+                match items.iter().find(|(id, n)| matches_ref(name, *id, &[n])) {
+                    Some((id, _)) => found.push(*id),
+                    None => drift.push(Drift {
+                        kind,
+                        name: name.clone(),
+                        state: "missing",
+                    }),
+                }
+            }
+            if prune {
+                drift.extend(
+                    items
+                        .iter()
+                        .filter(|(id, _)| !found.contains(id))
+                        .map(|(_, n)| Drift {
+                            kind,
+                            name: n.to_string(),
+                            state: "extra",
+                        }),
+                );
+            }
+            drift
+        };
+    let statuses: Vec<(u64, &str)> = live
+        .statuses
+        .iter()
+        .map(|s| (s.id, s.name.as_str()))
+        .collect();
+    let milestones: Vec<(u64, &str)> = live
+        .milestones
+        .iter()
+        .map(|m| (m.id, m.name.as_str()))
+        .collect();
+    let tags: Vec<(u64, &str)> = live.tags.iter().map(|t| (t.id, t.name.as_str())).collect();
+    drift.extend(names(&statuses, &config.statuses, "status", prune));
+    drift.extend(names(&milestones, &config.milestones, "milestone", prune));
+    // Other projects' tags are not extra
+    drift.extend(names(&tags, &config.tags, "tag", false));
+
+    if let Some(webhooks) = &config.webhooks {
+        for hook in webhooks {
+            match live.webhooks.iter().find(|w| w.name == hook.name) {
+                None => steps.push(Step::CreateWebhook(CreateWebhookRequest {
+                    name: hook.name.clone(),
+                    url: hook.url.clone(),
+                    events: hook.events.clone(),
+                })),
+                Some(current) => {
+                    let mut request = UpdateWebhookRequest::default();
+                    if current.url != hook.url {
+                        request.url = Some(hook.url.clone());
+                    }
+                    if sorted(&current.events) != sorted(&hook.events) {
+                        request.events = Some(hook.events.clone());
+                    }
+                    if request.url.is_some() || request.events.is_some() {
+                        steps.push(Step::UpdateWebhook(current.clone(), request));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(idlinks) = &config.idlinks {
+        for link in idlinks {
+            let current = live.idlinks.iter().find(|l| l.name == link.name);
+            if current.is_some_and(|c| c.url == link.url) {
+                continue;
+            }
+            // ID links cannot be edited, so a changed URL replaces the link
+            if let Some(current) = current {
+                steps.push(Step::DeleteIdLink(current.clone()));
+            }
+            steps.push(Step::CreateIdLink(CreateIdLinkRequest {
+                name: link.name.clone(),
+                url: link.url.clone(),
+            }));
+        }
+    }
+
+    Ok((steps, drift))
+}
+
+fn sorted(events: &[String]) -> Vec<&str> {
+    let mut events: Vec<&str> = events.iter().map(String::as_str).collect();
+    events.sort_unstable();
+    events
+}
+
+fn print_plan(project_id: u64, steps: &[Step], drift: &[Drift]) {
+    if steps.is_empty() && drift.is_empty() {
+        println!("{}", format!("Project {} is in sync", project_id).green());
+    } else if steps.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Project {}: no changes to apply; {} difference(s) need manual action",
+                project_id,
+                drift.len()
+            )
+            .yellow()
+        );
+    } else {
+        eprintln!(
+            "\n{}",
+            format!("Project {}: {} change(s)", project_id, steps.len()).bold()
+        );
+        for step in steps {
+            eprintln!("  {} {}", step.mark(), step.describe());
+        }
+        eprintln!();
+    }
+    if !drift.is_empty() {
+        eprintln!(
+            "{}",
+            "Not changed (the API cannot create or delete these; use the Repsona web app):"
+                .yellow()
+        );
+        for d in drift {
+            eprintln!("  {} {} {}", "!".yellow(), d.kind, d.name);
+            eprintln!("      {}", d.state.dimmed());
+        }
+    }
+}
+
+async fn execute(client: &RepsonaClient, project_id: u64, step: &Step) -> Result<()> {
+    match step {
+        Step::UpdateProject(request, _) => {
+            client.update_project(project_id, request).await?;
+        }
+        Step::AddMember(user) => {
+            client.add_project_member(project_id, user.id).await?;
+        }
+        Step::RemoveMember(user) => {
+            client.remove_project_member(project_id, user.id).await?;
+        }
+        Step::CreateWebhook(request) => {
+            client.create_webhook(request).await?;
+        }
+        Step::UpdateWebhook(hook, request) => {
+            client.update_webhook(hook.id, request).await?;
+        }
+        Step::CreateIdLink(request) => {
+            client.create_idlink(request).await?;
+        }
+        Step::DeleteIdLink(link) => client.delete_idlink(link.id).await?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: u64, name: &str) -> User {
+        User {
+            id,
+            email: format!("{}@example.com", name),
+            name: name.to_string(),
+            full_name: name.to_uppercase(),
+            avatar_url: None,
+            role: "member".to_string(),
+            billing_status: "active".to_string(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn live() -> Live {
+        Live {
+            project: None,
+            users: vec![user(1, "alice"), user(2, "bob"), user(3, "carol")],
+            members: vec![user(1, "alice"), user(3, "carol")],
+            statuses: vec![Status {
+                id: 10,
+                name: "Todo".to_string(),
+                is_closed: false,
+                color: None,
+            }],
+            webhooks: vec![Webhook {
+                id: 5,
+                name: "ci".to_string(),
+                url: "https://ci/old".to_string(),
+                events: vec!["task.created".to_string()],
+                active: true,
+            }],
+            idlinks: vec![IdLink {
+                id: 8,
+                name: "JIRA".to_string(),
+                url: "https://jira/{id}".to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn described(steps: &[Step]) -> Vec<String> {
+        steps.iter().map(Step::describe).collect()
+    }
+
+    #[test]
+    fn test_diff_adds_and_prunes() {
+        let config: ProjectConfig = toml::from_str(
+            r#"
+members = ["alice", "bob@example.com"]
+statuses = ["todo", "Review"]
+
+[[webhooks]]
+name = "ci"
+url = "https://ci/new"
+events = ["task.created"]
+
+[[idlinks]]
+name = "JIRA"
+url = "https://jira/{id}"
+"#,
+        )
+        .unwrap();
+
+        let (steps, drift) = diff(&config, &live(), false).unwrap();
+        assert_eq!(
+            described(&steps),
+            vec!["add member bob", "update webhook ci"]
+        );
+        assert_eq!(
+            drift,
+            vec![Drift {
+                kind: "status",
+                name: "Review".to_string(),
+                state: "missing"
+            }]
+        );
+
+        let (steps, _) = diff(&config, &live(), true).unwrap();
+        assert_eq!(
+            described(&steps),
+            vec!["add member bob", "remove member carol", "update webhook ci"]
+        );
+    }
+
+    #[test]
+    fn test_prune_leaves_space_wide_sections_alone() {
+        let config = ProjectConfig {
+            tags: Some(Vec::new()),
+            webhooks: Some(Vec::new()),
+            idlinks: Some(Vec::new()),
+            ..Default::default()
+        };
+        let mut live = live();
+        live.tags = vec![Tag {
+            id: 4,
+            name: "other-project".to_string(),
+            color: "red".to_string(),
+        }];
+        let (steps, drift) = diff(&config, &live, true).unwrap();
+        assert!(steps.is_empty(), "{:?}", described(&steps));
+        assert!(drift.is_empty());
+    }
+
+    #[test]
+    fn test_diff_replaces_changed_idlink_and_rejects_unknown_user() {
+        let config = ProjectConfig {
+            idlinks: Some(vec![IdLinkConfig {
+                name: "JIRA".to_string(),
+                url: "https://jira/browse/{id}".to_string(),
+            }]),
+            ..Default::default()
+        };
+        let (steps, drift) = diff(&config, &live(), true).unwrap();
+        assert_eq!(
+            described(&steps),
+            vec![
+                "delete idlink JIRA",
+                "create idlink JIRA (https://jira/browse/{id})"
+            ]
+        );
+        assert!(drift.is_empty());
+
+        let unknown = ProjectConfig {
+            members: Some(vec!["mallory".to_string()]),
+            ..Default::default()
+        };
+        assert!(diff(&unknown, &live(), false).is_err());
+    }
+
+    #[test]
+    fn test_export_round_trips_to_no_changes() {
+        let live = live();
+        assert!(export(12, &live, false).webhooks.is_none());
+        let config = export(12, &live, true);
+        let text = toml::to_string_pretty(&config).unwrap();
+        let parsed: ProjectConfig = toml::from_str(&text).unwrap();
+        assert_eq!(parsed, config);
+        let (steps, drift) = diff(&parsed, &live, true).unwrap();
+        assert!(steps.is_empty(), "{:?}", described(&steps));
+        assert!(drift.is_empty());
+    }
+}
@@ -18,6 +18,10 @@ pub async fn handle(cmd: ReportCommands) -> Result<()> {
         } => handle_generate(error, command, output).await,
         ReportCommands::Test => handle_test().await,
         ReportCommands::Info => handle_info().await,
+        // These need an API client, so they are dispatched with the other API commands
+        ReportCommands::Digest { .. } | ReportCommands::Flow { .. } => {
+            Err(anyhow::anyhow!("this report requires a client"))
+        }
     }
 }
 
@@ -0,0 +1,636 @@
+use crate::api::{types::*, RepsonaClient};
+use crate::cli::DigestFormat;
+use crate::commands::calendar::local_date;
+use crate::commands::task_query::find_tasks;
+use crate::output::{format_date, print, print_success, OutputFormat};
+use crate::query::{parse_date, start_of_day, TaskQuery};
+use crate::telemetry_span;
+use anyhow::{Context, Result};
+use chrono::{Duration, Local, NaiveDate};
+use colored::Colorize;
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+const CONCURRENCY: usize = 4;
+const STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,\"Segoe UI\",sans-serif;\
+    max-width:760px;margin:2rem auto;padding:0 1rem;color:#222;line-height:1.5}\
+    table{border-collapse:collapse}th,td{border:1px solid #ccc;padding:.3rem .7rem;text-align:center}\
+    h2{border-bottom:1px solid #eee;padding-bottom:.2rem}";
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "report".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct DigestOptions {
+    pub projects: Vec<u64>,
+    pub user: Option<String>,
+    pub since: String,
+    pub upcoming: u64,
+    pub format: DigestFormat,
+    pub out: Option<PathBuf>,
+}
+
+/// A task as it appears in the digest; `at` is when the reported event happened
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestTask {
+    pub id: u64,
+    pub project_id: u64,
+    pub project: String,
+    pub name: String,
+    pub assignee: Option<String>,
+    pub due_date: Option<u64>,
+    pub at: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Discussion {
+    pub task: DigestTask,
+    pub comments: usize,
+    pub authors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectActivity {
+    pub project_id: u64,
+    pub project: String,
+    pub events: usize,
+    pub people: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Digest {
+    pub scope: String,
+    pub since: u64,
+    pub until: u64,
+    pub created: Vec<DigestTask>,
+    pub completed: Vec<DigestTask>,
+    pub reopened: Vec<DigestTask>,
+    pub discussions: Vec<Discussion>,
+    pub overdue: Vec<DigestTask>,
+    pub upcoming: Vec<DigestTask>,
+    pub activity: Vec<ProjectActivity>,
+}
+
+/// Everything fetched for a digest, keyed by task ID where per-task
+struct Loaded {
+    tasks: Vec<Task>,
+    /// Closed status names per project, to tell completions from other status changes
+    closed: HashMap<u64, HashSet<String>>,
+    /// Missing for tasks whose history could not be loaded
+    histories: HashMap<u64, Vec<History>>,
+    comments: HashMap<u64, Vec<TaskComment>>,
+    activity: Vec<(Project, Vec<Activity>)>,
+}
+
+pub async fn handle(client: &RepsonaClient, options: DigestOptions, json: bool) -> Result<()> {
+    let format = if json {
+        DigestFormat::Json
+    } else {
+        options.format
+    };
+    let today = Local::now().date_naive();
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let since = telemetry_span::with_span_result("prepare_request", &prepare_attrs, || {
+        parse_date(&options.since, today)
+    })?;
+    let query = TaskQuery {
+        projects: options.projects.clone(),
+        assignee: options.user.clone(),
+        include_closed: true,
+        ..Default::default()
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let loaded = telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || {
+        load(client, &query, since)
+    })
+    .await?;
+
+    let scope = match &options.user {
+        Some(user) => user.clone(),
+        None => {
+            let mut names: Vec<String> = loaded
+                .tasks
+                .iter()
+                .map(|t| t.project.name.clone())
+                .collect();
+            names.extend(loaded.activity.iter().map(|(p, _)| p.name.clone()));
+            names.sort();
+            names.dedup();
+            names.join(", ")
+        }
+    };
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let digest = summarize(&loaded, scope, since, now, today, options.upcoming);
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || {
+        let text = match format {
+            DigestFormat::Json => {
+                if options.out.is_none() {
+                    return print(&digest, OutputFormat::Json);
+                }
+                serde_json::to_string_pretty(&digest)? + "\n"
+            }
+            DigestFormat::Markdown => to_markdown(&digest, today),
+            DigestFormat::Html => to_html(&digest, today),
+        };
+        match &options.out {
+            Some(path) => {
+                std::fs::write(path, text)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                print_success(&format!("Digest written to {}", path.display()));
+            }
+            None => print!("{}", text),
+        }
+        Ok(())
+    })
+}
+
+async fn load(client: &RepsonaClient, query: &TaskQuery, since: u64) -> Result<Loaded> {
+    let tasks = find_tasks(client, query, CONCURRENCY).await?;
+
+    let mut project_ids: Vec<u64> = tasks.iter().map(|t| t.project.id).collect();
+    project_ids.sort_unstable();
+    project_ids.dedup();
+    let statuses = stream::iter(project_ids)
+        .map(|id| async move { (id, client.list_project_statuses(id).await) })
+        .buffer_unordered(CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+    let mut closed: HashMap<u64, HashSet<String>> = HashMap::new();
+    let mut failed_projects = 0;
+    for (id, result) in statuses {
+        match result {
+            Ok(response) => {
+                let names = response
+                    .data
+                    .statuses
+                    .into_iter()
+                    .filter(|s| s.is_closed)
+                    .map(|s| s.name)
+                    .collect();
+                closed.insert(id, names);
+            }
+            Err(_) => failed_projects += 1,
+        }
+    }
+    if failed_projects > 0 {
+        eprintln!(
+            "{}",
+            format!(
+                "Could not load the statuses of {} project(s); counting their tasks as completed if closed",
+                failed_projects
+            )
+            .yellow()
+        );
+    }
+
+    // Only tasks touched in the period can have history or comments in it
+    let touched: Vec<&Task> = tasks.iter().filter(|t| t.updated_at >= since).collect();
+    let details = stream::iter(touched)
+        .map(|task| async move {
+            let (history, comments) = tokio::join!(
+                client.get_task_history(task.project.id, task.id),
+                client.list_task_comments(task.project.id, task.id)
+            );
+            (
+                task.id,
+                history.map(|r| r.data.history),
+                comments.map(|r| r.data.task_comments),
+            )
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+    let mut histories = HashMap::new();
+    let mut comments = HashMap::new();
+    let mut failed = 0;
+    for (id, history, task_comments) in details {
+        match history {
+            Ok(history) => {
+                histories.insert(id, history);
+            }
+            Err(_) => failed += 1,
+        }
+        if let Ok(task_comments) = task_comments {
+            comments.insert(id, task_comments);
+        }
+    }
+    if failed > 0 {
+        eprintln!(
+            "{}",
+            format!(
+                "Could not load the history of {} task(s); counting them as completed if closed",
+                failed
+            )
+            .yellow()
+        );
+    }
+
+    // Project activity only makes sense when the digest is about projects, not a person
+    let mut activity = Vec::new();
+    if query.assignee.is_none() {
+        let projects: Vec<Project> = client
+            .list_projects()
+            .await?
+            .data
+            .projects
+            .into_iter()
+            .filter(|p| {
+                if query.projects.is_empty() {
+                    !p.is_closed
+                } else {
+                    query.projects.contains(&p.id)
+                }
+            })
+            .collect();
+        activity = stream::iter(projects)
+            .map(|project| async move {
+                let result = client.get_project_activity(project.id).await;
+                (project, result)
+            })
+            .buffered(CONCURRENCY)
+            .filter_map(
+                |(project, result)| async move { Some((project, result.ok()?.data.activity)) },
+            )
+            .collect()
+            .await;
+    }
+
+    Ok(Loaded {
+        tasks,
+        closed,
+        histories,
+        comments,
+        activity,
+    })
+}
+
+fn digest_task(task: &Task, at: Option<u64>) -> DigestTask {
+    DigestTask {
+        id: task.id,
+        project_id: task.project.id,
+        project: task.project.name.clone(),
+        name: task.name.clone(),
+        assignee: task.responsible_user.as_ref().map(|u| u.name.clone()),
+        due_date: task.due_date,
+        at,
+    }
+}
+
+/// Status transitions into and out of a closed status since `since`
+fn status_events(
+    history: &[History],
+    closed: &HashSet<String>,
+    since: u64,
+) -> (Option<u64>, Option<u64>) {
+    let mut completed = None;
+    let mut reopened = None;
+    for entry in history.iter().filter(|h| h.created_at >= since) {
+        for change in entry.changes.iter().flatten() {
+            if !change.field.eq_ignore_ascii_case("status") {
+                continue;
+            }
+            let was_closed = change.from.as_ref().is_some_and(|s| closed.contains(s));
+            let is_closed = change.to.as_ref().is_some_and(|s| closed.contains(s));
+            if is_closed && !was_closed {
+                completed = completed.max(Some(entry.created_at));
+            } else if was_closed && !is_closed {
+                reopened = reopened.max(Some(entry.created_at));
+            }
+        }
+    }
+    (completed, reopened)
+}
+
+fn summarize(
+    loaded: &Loaded,
+    scope: String,
+    since: u64,
+    now: u64,
+    today: NaiveDate,
+    upcoming_days: u64,
+) -> Digest {
+    let start_of_today = start_of_day(today);
+    let horizon = start_of_day(today + Duration::days(upcoming_days as i64 + 1));
+    let mut digest = Digest {
+        scope,
+        since,
+        until: now,
+        created: Vec::new(),
+        completed: Vec::new(),
+        reopened: Vec::new(),
+        discussions: Vec::new(),
+        overdue: Vec::new(),
+        upcoming: Vec::new(),
+        activity: Vec::new(),
+    };
+
+    for task in &loaded.tasks {
+        if task.created_at >= since {
+            digest
+                .created
+                .push(digest_task(task, Some(task.created_at)));
+        }
+        // Without history or the project's closed statuses, fall back to the current status
+        match (
+            loaded.histories.get(&task.id),
+            loaded.closed.get(&task.project.id),
+        ) {
+            (Some(history), Some(closed)) => {
+                let (completed, reopened) = status_events(history, closed, since);
+                if let Some(at) = completed {
+                    digest.completed.push(digest_task(task, Some(at)));
+                }
+                if let Some(at) = reopened {
+                    digest.reopened.push(digest_task(task, Some(at)));
+                }
+            }
+            _ if task.status.is_closed && task.updated_at >= since => {
+                digest
+                    .completed
+                    .push(digest_task(task, Some(task.updated_at)));
+            }
+            _ => {}
+        }
+        if let Some(comments) = loaded.comments.get(&task.id) {
+            let recent: Vec<&TaskComment> =
+                comments.iter().filter(|c| c.created_at >= since).collect();
+            if !recent.is_empty() {
+                let mut authors: Vec<String> = recent.iter().map(|c| c.user.name.clone()).collect();
+                authors.sort();
+                authors.dedup();
+                digest.discussions.push(Discussion {
+                    task: digest_task(task, recent.iter().map(|c| c.created_at).max()),
+                    comments: recent.len(),
+                    authors,
+                });
+            }
+        }
+        if !task.status.is_closed {
+            match task.due_date {
+                Some(due) if due < start_of_today => digest.overdue.push(digest_task(task, None)),
+                Some(due) if due < horizon => digest.upcoming.push(digest_task(task, None)),
+                _ => {}
+            }
+        }
+    }
+
+    for (project, entries) in &loaded.activity {
+        let recent: Vec<&Activity> = entries.iter().filter(|a| a.created_at >= since).collect();
+        let people: HashSet<u64> = recent
+            .iter()
+            .filter_map(|a| a.user.as_ref().map(|u| u.id))
+            .collect();
+        digest.activity.push(ProjectActivity {
+            project_id: project.id,
+            project: project.name.clone(),
+            events: recent.len(),
+            people: people.len(),
+        });
+    }
+
+    let by_time = |a: &DigestTask, b: &DigestTask| b.at.cmp(&a.at).then(a.id.cmp(&b.id));
+    digest.created.sort_by(by_time);
+    digest.completed.sort_by(by_time);
+    digest.reopened.sort_by(by_time);
+    digest
+        .discussions
+        .sort_by(|a, b| b.comments.cmp(&a.comments).then(a.task.id.cmp(&b.task.id)));
+    digest.overdue.sort_by_key(|t| (t.due_date, t.id));
+    digest.upcoming.sort_by_key(|t| (t.due_date, t.id));
+    digest
+}
+
+fn task_line(task: &DigestTask) -> String {
+    let mut line = format!("- #{} {} ({})", task.id, task.name, task.project);
+    if let Some(assignee) = &task.assignee {
+        let _ = write!(line, " · {}", assignee);
+    }
+    line
+}
+
+fn section(out: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = write!(out, "\n## {} ({})\n\n", title, items.len());
+    for item in items {
+        out.push_str(item);
+        out.push('\n');
+    }
+}
+
+fn to_markdown(digest: &Digest, today: NaiveDate) -> String {
+    let mut out = format!(
+        "# Digest: {}\n\n{} – {}\n\n",
+        digest.scope,
+        format_date(digest.since),
+        format_date(digest.until)
+    );
+    out.push_str("| Created | Completed | Reopened | Comments | Overdue | Upcoming |\n");
+    out.push_str("|---|---|---|---|---|---|\n");
+    let comments: usize = digest.discussions.iter().map(|d| d.comments).sum();
+    let _ = writeln!(
+        out,
+        "| {} | {} | {} | {} | {} | {} |",
+        digest.created.len(),
+        digest.completed.len(),
+        digest.reopened.len(),
+        comments,
+        digest.overdue.len(),
+        digest.upcoming.len()
+    );
+
+    let dated = |tasks: &[DigestTask]| -> Vec<String> {
+        tasks
+            .iter()
+            .map(|t| match t.at {
+                Some(at) => format!("{} · {}", task_line(t), format_date(at)),
+                None => task_line(t),
+            })
+            .collect()
+    };
+    section(&mut out, "Completed", &dated(&digest.completed));
+    section(&mut out, "Created", &dated(&digest.created));
+    section(&mut out, "Reopened", &dated(&digest.reopened));
+    let discussions: Vec<String> = digest
+        .discussions
+        .iter()
+        .map(|d| {
+            format!(
+                "{} · {} comment(s) by {}",
+                task_line(&d.task),
+                d.comments,
+                d.authors.join(", ")
+            )
+        })
+        .collect();
+    section(&mut out, "Discussions", &discussions);
+    let overdue: Vec<String> = digest
+        .overdue
+        .iter()
+        .map(|t| {
+            let due = t.due_date.unwrap_or_default();
+            format!(
+                "{} · due {} ({} day(s) late)",
+                task_line(t),
+                format_date(due),
+                (today - local_date(due)).num_days()
+            )
+        })
+        .collect();
+    section(&mut out, "Overdue", &overdue);
+    let upcoming: Vec<String> = digest
+        .upcoming
+        .iter()
+        .map(|t| {
+            format!(
+                "{} · due {}",
+                task_line(t),
+                format_date(t.due_date.unwrap_or_default())
+            )
+        })
+        .collect();
+    section(&mut out, "Upcoming", &upcoming);
+    let activity: Vec<String> = digest
+        .activity
+        .iter()
+        .filter(|a| a.events > 0)
+        .map(|a| {
+            format!(
+                "- {}: {} event(s) by {} people",
+                a.project, a.events, a.people
+            )
+        })
+        .collect();
+    section(&mut out, "Activity", &activity);
+    out
+}
+
+fn to_html(digest: &Digest, today: NaiveDate) -> String {
+    let body = crate::markdown::to_html(&to_markdown(digest, today));
+    let title = format!("Digest: {}", digest.scope)
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\
+         <style>{}</style></head>\n<body>\n{}</body></html>\n",
+        title, STYLE, body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn ids(tasks: &[DigestTask]) -> Vec<u64> {
+        tasks.iter().map(|t| t.id).collect()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn day(offset: i64) -> u64 {
+        start_of_day(today() + Duration::days(offset)) + 12 * 3600
+    }
+
+    fn task(id: u64, is_closed: bool, due: Option<u64>, created_at: u64) -> Task {
+        let status = if is_closed { "Done" } else { "Open" };
+        Task {
+            due_date: due,
+            created_at,
+            updated_at: day(-1),
+            ..test_support::task(id)
+                .with_status(1, status, is_closed)
+                .in_project(7, "Web")
+        }
+    }
+
+    fn status_change(at: u64, from: &str, to: &str) -> History {
+        History {
+            id: at,
+            created_at: at,
+            action: "update".to_string(),
+            user: None,
+            changes: Some(vec![Change {
+                field: "status".to_string(),
+                from: Some(from.to_string()),
+                to: Some(to.to_string()),
+            }]),
+        }
+    }
+
+    #[test]
+    fn test_summarize_counts_events_in_period() {
+        let loaded = Loaded {
+            tasks: vec![
+                task(1, true, None, day(-30)),
+                task(2, false, Some(day(-3)), day(-2)),
+                task(3, false, Some(day(2)), day(-30)),
+                task(4, true, None, day(-30)),
+                task(5, false, Some(day(20)), day(-30)),
+            ],
+            closed: HashMap::from([(7, HashSet::from(["Done".to_string()]))]),
+            histories: HashMap::from([
+                (1, vec![status_change(day(-2), "Open", "Done")]),
+                (
+                    2,
+                    vec![
+                        status_change(day(-20), "Open", "Done"),
+                        status_change(day(-1), "Done", "Open"),
+                    ],
+                ),
+                // Moving between open statuses is not a completion
+                (5, vec![status_change(day(-10), "Open", "Review")]),
+            ]),
+            comments: HashMap::new(),
+            activity: Vec::new(),
+        };
+        let since = day(-7);
+        let digest = summarize(&loaded, "Web".to_string(), since, day(0), today(), 7);
+        // Task 4 has no history loaded and is closed, so it counts as completed
+        assert_eq!(ids(&digest.completed), vec![4, 1]);
+        assert_eq!(ids(&digest.reopened), vec![2]);
+        assert_eq!(ids(&digest.created), vec![2]);
+        assert_eq!(ids(&digest.overdue), vec![2]);
+        assert_eq!(ids(&digest.upcoming), vec![3]);
+
+        let markdown = to_markdown(&digest, today());
+        assert!(markdown.contains("| 1 | 2 | 1 | 0 | 1 | 1 |"));
+        assert!(markdown.contains("## Overdue (1)\n\n- #2 Task 2 (Web) · due"));
+        assert!(markdown.contains("(3 day(s) late)"));
+        assert!(!markdown.contains("## Discussions"));
+    }
+
+    #[test]
+    fn test_summarize_falls_back_without_closed_statuses() {
+        let loaded = Loaded {
+            tasks: vec![
+                task(1, true, None, day(-30)),
+                task(2, false, None, day(-30)),
+            ],
+            closed: HashMap::new(),
+            histories: HashMap::from([
+                (1, vec![status_change(day(-2), "Open", "Done")]),
+                (2, vec![status_change(day(-2), "Done", "Open")]),
+            ]),
+            comments: HashMap::new(),
+            activity: Vec::new(),
+        };
+        let digest = summarize(&loaded, "Web".to_string(), day(-7), day(0), today(), 7);
+        assert_eq!(ids(&digest.completed), vec![1]);
+        assert!(digest.reopened.is_empty());
+    }
+}
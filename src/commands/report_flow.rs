@@ -0,0 +1,666 @@
+use crate::api::{types::*, RepsonaClient};
+use crate::cli::FlowFormat;
+use crate::commands::calendar::local_date;
+use crate::commands::task_query::find_tasks;
+use crate::output::{format_date, print, print_success, OutputFormat};
+use crate::query::{parse_date, TaskQuery};
+use crate::telemetry_span;
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use colored::Colorize;
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+const CONCURRENCY: usize = 4;
+const DAY: f64 = 86_400.0;
+const BAR_WIDTH: usize = 40;
+/// Upper bounds (in days) of the histogram buckets; the last bucket is open-ended
+const BUCKETS: [f64; 7] = [1.0, 2.0, 4.0, 7.0, 14.0, 30.0, 60.0];
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "report".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct FlowOptions {
+    pub project_id: u64,
+    pub since: String,
+    pub format: FlowFormat,
+    pub out: Option<PathBuf>,
+}
+
+/// A status change reconstructed from task history
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Transition {
+    pub at: u64,
+    pub from: Option<String>,
+    pub to: String,
+}
+
+/// Flow measurements of one task; durations are in days
+#[derive(Debug, Serialize)]
+pub struct TaskFlow {
+    pub id: u64,
+    pub name: String,
+    pub created_at: u64,
+    pub started_at: Option<u64>,
+    pub completed_at: Option<u64>,
+    pub lead_days: Option<f64>,
+    pub cycle_days: Option<f64>,
+    pub days_in_status: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub count: usize,
+    pub p50: Option<f64>,
+    pub p85: Option<f64>,
+    pub p95: Option<f64>,
+    pub max: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusTime {
+    pub status: String,
+    pub mean_days: f64,
+    pub p50_days: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeekCount {
+    pub week: String,
+    pub completed: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Flow {
+    pub project_id: u64,
+    pub project: String,
+    pub since: u64,
+    pub until: u64,
+    /// Open tasks that have left the first status
+    pub in_progress: usize,
+    pub lead_time: Stats,
+    pub cycle_time: Stats,
+    pub time_in_status: Vec<StatusTime>,
+    pub throughput: Vec<WeekCount>,
+    /// Tasks completed in the period
+    pub tasks: Vec<TaskFlow>,
+}
+
+pub async fn handle(client: &RepsonaClient, options: FlowOptions, json: bool) -> Result<()> {
+    let format = if json {
+        FlowFormat::Json
+    } else {
+        options.format
+    };
+    let today = Local::now().date_naive();
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let since = telemetry_span::with_span_result("prepare_request", &prepare_attrs, || {
+        parse_date(&options.since, today)
+    })?;
+    let query = TaskQuery {
+        projects: vec![options.project_id],
+        include_closed: true,
+        ..Default::default()
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let (project, statuses, tasks, histories) =
+        telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || async {
+            let (project, statuses, tasks) = tokio::join!(
+                client.get_project(options.project_id),
+                client.list_project_statuses(options.project_id),
+                find_tasks(client, &query, CONCURRENCY)
+            );
+            let tasks = tasks?;
+            // Only closed tasks touched since the start of the period can have finished in it
+            let relevant: Vec<&Task> = tasks
+                .iter()
+                .filter(|t| t.status.is_closed && t.updated_at >= since)
+                .collect();
+            let histories: Vec<(u64, Result<Vec<History>>)> = stream::iter(relevant)
+                .map(|task| async move {
+                    let history = client
+                        .get_task_history(options.project_id, task.id)
+                        .await
+                        .map(|r| r.data.history);
+                    (task.id, history)
+                })
+                .buffer_unordered(CONCURRENCY)
+                .collect()
+                .await;
+            Ok::<_, anyhow::Error>((
+                project?.data.project,
+                statuses?.data.statuses,
+                tasks,
+                histories,
+            ))
+        })
+        .await?;
+
+    let mut failed = 0;
+    let histories: BTreeMap<u64, Vec<History>> = histories
+        .into_iter()
+        .filter_map(|(id, result)| match result {
+            Ok(history) => Some((id, history)),
+            Err(_) => {
+                failed += 1;
+                None
+            }
+        })
+        .collect();
+    if failed > 0 {
+        eprintln!(
+            "{}",
+            format!(
+                "Could not load the history of {} task(s); they are left out",
+                failed
+            )
+            .yellow()
+        );
+    }
+
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let flow = summarize(&project, &statuses, &tasks, &histories, since, now);
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || {
+        let text = match format {
+            FlowFormat::Json => {
+                if options.out.is_none() {
+                    return print(&flow, OutputFormat::Json);
+                }
+                serde_json::to_string_pretty(&flow)? + "\n"
+            }
+            FlowFormat::Csv => to_csv(&flow, &statuses)?,
+            FlowFormat::Text => render(&flow),
+        };
+        match &options.out {
+            Some(path) => {
+                std::fs::write(path, text)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                print_success(&format!("Flow report written to {}", path.display()));
+            }
+            None => print!("{}", text),
+        }
+        Ok(())
+    })
+}
+
+/// Status changes in time order
+pub(crate) fn transitions(history: &[History]) -> Vec<Transition> {
+    let mut out: Vec<Transition> = history
+        .iter()
+        .flat_map(|entry| {
+            entry
+                .changes
+                .iter()
+                .flatten()
+                .filter(|c| c.field.eq_ignore_ascii_case("status"))
+                .filter_map(move |c| {
+                    Some(Transition {
+                        at: entry.created_at,
+                        from: c.from.clone(),
+                        to: c.to.clone()?,
+                    })
+                })
+        })
+        .collect();
+    out.sort_by_key(|t| t.at);
+    out
+}
+
+/// Replay a task's status changes.
+///
+/// The task starts when it first leaves the project's first status and completes at its last move
+/// into a closed status; time spent in closed statuses is not counted.
+fn analyze(task: &Task, changes: &[Transition], statuses: &[Status], now: u64) -> TaskFlow {
+    let is_closed = |name: &str| statuses.iter().any(|s| s.is_closed && s.name == name);
+    let initial = statuses.first().map(|s| s.name.as_str());
+
+    let mut current = changes
+        .first()
+        .and_then(|c| c.from.clone())
+        .unwrap_or_else(|| task.status.name.clone());
+    let mut since = task.created_at;
+    let mut started_at = (Some(current.as_str()) != initial).then_some(task.created_at);
+    let mut completed_at = None;
+    let mut days_in_status: BTreeMap<String, f64> = BTreeMap::new();
+
+    for change in changes {
+        if !is_closed(&current) {
+            *days_in_status.entry(current.clone()).or_default() +=
+                change.at.saturating_sub(since) as f64 / DAY;
+        }
+        if started_at.is_none() && Some(change.to.as_str()) != initial {
+            started_at = Some(change.at);
+        }
+        completed_at = is_closed(&change.to).then_some(change.at);
+        current = change.to.clone();
+        since = change.at;
+    }
+    if !is_closed(&current) {
+        *days_in_status.entry(current).or_default() += now.saturating_sub(since) as f64 / DAY;
+    }
+    // Closed without a recorded transition (e.g. created closed): no timing to report
+    let completed_at = completed_at.filter(|_| task.status.is_closed);
+
+    let days = |from: u64, to: u64| to.saturating_sub(from) as f64 / DAY;
+    TaskFlow {
+        id: task.id,
+        name: task.name.clone(),
+        created_at: task.created_at,
+        started_at,
+        completed_at,
+        lead_days: completed_at.map(|done| days(task.created_at, done)),
+        cycle_days: completed_at.and_then(|done| Some(days(started_at?, done))),
+        days_in_status,
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn stats(mut values: Vec<f64>) -> Stats {
+    values.sort_by(f64::total_cmp);
+    Stats {
+        count: values.len(),
+        p50: percentile(&values, 50.0),
+        p85: percentile(&values, 85.0),
+        p95: percentile(&values, 95.0),
+        max: values.last().copied(),
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn summarize(
+    project: &Project,
+    statuses: &[Status],
+    tasks: &[Task],
+    histories: &BTreeMap<u64, Vec<History>>,
+    since: u64,
+    now: u64,
+) -> Flow {
+    let initial = statuses.first().map(|s| s.id);
+    let mut in_progress = 0;
+    let mut completed = Vec::new();
+    for task in tasks {
+        if !task.status.is_closed {
+            if Some(task.status.id) != initial {
+                in_progress += 1;
+            }
+            continue;
+        }
+        let Some(history) = histories.get(&task.id) else {
+            continue;
+        };
+        let flow = analyze(task, &transitions(history), statuses, now);
+        if flow.completed_at.is_some_and(|at| at >= since) {
+            completed.push(flow);
+        }
+    }
+    completed.sort_by_key(|f| (f.completed_at, f.id));
+
+    // Every week of the period, including weeks without completions
+    let mut weeks: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    let mut week = week_start(local_date(since));
+    let last = week_start(local_date(now));
+    while week <= last {
+        weeks.insert(week, 0);
+        week += Duration::days(7);
+    }
+    for flow in &completed {
+        if let Some(at) = flow.completed_at {
+            *weeks.entry(week_start(local_date(at))).or_default() += 1;
+        }
+    }
+
+    // Time in status, in project status order
+    let mut time_in_status = Vec::new();
+    for status in statuses.iter().filter(|s| !s.is_closed) {
+        let values: Vec<f64> = completed
+            .iter()
+            .filter_map(|f| f.days_in_status.get(&status.name).copied())
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let median = stats(values).p50.unwrap_or_default();
+        time_in_status.push(StatusTime {
+            status: status.name.clone(),
+            mean_days: mean,
+            p50_days: median,
+        });
+    }
+
+    Flow {
+        project_id: project.id,
+        project: project.name.clone(),
+        since,
+        until: now,
+        in_progress,
+        lead_time: stats(completed.iter().filter_map(|f| f.lead_days).collect()),
+        cycle_time: stats(completed.iter().filter_map(|f| f.cycle_days).collect()),
+        time_in_status,
+        throughput: weeks
+            .into_iter()
+            .map(|(week, completed)| WeekCount {
+                week: week.to_string(),
+                completed,
+            })
+            .collect(),
+        tasks: completed,
+    }
+}
+
+/// Bar rows scaled to the largest count
+fn bars(rows: &[(String, usize)]) -> String {
+    let label_width = rows
+        .iter()
+        .map(|(l, _)| l.chars().count())
+        .max()
+        .unwrap_or(0);
+    let largest = rows.iter().map(|(_, n)| *n).max().unwrap_or(0).max(1);
+    let mut out = String::new();
+    for (label, count) in rows {
+        let width = (count * BAR_WIDTH).div_ceil(largest);
+        let _ = writeln!(
+            out,
+            "  {:<w$}  {:<bw$} {}",
+            label,
+            "█".repeat(width),
+            count,
+            w = label_width,
+            bw = BAR_WIDTH
+        );
+    }
+    out
+}
+
+fn histogram(values: &[f64]) -> String {
+    let mut rows = Vec::new();
+    let mut lower = 0.0;
+    for upper in BUCKETS {
+        let count = values
+            .iter()
+            .filter(|v| **v >= lower && **v < upper)
+            .count();
+        rows.push((format!("{}–{}d", lower, upper), count));
+        lower = upper;
+    }
+    let count = values.iter().filter(|v| **v >= lower).count();
+    rows.push((format!("{}d+", lower), count));
+    bars(&rows)
+}
+
+fn stats_line(stats: &Stats) -> String {
+    let day = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.1}", v));
+    format!(
+        "  n {}  p50 {}  p85 {}  p95 {}  max {}\n",
+        stats.count,
+        day(stats.p50),
+        day(stats.p85),
+        day(stats.p95),
+        day(stats.max)
+    )
+}
+
+fn render(flow: &Flow) -> String {
+    let mut out = format!(
+        "{} {} ({} – {})\n",
+        "Flow:".bold(),
+        flow.project,
+        format_date(flow.since),
+        format_date(flow.until)
+    );
+    let _ = writeln!(
+        out,
+        "Completed: {}  In progress: {}",
+        flow.tasks.len(),
+        flow.in_progress
+    );
+
+    let lead: Vec<f64> = flow.tasks.iter().filter_map(|f| f.lead_days).collect();
+    let cycle: Vec<f64> = flow.tasks.iter().filter_map(|f| f.cycle_days).collect();
+    let _ = write!(out, "\n{}\n", "Lead time (created → done), days".bold());
+    out.push_str(&stats_line(&flow.lead_time));
+    if !lead.is_empty() {
+        out.push_str(&histogram(&lead));
+    }
+    let _ = write!(out, "\n{}\n", "Cycle time (started → done), days".bold());
+    out.push_str(&stats_line(&flow.cycle_time));
+    if !cycle.is_empty() {
+        out.push_str(&histogram(&cycle));
+    }
+
+    if !flow.time_in_status.is_empty() {
+        let _ = write!(out, "\n{}\n", "Time in status, days".bold());
+        let width = flow
+            .time_in_status
+            .iter()
+            .map(|s| s.status.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let _ = writeln!(
+            out,
+            "  {:<w$}  {:>6}  {:>6}",
+            "Status",
+            "mean",
+            "p50",
+            w = width
+        );
+        for status in &flow.time_in_status {
+            let _ = writeln!(
+                out,
+                "  {:<w$}  {:>6.1}  {:>6.1}",
+                status.status,
+                status.mean_days,
+                status.p50_days,
+                w = width
+            );
+        }
+    }
+
+    let _ = write!(out, "\n{}\n", "Throughput per week".bold());
+    let rows: Vec<(String, usize)> = flow
+        .throughput
+        .iter()
+        .map(|w| (w.week.clone(), w.completed))
+        .collect();
+    out.push_str(&bars(&rows));
+    out
+}
+
+/// One row per completed task, with a column per open status
+fn to_csv(flow: &Flow, statuses: &[Status]) -> Result<String> {
+    let open: Vec<&str> = statuses
+        .iter()
+        .filter(|s| !s.is_closed)
+        .map(|s| s.name.as_str())
+        .collect();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut header = vec![
+        "id",
+        "name",
+        "created",
+        "started",
+        "completed",
+        "lead_days",
+        "cycle_days",
+    ];
+    header.extend(open.iter().copied());
+    writer.write_record(&header)?;
+
+    let date = |at: Option<u64>| at.map(format_date).unwrap_or_default();
+    let days = |v: Option<f64>| v.map(|v| format!("{:.2}", v)).unwrap_or_default();
+    for task in &flow.tasks {
+        let mut row = vec![
+            task.id.to_string(),
+            task.name.clone(),
+            date(Some(task.created_at)),
+            date(task.started_at),
+            date(task.completed_at),
+            days(task.lead_days),
+            days(task.cycle_days),
+        ];
+        row.extend(
+            open.iter()
+                .map(|s| days(task.days_in_status.get(*s).copied())),
+        );
+        writer.write_record(&row)?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    const T0: u64 = 1_790_000_000;
+    const D: u64 = 86_400;
+
+    fn status(id: u64, name: &str, is_closed: bool) -> Status {
+        Status {
+            id,
+            name: name.to_string(),
+            is_closed,
+            color: None,
+        }
+    }
+
+    fn statuses() -> Vec<Status> {
+        vec![
+            status(1, "Todo", false),
+            status(2, "Doing", false),
+            status(3, "Review", false),
+            status(4, "Done", true),
+        ]
+    }
+
+    fn change(at: u64, from: &str, to: &str) -> History {
+        History {
+            id: at,
+            created_at: at,
+            action: "update".to_string(),
+            user: None,
+            changes: Some(vec![Change {
+                field: "status".to_string(),
+                from: Some(from.to_string()),
+                to: Some(to.to_string()),
+            }]),
+        }
+    }
+
+    fn done_task() -> Task {
+        Task {
+            status: status(4, "Done", true),
+            created_at: T0,
+            updated_at: T0 + 10 * D,
+            ..test_support::task(9).named("Ship it").in_project(7, "Web")
+        }
+    }
+
+    #[test]
+    fn test_analyze_replays_transitions() {
+        // Out of order on purpose: history is sorted before replaying
+        let history = vec![
+            change(T0 + 6 * D, "Review", "Doing"),
+            change(T0 + 2 * D, "Todo", "Doing"),
+            change(T0 + 5 * D, "Doing", "Review"),
+            change(T0 + 9 * D, "Doing", "Done"),
+        ];
+        let flow = analyze(
+            &done_task(),
+            &transitions(&history),
+            &statuses(),
+            T0 + 20 * D,
+        );
+        assert_eq!(flow.started_at, Some(T0 + 2 * D));
+        assert_eq!(flow.completed_at, Some(T0 + 9 * D));
+        assert_eq!(flow.lead_days, Some(9.0));
+        assert_eq!(flow.cycle_days, Some(7.0));
+        assert_eq!(flow.days_in_status.get("Todo"), Some(&2.0));
+        assert_eq!(flow.days_in_status.get("Doing"), Some(&6.0));
+        assert_eq!(flow.days_in_status.get("Review"), Some(&1.0));
+        assert!(!flow.days_in_status.contains_key("Done"));
+    }
+
+    #[test]
+    fn test_summarize_counts_wip_without_history() {
+        let project = Project {
+            id: 7,
+            name: "Web".to_string(),
+            full_name: "Web".to_string(),
+            purpose: None,
+            avatar_url: None,
+            is_closed: false,
+            is_public: false,
+            created_at: T0,
+            updated_at: T0,
+        };
+        let doing = Task {
+            id: 10,
+            status: status(2, "Doing", false),
+            ..done_task()
+        };
+        let todo = Task {
+            id: 11,
+            status: status(1, "Todo", false),
+            ..done_task()
+        };
+        let history = BTreeMap::from([(
+            9,
+            vec![
+                change(T0 + 2 * D, "Todo", "Doing"),
+                change(T0 + 9 * D, "Doing", "Done"),
+            ],
+        )]);
+        let flow = summarize(
+            &project,
+            &statuses(),
+            &[done_task(), doing, todo],
+            &history,
+            T0,
+            T0 + 20 * D,
+        );
+        assert_eq!(flow.in_progress, 1);
+        assert_eq!(flow.tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_percentiles_and_histogram() {
+        let s = stats(vec![5.0, 1.0, 3.0, 2.0, 4.0, 10.0, 6.0, 7.0, 8.0, 9.0]);
+        assert_eq!(s.count, 10);
+        assert_eq!(s.p50, Some(5.0));
+        assert_eq!(s.p85, Some(9.0));
+        assert_eq!(s.p95, Some(10.0));
+        assert_eq!(stats(Vec::new()).p50, None);
+
+        let text = histogram(&[0.5, 0.2, 3.0, 90.0]);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), BUCKETS.len() + 1);
+        assert!(lines[0].starts_with("  0–1d "));
+        assert!(lines[0].ends_with(&format!("{} 2", "█".repeat(BAR_WIDTH))));
+        assert!(lines[2].ends_with(" 1"));
+        assert!(lines[7].starts_with("  60d+"));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::api::RepsonaClient;
+use crate::cli::SearchKind;
+use crate::config;
+use crate::mirror::{DocKind, Mirror, SearchHit, SearchQuery, MATCH_END, MATCH_START};
+use crate::output::{format_date, print, OutputFormat};
+use anyhow::{bail, Result};
+use colored::Colorize;
+
+pub struct SearchOptions {
+    pub query: String,
+    pub project: Option<u64>,
+    pub kinds: Vec<SearchKind>,
+    pub limit: usize,
+}
+
+pub async fn handle(client: &RepsonaClient, options: SearchOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let path = Mirror::path_for_space(&config::current_profile_name()?, client.space_id())?;
+    if !path.exists() {
+        bail!("No offline mirror for this space yet. Run 'rpsn sync' first");
+    }
+    let mirror = Mirror::open(&path)?;
+    let mut hits = mirror.search(&SearchQuery {
+        text: options.query,
+        project_id: options.project,
+        kinds: doc_kinds(&options.kinds),
+        limit: options.limit,
+    })?;
+
+    match format {
+        OutputFormat::Json => {
+            for hit in &mut hits {
+                hit.snippet = hit.snippet.replace([MATCH_START, MATCH_END], "**");
+            }
+            print(&hits, format)?;
+        }
+        OutputFormat::Human => {
+            if hits.is_empty() {
+                println!("{}", "No matches".dimmed());
+            }
+            for hit in &hits {
+                print_hit(hit);
+            }
+            if let Some(synced_at) = mirror.synced_at()? {
+                println!(
+                    "{}",
+                    format!("Offline results as of {}", format_date(synced_at)).dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn doc_kinds(kinds: &[SearchKind]) -> Vec<DocKind> {
+    kinds
+        .iter()
+        .flat_map(|kind| match kind {
+            SearchKind::Task => vec![DocKind::Task],
+            SearchKind::Note => vec![DocKind::Note],
+            SearchKind::Comment => vec![DocKind::TaskComment, DocKind::NoteComment],
+        })
+        .collect()
+}
+
+/// Command that opens the hit, e.g. `rpsn task get 12 345`
+fn open_command(hit: &SearchHit) -> String {
+    match hit.kind {
+        DocKind::Task => format!("rpsn task get {} {}", hit.project_id, hit.item_id),
+        DocKind::Note => format!("rpsn note get {} {}", hit.project_id, hit.item_id),
+        DocKind::TaskComment => format!(
+            "rpsn task comments {} {}",
+            hit.project_id,
+            hit.parent_id.unwrap_or_default()
+        ),
+        DocKind::NoteComment => format!(
+            "rpsn note comments {} {}",
+            hit.project_id,
+            hit.parent_id.unwrap_or_default()
+        ),
+    }
+}
+
+fn print_hit(hit: &SearchHit) {
+    let project = hit
+        .project_name
+        .clone()
+        .unwrap_or_else(|| hit.project_id.to_string());
+    let label = match hit.kind {
+        DocKind::Task => "task",
+        DocKind::Note => "note",
+        DocKind::TaskComment | DocKind::NoteComment => "comment",
+    };
+    println!(
+        "{} {} {}",
+        format!("[{}]", project).cyan(),
+        hit.title.bold(),
+        label.dimmed()
+    );
+    if !hit.snippet.trim().is_empty() {
+        println!("  {}", highlight(&hit.snippet));
+    }
+    println!("  {}", open_command(hit).dimmed());
+}
+
+fn highlight(snippet: &str) -> String {
+    let mut out = String::new();
+    let mut rest = snippet;
+    while let Some(start) = rest.find(MATCH_START) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + MATCH_START.len_utf8()..];
+        let end = after.find(MATCH_END).unwrap_or(after.len());
+        out.push_str(&after[..end].yellow().bold().to_string());
+        rest = after.get(end + MATCH_END.len_utf8()..).unwrap_or("");
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::test_support::color_override;
+
+    #[test]
+    fn test_comment_kind_covers_tasks_and_notes() {
+        assert_eq!(
+            doc_kinds(&[SearchKind::Comment]),
+            vec![DocKind::TaskComment, DocKind::NoteComment]
+        );
+        assert!(doc_kinds(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_highlight_strips_markers() {
+        let _color = color_override(false);
+        let snippet = format!("fix {}login{} page", MATCH_START, MATCH_END);
+        assert_eq!(highlight(&snippet), "fix login page");
+    }
+}
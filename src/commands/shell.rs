@@ -0,0 +1,596 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use clap::{CommandFactory, Parser};
+use colored::Colorize;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{Context, Editor, Helper, Highlighter, Hinter, Validator};
+
+use crate::api::{endpoints::me::TaskFilter, RepsonaClient};
+use crate::cli::Cli;
+use crate::commands::board::matches_ref;
+use crate::completion::{
+    analyze, matching_entries, matching_words, Candidate, Completion, Entry, Source,
+};
+use crate::config;
+use crate::output::print_success;
+
+const BUILTINS: &[&str] = &["use", "refresh", "help", "exit", "quit"];
+
+/// Global flags fixed when the shell starts; typed inside it they would have no effect
+const SESSION_FLAGS: &[&str] = &[
+    "--dry-run",
+    "--no-cache",
+    "--refresh-cache",
+    "--trace",
+    "--space",
+    "--token",
+    "--profile",
+];
+
+const HELP: &str = "\
+Shell commands:
+  use project <id|name>   Set the project context for task/note/board commands
+  use                     Show the current context
+  use none                Clear the project context
+  refresh                 Reload the completion index
+  help                    Show this help (use '<command> --help' for command help)
+  exit, quit              Leave the shell (Ctrl-D also works)
+
+Any rpsn command can be typed without the leading 'rpsn'. With a project
+context set, the project ID can be omitted: 'task list', 'task done 345'.";
+
+/// Names and IDs known to the shell, used for completion and `use project`
+#[derive(Debug, Default)]
+pub struct Index {
+    pub projects: Vec<Entry>,
+    pub users: Vec<Entry>,
+    /// Tasks of the context project
+    pub tasks: Vec<Entry>,
+    /// Notes of the context project
+    pub notes: Vec<Entry>,
+}
+
+impl Index {
+    fn entries(&self, source: Source, context_project: Option<u64>) -> &[Entry] {
+        match source {
+            Source::Projects => &self.projects,
+            Source::Users => &self.users,
+            Source::Tasks(project_id) if Some(project_id) == context_project => &self.tasks,
+            Source::Notes(project_id) if Some(project_id) == context_project => &self.notes,
+            Source::Tasks(_) | Source::Notes(_) => &[],
+        }
+    }
+}
+
+/// Built-in shell commands that are not part of the rpsn CLI
+#[derive(Debug, PartialEq, Eq)]
+enum Builtin {
+    Use(Option<String>),
+    Refresh,
+    Help,
+    Exit,
+}
+
+fn parse_builtin(words: &[String]) -> Result<Option<Builtin>> {
+    let Some(first) = words.first() else {
+        return Ok(None);
+    };
+    let builtin = match first.as_str() {
+        "exit" | "quit" => Builtin::Exit,
+        "help" if words.len() == 1 => Builtin::Help,
+        "refresh" => Builtin::Refresh,
+        "use" => match &words[1..] {
+            [] => Builtin::Use(None),
+            [kind, target] if kind == "project" => Builtin::Use(Some(target.clone())),
+            [none] if none == "none" => Builtin::Use(Some(String::new())),
+            _ => bail!("Usage: use project <id|name> | use none"),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(builtin))
+}
+
+/// Whether `path` (e.g. `["task", "done"]`) names a command whose first positional is a project ID
+fn takes_project_id(command: &clap::Command, path: &[String]) -> bool {
+    let mut cmd = command;
+    let mut matched = false;
+    for word in path {
+        match cmd.find_subcommand(word) {
+            Some(sub) => {
+                cmd = sub;
+                matched = true;
+            }
+            None => break,
+        }
+    }
+    matched
+        && cmd.get_subcommands().next().is_none()
+        && cmd
+            .get_positionals()
+            .next()
+            .is_some_and(|arg| arg.get_id() == "project_id")
+}
+
+/// Insert the context project ID after the subcommand path when the command expects one
+fn with_project(command: &clap::Command, words: &[String], project_id: u64) -> Option<Vec<String>> {
+    let depth = words
+        .iter()
+        .take_while(|w| !w.starts_with('-'))
+        .take(2)
+        .count();
+    (1..=depth)
+        .rev()
+        .find(|&n| takes_project_id(command, &words[..n]))
+        .map(|n| {
+            let mut expanded = words[..n].to_vec();
+            expanded.push(project_id.to_string());
+            expanded.extend_from_slice(&words[n..]);
+            expanded
+        })
+}
+
+/// The first session-wide global flag in `words`, if any
+fn session_flag(words: &[String]) -> Option<&'static str> {
+    words
+        .iter()
+        .take_while(|w| w.as_str() != "--")
+        .find_map(|w| {
+            SESSION_FLAGS.iter().copied().find(|flag| {
+                w == flag
+                    || w.strip_prefix(flag)
+                        .is_some_and(|rest| rest.starts_with('='))
+            })
+        })
+}
+
+/// Write the history file readable only by the owner
+fn save_history(editor: &mut Editor<ShellHelper, DefaultHistory>, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        config::create_private_dir(parent)?;
+    }
+    config::open_private_file(path, false)?;
+    editor.save_history(path)?;
+    Ok(())
+}
+
+fn parse_line(
+    command: &clap::Command,
+    words: &[String],
+    project: Option<u64>,
+) -> clap::error::Result<Cli> {
+    let argv = |words: &[String]| std::iter::once("rpsn".to_string()).chain(words.to_vec());
+    if let Some(expanded) = project.and_then(|id| with_project(command, words, id)) {
+        if let Ok(cli) = Cli::try_parse_from(argv(&expanded)) {
+            return Ok(cli);
+        }
+    }
+    Cli::try_parse_from(argv(words))
+}
+
+#[derive(Helper, Hinter, Highlighter, Validator)]
+struct ShellHelper {
+    command: clap::Command,
+    index: Index,
+    project: Option<u64>,
+}
+
+impl ShellHelper {
+    fn candidates(&self, prior: &[&str], partial: &str) -> Vec<Pair> {
+        let candidates = if prior.first() == Some(&"use") {
+            match prior.len() {
+                1 => matching_words(&["project", "none"], partial),
+                2 if prior[1] == "project" => matching_entries(&self.index.projects, partial),
+                _ => Vec::new(),
+            }
+        } else {
+            match analyze(&self.command, prior, partial, self.project) {
+                Completion::Words(mut words) => {
+                    if prior.is_empty() {
+                        words.extend(BUILTINS.iter().map(|b| b.to_string()));
+                    }
+                    matching_words(&words, partial)
+                }
+                Completion::Dynamic(source) => {
+                    matching_entries(self.index.entries(source, self.project), partial)
+                }
+                Completion::Nothing => Vec::new(),
+            }
+        };
+        candidates.into_iter().map(to_pair).collect()
+    }
+}
+
+fn to_pair(candidate: Candidate) -> Pair {
+    Pair {
+        display: match &candidate.description {
+            Some(description) => format!("{:<8} {}", candidate.value, description),
+            None => candidate.value.clone(),
+        },
+        replacement: format!("{} ", candidate.value),
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        let start = before
+            .rfind(char::is_whitespace)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let prior: Vec<&str> = before[..start].split_whitespace().collect();
+        Ok((start, self.candidates(&prior, &before[start..])))
+    }
+}
+
+async fn load_index(client: &RepsonaClient) -> Index {
+    let (projects, users) = tokio::join!(client.list_projects(), client.list_users());
+    Index {
+        projects: projects
+            .map(|r| {
+                r.data
+                    .projects
+                    .into_iter()
+                    .map(|p| Entry::new(p.id, p.name))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        users: users
+            .map(|r| {
+                r.data
+                    .users
+                    .into_iter()
+                    .map(|u| Entry::new(u.id, format!("{} ({})", u.full_name, u.name)))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ..Index::default()
+    }
+}
+
+async fn load_project_index(client: &RepsonaClient, project_id: u64, index: &mut Index) {
+    let filter = TaskFilter::default();
+    let (tasks, notes) = tokio::join!(
+        client.list_tasks(project_id, &filter),
+        client.list_notes(project_id)
+    );
+    index.tasks = tasks
+        .map(|r| {
+            r.data
+                .tasks
+                .into_iter()
+                .map(|t| Entry::new(t.id, t.name))
+                .collect()
+        })
+        .unwrap_or_default();
+    index.notes = notes
+        .map(|r| {
+            r.data
+                .notes
+                .into_iter()
+                .map(|n| Entry::new(n.id, n.name))
+                .collect()
+        })
+        .unwrap_or_default();
+}
+
+/// Resolve `use project` input against the index, falling back to the API for unknown IDs
+async fn resolve_project(client: &RepsonaClient, index: &Index, target: &str) -> Result<Entry> {
+    if let Some(entry) = index
+        .projects
+        .iter()
+        .find(|e| matches_ref(target, e.id, &[e.label.as_str()]))
+    {
+        return Ok(entry.clone());
+    }
+    match target.parse::<u64>() {
+        Ok(id) => {
+            let project = client.get_project(id).await?.data.project;
+            Ok(Entry::new(project.id, project.name))
+        }
+        Err(_) => bail!("Project not found: {}", target),
+    }
+}
+
+fn prompt(project: Option<&Entry>) -> String {
+    match project {
+        Some(p) => format!("rpsn:{}> ", p.label),
+        None => "rpsn> ".to_string(),
+    }
+}
+
+/// Run the interactive shell until `exit` or end of input
+pub async fn run(client: &RepsonaClient, json: bool) -> Result<()> {
+    let mut command = Cli::command();
+    command.build();
+
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(ShellHelper {
+        command: command.clone(),
+        index: load_index(client).await,
+        project: None,
+    }));
+    let history_path = config::config_dir()?.join("shell_history");
+    let _ = editor.load_history(&history_path);
+
+    println!(
+        "{}",
+        "rpsn shell - type 'help' for shell commands, 'exit' to quit".dimmed()
+    );
+
+    let mut project: Option<Entry> = None;
+    loop {
+        let line = match tokio::task::block_in_place(|| editor.readline(&prompt(project.as_ref())))
+        {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        // Never persist credentials typed on the command line
+        if !line.contains("--token") {
+            let _ = editor.add_history_entry(line);
+        }
+
+        let Some(words) = shlex::split(line) else {
+            eprintln!("{}", "Error: unbalanced quotes".red());
+            continue;
+        };
+
+        match parse_builtin(&words) {
+            Ok(Some(Builtin::Exit)) => break,
+            Ok(Some(Builtin::Help)) => println!("{}", HELP),
+            Ok(Some(Builtin::Use(None))) => match &project {
+                Some(p) => println!("Project: {} (#{})", p.label, p.id),
+                None => println!("No project context"),
+            },
+            Ok(Some(Builtin::Use(Some(target)))) if target.is_empty() => {
+                project = None;
+                if let Some(helper) = editor.helper_mut() {
+                    helper.project = None;
+                    helper.index.tasks.clear();
+                    helper.index.notes.clear();
+                }
+            }
+            Ok(Some(Builtin::Use(Some(target)))) => {
+                let Some(helper) = editor.helper_mut() else {
+                    continue;
+                };
+                match resolve_project(client, &helper.index, &target).await {
+                    Ok(entry) => {
+                        load_project_index(client, entry.id, &mut helper.index).await;
+                        helper.project = Some(entry.id);
+                        print_success(&format!("Using project {} (#{})", entry.label, entry.id));
+                        project = Some(entry);
+                    }
+                    Err(err) => eprintln!("{} {:#}", "Error:".red().bold(), err),
+                }
+            }
+            Ok(Some(Builtin::Refresh)) => {
+                if let Some(helper) = editor.helper_mut() {
+                    let tasks_and_notes = project.as_ref().map(|p| p.id);
+                    helper.index = load_index(client).await;
+                    if let Some(project_id) = tasks_and_notes {
+                        load_project_index(client, project_id, &mut helper.index).await;
+                    }
+                    print_success("Completion index refreshed");
+                }
+            }
+            Ok(None) => {
+                if let Some(flag) = session_flag(&words) {
+                    eprintln!(
+                        "{} {} only takes effect when starting the shell (rpsn {} ... shell)",
+                        "Error:".red().bold(),
+                        flag,
+                        flag
+                    );
+                    continue;
+                }
+                let cli = match parse_line(&command, &words, project.as_ref().map(|p| p.id)) {
+                    Ok(cli) => cli,
+                    Err(err) => {
+                        let _ = err.print();
+                        continue;
+                    }
+                };
+                if let Err(err) =
+                    super::dispatch(client, cli.command, json || cli.json, cli.yes).await
+                {
+                    eprintln!("{} {:#}", "Error:".red().bold(), err);
+                }
+                // Keep task/note completions in step with creations and deletions
+                let changes_index =
+                    matches!(words.get(1).map(String::as_str), Some("create" | "delete"))
+                        && matches!(words[0].as_str(), "task" | "note");
+                if let (true, Some(p), Some(helper)) =
+                    (changes_index, project.as_ref(), editor.helper_mut())
+                {
+                    load_project_index(client, p.id, &mut helper.index).await;
+                }
+            }
+            Err(err) => eprintln!("{} {:#}", "Error:".red().bold(), err),
+        }
+    }
+
+    if let Err(err) = save_history(&mut editor, &history_path) {
+        eprintln!(
+            "{} {:#}",
+            "Warning: could not save shell history:".yellow(),
+            err
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Commands, TaskCommands};
+
+    fn words(line: &str) -> Vec<String> {
+        shlex::split(line).unwrap()
+    }
+
+    fn command() -> clap::Command {
+        let mut command = Cli::command();
+        command.build();
+        command
+    }
+
+    fn helper(project: Option<u64>) -> ShellHelper {
+        ShellHelper {
+            command: command(),
+            index: Index {
+                projects: vec![Entry::new(12, "Website"), Entry::new(13, "Mobile")],
+                users: vec![Entry::new(5, "Alice Smith (alice)")],
+                tasks: vec![Entry::new(345, "Fix login"), Entry::new(346, "Write docs")],
+                notes: vec![Entry::new(9, "Runbook")],
+            },
+            project,
+        }
+    }
+
+    fn replacements(pairs: Vec<Pair>) -> Vec<String> {
+        pairs.into_iter().map(|p| p.replacement).collect()
+    }
+
+    #[test]
+    fn test_parse_builtin() {
+        assert_eq!(
+            parse_builtin(&words("use project 12")).unwrap(),
+            Some(Builtin::Use(Some("12".to_string())))
+        );
+        assert_eq!(
+            parse_builtin(&words("use none")).unwrap(),
+            Some(Builtin::Use(Some(String::new())))
+        );
+        assert_eq!(
+            parse_builtin(&words("use")).unwrap(),
+            Some(Builtin::Use(None))
+        );
+        assert_eq!(parse_builtin(&words("quit")).unwrap(), Some(Builtin::Exit));
+        assert!(parse_builtin(&words("use task 3")).is_err());
+        assert_eq!(parse_builtin(&words("task list 1")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_with_project_inserts_after_subcommand() {
+        let command = command();
+        assert_eq!(
+            with_project(&command, &words("task done 345"), 12),
+            Some(words("task done 12 345"))
+        );
+        assert_eq!(
+            with_project(&command, &words("board --compact"), 12),
+            Some(words("board 12 --compact"))
+        );
+        assert_eq!(with_project(&command, &words("inbox list"), 12), None);
+        assert_eq!(with_project(&command, &words("me tasks"), 12), None);
+    }
+
+    #[test]
+    fn test_parse_line_uses_context() {
+        let command = command();
+        let cli = parse_line(&command, &words("task done 345"), Some(12)).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Task(TaskCommands::Done {
+                project_id: 12,
+                task_id: 345
+            })
+        ));
+
+        // An explicit project ID still wins
+        let cli = parse_line(&command, &words("task done 13 345"), Some(12)).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Task(TaskCommands::Done {
+                project_id: 13,
+                task_id: 345
+            })
+        ));
+
+        assert!(parse_line(&command, &words("task done 345"), None).is_err());
+    }
+
+    #[test]
+    fn test_session_flag() {
+        assert_eq!(
+            session_flag(&words("task list --dry-run")),
+            Some("--dry-run")
+        );
+        assert_eq!(
+            session_flag(&words("task list --token=abc")),
+            Some("--token")
+        );
+        assert_eq!(session_flag(&words("task list --json")), None);
+        assert_eq!(session_flag(&words("note create 1 -- --trace")), None);
+    }
+
+    #[test]
+    fn test_complete_commands_and_builtins() {
+        let helper = helper(None);
+        let top = replacements(helper.candidates(&[], "t"));
+        assert!(top.contains(&"task ".to_string()));
+        assert!(top.contains(&"tag ".to_string()));
+
+        let builtins = replacements(helper.candidates(&[], "us"));
+        assert!(builtins.contains(&"use ".to_string()));
+        assert!(builtins.contains(&"user ".to_string()));
+
+        let subs = replacements(helper.candidates(&["task"], "do"));
+        assert_eq!(subs, vec!["done ".to_string()]);
+    }
+
+    #[test]
+    fn test_complete_ids_without_context() {
+        let helper = helper(None);
+        let projects = replacements(helper.candidates(&["task", "get"], ""));
+        assert_eq!(projects, vec!["12 ".to_string(), "13 ".to_string()]);
+
+        // Only the context project's tasks are indexed
+        let tasks = replacements(helper.candidates(&["task", "get", "12"], "log"));
+        assert!(tasks.is_empty());
+    }
+
+    #[test]
+    fn test_complete_ids_with_context() {
+        let helper = helper(Some(12));
+        let tasks = replacements(helper.candidates(&["task", "done"], "34"));
+        assert_eq!(tasks, vec!["345 ".to_string(), "346 ".to_string()]);
+
+        let notes = replacements(helper.candidates(&["note", "get"], ""));
+        assert_eq!(notes, vec!["9 ".to_string()]);
+
+        let users =
+            replacements(helper.candidates(&["task", "update", "345", "--assignee"], "ali"));
+        assert_eq!(users, vec!["5 ".to_string()]);
+    }
+
+    #[test]
+    fn test_complete_use_project_by_name() {
+        let helper = helper(None);
+        let pairs = helper.candidates(&["use", "project"], "mob");
+        assert_eq!(replacements(pairs), vec!["13 ".to_string()]);
+    }
+
+    #[test]
+    fn test_complete_flags() {
+        let helper = helper(Some(12));
+        let flags = replacements(helper.candidates(&["task", "create"], "--ti"));
+        assert_eq!(flags, vec!["--title ".to_string()]);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::api::{endpoints::me::TaskFilter, types::*, RepsonaClient};
+use crate::config;
+use crate::mirror::{Mirror, MirrorCounts, ProjectChanges};
+use crate::output::{print, print_success, OutputFormat};
+use crate::telemetry_span;
+use anyhow::{bail, Result};
+use colored::Colorize;
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "sync".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct SyncOptions {
+    pub full: bool,
+    /// Limit the sync to these projects; empty means all
+    pub projects: Vec<u64>,
+    pub concurrency: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncSummary {
+    pub database: String,
+    pub projects: usize,
+    pub tasks_updated: usize,
+    pub tasks_deleted: usize,
+    pub notes_updated: usize,
+    pub notes_deleted: usize,
+    pub projects_removed: usize,
+    pub totals: MirrorCounts,
+}
+
+pub async fn handle(client: &RepsonaClient, options: SyncOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let path = Mirror::path_for_space(&config::current_profile_name()?, client.space_id())?;
+    let mut mirror = Mirror::open(&path)?;
+    let mut summary = SyncSummary {
+        database: path.display().to_string(),
+        ..Default::default()
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || async {
+        // A stale cached list would miss new projects and keep mirroring deleted ones
+        let (projects, users) = tokio::join!(client.list_projects_fresh(), client.list_users());
+        let projects = projects?.data.projects;
+        mirror.replace_users(&users?.data.users)?;
+
+        let selected: Vec<&Project> = if options.projects.is_empty() {
+            let keep: Vec<u64> = projects.iter().map(|p| p.id).collect();
+            summary.projects_removed = mirror.prune_projects(&keep)?.len();
+            projects.iter().collect()
+        } else {
+            let selected: Vec<&Project> = projects
+                .iter()
+                .filter(|p| options.projects.contains(&p.id))
+                .collect();
+            if let Some(missing) = options
+                .projects
+                .iter()
+                .find(|id| !selected.iter().any(|p| p.id == **id))
+            {
+                bail!("Project {} not found or not accessible", missing);
+            }
+            selected
+        };
+        mirror.upsert_projects(&projects)?;
+
+        for project in selected {
+            if !json {
+                eprintln!("{}", format!("Syncing {}...", project.name).dimmed());
+            }
+            sync_project(client, &mut mirror, project.id, &options, &mut summary).await?;
+        }
+        mirror.set_synced_at(now())?;
+        summary.projects = if options.projects.is_empty() {
+            projects.len()
+        } else {
+            options.projects.len()
+        };
+        summary.totals = mirror.counts()?;
+        Ok::<(), anyhow::Error>(())
+    })
+    .await?;
+
+    match format {
+        OutputFormat::Json => print(&summary, format)?,
+        OutputFormat::Human => {
+            print_success(&format!(
+                "Synced {} projects: {} tasks and {} notes updated, {} removed",
+                summary.projects,
+                summary.tasks_updated,
+                summary.notes_updated,
+                summary.tasks_deleted + summary.notes_deleted
+            ));
+            println!(
+                "{}",
+                format!(
+                    "{} ({} tasks, {} notes, {} comments)",
+                    summary.database,
+                    summary.totals.tasks,
+                    summary.totals.notes,
+                    summary.totals.comments
+                )
+                .dimmed()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn sync_project(
+    client: &RepsonaClient,
+    mirror: &mut Mirror,
+    project_id: u64,
+    options: &SyncOptions,
+    summary: &mut SyncSummary,
+) -> Result<()> {
+    let filter = TaskFilter::default();
+    let (tasks, notes) = tokio::join!(
+        client.list_tasks(project_id, &filter),
+        client.list_notes(project_id)
+    );
+    let (tasks, deleted_tasks) = changed(
+        tasks?.data.tasks,
+        mirror.task_versions(project_id)?,
+        options.full,
+        |t| (t.id, t.updated_at),
+    );
+    let (notes, deleted_notes) = changed(
+        notes?.data.notes,
+        mirror.note_versions(project_id)?,
+        options.full,
+        |n| (n.id, n.updated_at),
+    );
+
+    // Comments are fetched one item at a time, so only for items that changed
+    let concurrency = options.concurrency.max(1);
+    let task_comments = stream::iter(tasks.iter().map(|t| t.id))
+        .map(|task_id| async move {
+            let response = client.list_task_comments(project_id, task_id).await?;
+            Ok::<_, anyhow::Error>((task_id, response.data.task_comments))
+        })
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await?;
+    let note_comments = stream::iter(notes.iter().map(|n| n.id))
+        .map(|note_id| async move {
+            let response = client.list_note_comments(project_id, note_id).await?;
+            Ok::<_, anyhow::Error>((note_id, response.data.note_comments))
+        })
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await?;
+
+    summary.tasks_updated += tasks.len();
+    summary.notes_updated += notes.len();
+    summary.tasks_deleted += deleted_tasks.len();
+    summary.notes_deleted += deleted_notes.len();
+    mirror.apply(
+        project_id,
+        &ProjectChanges {
+            tasks,
+            notes,
+            task_comments,
+            note_comments,
+            deleted_tasks,
+            deleted_notes,
+        },
+    )
+}
+
+/// Split fetched items into those whose `updatedAt` differs from the stored version
+/// (all of them when `full`), and the stored IDs that no longer exist
+fn changed<T>(
+    items: Vec<T>,
+    mut stored: HashMap<u64, u64>,
+    full: bool,
+    version: impl Fn(&T) -> (u64, u64),
+) -> (Vec<T>, Vec<u64>) {
+    let changed = items
+        .into_iter()
+        .filter(|item| {
+            let (id, updated_at) = version(item);
+            let previous = stored.remove(&id);
+            full || previous != Some(updated_at)
+        })
+        .collect();
+    let mut deleted: Vec<u64> = stored.into_keys().collect();
+    deleted.sort_unstable();
+    (changed, deleted)
+}
+
+fn now() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_detects_updates_and_deletes() {
+        let stored = HashMap::from([(1, 100), (2, 100), (3, 100)]);
+        let items = vec![(1, 100), (2, 150), (4, 10)];
+        let (changed_items, deleted) = changed(items, stored, false, |i| *i);
+        assert_eq!(changed_items, vec![(2, 150), (4, 10)]);
+        assert_eq!(deleted, vec![3]);
+    }
+
+    #[test]
+    fn test_changed_full_refetches_everything() {
+        let stored = HashMap::from([(1, 100)]);
+        let (changed_items, deleted) = changed(vec![(1, 100)], stored, true, |i| *i);
+        assert_eq!(changed_items, vec![(1, 100)]);
+        assert!(deleted.is_empty());
+    }
+}
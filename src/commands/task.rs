@@ -1,11 +1,17 @@
 use crate::ai::{AiClient, AnthropicClient};
 use crate::api::{endpoints::me::TaskFilter, endpoints::task::*, types::TaskStatus, RepsonaClient};
 use crate::cli::TaskCommands;
+use crate::commands::edit;
 use crate::commands::tag::parse_tags;
+use crate::commands::task_bulk::{self, BulkAction, BulkOptions};
+use crate::commands::task_import::{self, ImportOptions};
+use crate::commands::task_query;
+use crate::commands::util::read_body;
 use crate::config;
-use crate::output::{print, print_success, OutputFormat};
+use crate::output::{print, print_success, print_text_item, OutputFormat};
+use crate::query::TaskQuery;
 use crate::telemetry_span;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::Colorize;
 use std::io::{self, Write};
 
@@ -16,7 +22,12 @@ fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
     ]
 }
 
-pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -> Result<()> {
+pub async fn handle(
+    client: &RepsonaClient,
+    command: TaskCommands,
+    json: bool,
+    yes: bool,
+) -> Result<()> {
     let format = if json {
         OutputFormat::Json
     } else {
@@ -40,9 +51,46 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
                 print(&response.data.tasks, format)
             })?;
         }
+        TaskCommands::Query {
+            view,
+            projects,
+            assignee,
+            status,
+            tag,
+            due_before,
+            priority,
+            updated_since,
+            include_closed,
+            limit,
+            concurrency,
+        } => {
+            // `@name` loads a saved query; flags given here override its fields
+            let (saved, view) = match view.as_deref().and_then(|v| v.strip_prefix('@')) {
+                Some(name) => (config::load_saved_query(name)?, None),
+                None => (TaskQuery::default(), view),
+            };
+            let query = saved.merged_with(TaskQuery {
+                view: view.as_deref().map(str::parse).transpose()?,
+                projects,
+                assignee,
+                status,
+                tag,
+                due_before,
+                priority,
+                updated_since,
+                include_closed,
+            });
+            let options = task_query::QueryOptions {
+                query,
+                limit,
+                concurrency,
+            };
+            task_query::handle(client, options, json).await?;
+        }
         TaskCommands::Get {
             project_id,
             task_id,
+            raw,
         } => {
             let exec_attrs = phase_attrs("execute_operation");
             let response = telemetry_span::with_span_async_result(
@@ -53,13 +101,14 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
             .await?;
             let render_attrs = phase_attrs("render_output");
             telemetry_span::with_span_result("render_output", &render_attrs, || {
-                print(&response.data.task, format)
+                print_text_item("task", &response.data.task, format, raw)
             })?;
         }
         TaskCommands::Create {
             project_id,
             title,
             description,
+            body_file,
             status,
             priority,
             due,
@@ -67,6 +116,10 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
             tags,
         } => {
             let validate_attrs = phase_attrs("validate_input");
+            let description =
+                telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+                    read_body(description, body_file)
+                })?;
             let tags_vec = telemetry_span::with_span("validate_input", &validate_attrs, || {
                 tags.map(|t| parse_tags(&t))
             });
@@ -103,6 +156,7 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
             task_id,
             title,
             description,
+            body_file,
             status,
             priority,
             due,
@@ -110,6 +164,10 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
             tags,
         } => {
             let validate_attrs = phase_attrs("validate_input");
+            let description =
+                telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+                    read_body(description, body_file)
+                })?;
             let tags_vec = telemetry_span::with_span("validate_input", &validate_attrs, || {
                 tags.map(|t| parse_tags(&t))
             });
@@ -190,6 +248,10 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
                 print_success("Task reopened");
             });
         }
+        TaskCommands::Edit {
+            project_id,
+            task_id,
+        } => edit::edit_task(client, project_id, task_id, json).await?,
         TaskCommands::Delete {
             project_id,
             task_id,
@@ -223,6 +285,7 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
         TaskCommands::CommentList {
             project_id,
             task_id,
+            raw,
         } => {
             let exec_attrs = phase_attrs("execute_operation");
             let response = telemetry_span::with_span_async_result(
@@ -233,15 +296,21 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
             .await?;
             let render_attrs = phase_attrs("render_output");
             telemetry_span::with_span_result("render_output", &render_attrs, || {
-                print(&response.data.task_comments, format)
+                print_text_item("task_comments", &response.data.task_comments, format, raw)
             })?;
         }
         TaskCommands::CommentAdd {
             project_id,
             task_id,
             comment,
+            body_file,
             reply_to,
         } => {
+            let validate_attrs = phase_attrs("validate_input");
+            let comment =
+                telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+                    read_body(comment, body_file)?.context("--comment or --body-file is required")
+                })?;
             let exec_attrs = phase_attrs("execute_operation");
             let response = telemetry_span::with_span_async_result(
                 "execute_operation",
@@ -265,7 +334,17 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
             project_id,
             comment_id,
             comment,
+            body_file,
+            edit,
+            task,
         } => {
+            if edit {
+                let task_id = task.context("--edit needs --task")?;
+                return edit::edit_task_comment(client, project_id, task_id, comment_id, json)
+                    .await;
+            }
+            let comment = read_body(comment, body_file)?
+                .context("--comment, --body-file or --edit is required")?;
             let exec_attrs = phase_attrs("execute_operation");
             let response = telemetry_span::with_span_async_result(
                 "execute_operation",
@@ -299,6 +378,60 @@ pub async fn handle(client: &RepsonaClient, command: TaskCommands, json: bool) -
                 print_success("Comment deleted");
             });
         }
+        TaskCommands::BulkUpdate { target, set } => {
+            let options = BulkOptions {
+                target,
+                action: BulkAction::Update(set),
+                yes,
+            };
+            task_bulk::handle(client, options, json).await?;
+        }
+        TaskCommands::BulkDone { target } => {
+            let options = BulkOptions {
+                target,
+                action: BulkAction::Done,
+                yes,
+            };
+            task_bulk::handle(client, options, json).await?;
+        }
+        TaskCommands::BulkDelete { target } => {
+            let options = BulkOptions {
+                target,
+                action: BulkAction::Delete,
+                yes,
+            };
+            task_bulk::handle(client, options, json).await?;
+        }
+        TaskCommands::Import {
+            project_id,
+            file,
+            map,
+            format,
+            state,
+            report,
+        } => {
+            let options = ImportOptions {
+                project_id,
+                file,
+                format,
+                map,
+                state,
+                report,
+            };
+            task_import::handle(client, options, json).await?;
+        }
+        TaskCommands::BulkTag {
+            action,
+            tag,
+            target,
+        } => {
+            let options = BulkOptions {
+                target,
+                action: BulkAction::Tag(action, tag),
+                yes,
+            };
+            task_bulk::handle(client, options, json).await?;
+        }
         TaskCommands::Activity {
             project_id,
             task_id,
@@ -0,0 +1,684 @@
+use crate::api::{
+    endpoints::{me::TaskFilter, task::UpdateTaskRequest},
+    types::*,
+    RepsonaClient,
+};
+use crate::cli::{BulkTarget, TagAction};
+use crate::commands::board::matches_ref;
+use crate::commands::task_query;
+use crate::output::{confirm, format_date, print, print_success, OutputFormat};
+use crate::query::{self, TaskQuery};
+use crate::telemetry_span;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "task".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+/// What a bulk command does to each selected task
+pub enum BulkAction {
+    /// `--set key=value` assignments
+    Update(Vec<String>),
+    Done,
+    Delete,
+    Tag(TagAction, String),
+}
+
+pub struct BulkOptions {
+    pub target: BulkTarget,
+    pub action: BulkAction,
+    /// Apply without asking (`--yes`)
+    pub yes: bool,
+}
+
+/// One field change shown in the preview
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+/// Outcome for one task
+#[derive(Debug, Serialize)]
+pub struct BulkResult {
+    pub project_id: u64,
+    pub task_id: u64,
+    pub title: String,
+    pub changes: Vec<Change>,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// `--set` values with names resolved to IDs
+#[derive(Debug, Default)]
+struct Update {
+    title: Option<String>,
+    /// Target status per project, since status IDs differ between projects
+    status: HashMap<u64, Status>,
+    priority: Option<u32>,
+    assignee: Option<User>,
+    due: Option<u64>,
+}
+
+#[derive(Debug)]
+enum Resolved {
+    Update(Box<Update>),
+    Done,
+    Delete,
+    AddTag(Tag),
+    RemoveTag(Tag),
+}
+
+enum Operation {
+    Update(UpdateTaskRequest),
+    SetStatus(u64),
+    Delete,
+}
+
+struct Plan {
+    task: Task,
+    changes: Vec<Change>,
+    operation: Operation,
+}
+
+pub async fn handle(client: &RepsonaClient, options: BulkOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let target = &options.target;
+    let concurrency = target.concurrency.max(1);
+
+    let validate_attrs = phase_attrs("validate_input");
+    let selection = telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+        match target.ids.as_deref() {
+            Some(ids) => read_ids(ids, target.project_id).map(Selection::Ids),
+            None => filter_query(target).map(Selection::Query),
+        }
+    })?;
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let tasks = telemetry_span::with_span_async_result("select_tasks", &exec_attrs, || async {
+        match &selection {
+            Selection::Ids(ids) => tasks_by_id(client, ids, concurrency).await,
+            Selection::Query(query) => task_query::find_tasks(client, query, concurrency).await,
+        }
+    })
+    .await?;
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let resolved =
+        telemetry_span::with_span_async_result("prepare_request", &prepare_attrs, || {
+            resolve(client, &options.action, &tasks)
+        })
+        .await?;
+    let plans: Vec<Plan> = tasks
+        .into_iter()
+        .filter_map(|t| plan(t, &resolved))
+        .collect();
+
+    let render_attrs = phase_attrs("render_output");
+    if plans.is_empty() {
+        return telemetry_span::with_span_result("render_output", &render_attrs, || {
+            if json {
+                print(&Vec::<BulkResult>::new(), format)
+            } else {
+                println!("{}", "No tasks to change".dimmed());
+                Ok(())
+            }
+        });
+    }
+
+    if !options.yes {
+        if json {
+            bail!("Pass --yes to apply bulk changes with --json");
+        }
+        telemetry_span::with_span("render_output", &render_attrs, || {
+            print_preview(&plans, &resolved)
+        });
+        let proceed =
+            telemetry_span::with_span_result("interactive_confirm", &validate_attrs, || {
+                confirm(&format!("Apply to {} task(s)? [y/N]: ", plans.len()))
+            })?;
+        if !proceed {
+            eprintln!("{}", "Cancelled".yellow());
+            return Ok(());
+        }
+    }
+
+    let results = telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || {
+        apply(client, plans, concurrency, json)
+    })
+    .await?;
+
+    let failed = results.iter().filter(|r| !r.ok).count();
+    telemetry_span::with_span_result("render_output", &render_attrs, || {
+        if json {
+            print(&results, format)?;
+        } else if failed < results.len() {
+            print_success(&format!(
+                "{} {} task(s)",
+                past_tense(&resolved),
+                results.len() - failed
+            ));
+        }
+        Ok::<(), anyhow::Error>(())
+    })?;
+    if failed > 0 {
+        bail!("{} of {} task(s) failed", failed, results.len());
+    }
+    Ok(())
+}
+
+/// `--ids` value, or the IDs on stdin for `-`
+enum Selection {
+    Ids(Vec<(u64, u64)>),
+    Query(TaskQuery),
+}
+
+/// The `--where` query, refusing one that would select every open task in the space
+fn filter_query(target: &BulkTarget) -> Result<TaskQuery> {
+    let mut query = TaskQuery::parse_where(target.filter.as_deref().unwrap_or(""))?;
+    if target.project_id.is_none() && query.is_unfiltered() {
+        bail!("--where has no conditions; pass a project ID or at least one filter");
+    }
+    query.projects.extend(target.project_id);
+    Ok(query)
+}
+
+fn read_ids(ids: &str, project_id: Option<u64>) -> Result<Vec<(u64, u64)>> {
+    if ids == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        return parse_ids(&input, project_id);
+    }
+    parse_ids(ids, project_id)
+}
+
+/// Parse task IDs separated by commas or whitespace; each is `TASK` (in `project_id`)
+/// or `PROJECT/TASK`. Duplicates are dropped.
+fn parse_ids(input: &str, project_id: Option<u64>) -> Result<Vec<(u64, u64)>> {
+    let mut ids = Vec::new();
+    for word in input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|w| !w.is_empty())
+    {
+        let id = match word.split_once('/') {
+            Some((project, task)) => (
+                project
+                    .parse()
+                    .with_context(|| format!("Invalid project ID in '{}'", word))?,
+                task.parse()
+                    .with_context(|| format!("Invalid task ID in '{}'", word))?,
+            ),
+            None => {
+                let task = word
+                    .parse()
+                    .with_context(|| format!("Invalid task ID '{}'", word))?;
+                let project = project_id.with_context(|| {
+                    format!(
+                        "Task {} needs a project: pass a project ID or write PROJECT/TASK",
+                        word
+                    )
+                })?;
+                (project, task)
+            }
+        };
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if ids.is_empty() {
+        bail!("No task IDs given");
+    }
+    Ok(ids)
+}
+
+/// Load the named tasks, one task list request per project. Unknown IDs are
+/// reported on stderr and skipped.
+async fn tasks_by_id(
+    client: &RepsonaClient,
+    ids: &[(u64, u64)],
+    concurrency: usize,
+) -> Result<Vec<Task>> {
+    let mut projects: Vec<u64> = ids.iter().map(|(p, _)| *p).collect();
+    projects.sort_unstable();
+    projects.dedup();
+
+    let filter = TaskFilter::default();
+    let by_project: HashMap<u64, Vec<Task>> = stream::iter(projects)
+        .map(|project_id| {
+            let filter = &filter;
+            async move {
+                let response = client
+                    .list_tasks(project_id, filter)
+                    .await
+                    .with_context(|| format!("Failed to load tasks of project {}", project_id))?;
+                Ok::<_, anyhow::Error>((project_id, response.data.tasks))
+            }
+        })
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await?;
+
+    let mut tasks = Vec::new();
+    for (project_id, task_id) in ids {
+        match by_project[project_id].iter().find(|t| t.id == *task_id) {
+            Some(task) => tasks.push(task.clone()),
+            None => eprintln!(
+                "{}",
+                format!("Task {}/{} not found, skipping", project_id, task_id).yellow()
+            ),
+        }
+    }
+    Ok(tasks)
+}
+
+/// Look up the statuses, users and tags the action names
+async fn resolve(client: &RepsonaClient, action: &BulkAction, tasks: &[Task]) -> Result<Resolved> {
+    Ok(match action {
+        BulkAction::Done => Resolved::Done,
+        BulkAction::Delete => Resolved::Delete,
+        BulkAction::Tag(action, name) => {
+            let tags = client.list_tags().await?.data.tags;
+            let tag = tags
+                .into_iter()
+                .find(|t| matches_ref(name, t.id, &[&t.name]))
+                .with_context(|| format!("Tag '{}' not found", name))?;
+            match action {
+                TagAction::Add => Resolved::AddTag(tag),
+                TagAction::Remove => Resolved::RemoveTag(tag),
+            }
+        }
+        BulkAction::Update(sets) => {
+            let mut update = Update::default();
+            let today = chrono::Local::now().date_naive();
+            for set in sets {
+                let (key, value) = set
+                    .split_once('=')
+                    .with_context(|| format!("Expected key=value in --set, got '{}'", set))?;
+                let value = value.trim();
+                match key.trim() {
+                    "title" => update.title = Some(value.to_string()),
+                    "priority" => {
+                        let priority: u32 = value
+                            .parse()
+                            .with_context(|| format!("Invalid priority '{}'", value))?;
+                        if !(1..=5).contains(&priority) {
+                            bail!("Priority must be between 1 and 5, got {}", priority);
+                        }
+                        update.priority = Some(priority);
+                    }
+                    "due" => update.due = Some(query::parse_date(value, today)?),
+                    "assignee" => update.assignee = Some(resolve_user(client, value).await?),
+                    "status" => update.status = resolve_status(client, value, tasks).await?,
+                    other => bail!(
+                        "Unknown --set key '{}' (expected title, status, priority, assignee or due)",
+                        other
+                    ),
+                }
+            }
+            Resolved::Update(Box::new(update))
+        }
+    })
+}
+
+async fn resolve_user(client: &RepsonaClient, value: &str) -> Result<User> {
+    if value.eq_ignore_ascii_case("me") {
+        return Ok(client.get_me().await?.data.user);
+    }
+    let users = client.list_users().await?.data.users;
+    users
+        .into_iter()
+        .find(|u| matches_ref(value, u.id, &[&u.name, &u.full_name]))
+        .with_context(|| format!("User '{}' not found", value))
+}
+
+/// Find the status named `value` in every project the tasks belong to
+async fn resolve_status(
+    client: &RepsonaClient,
+    value: &str,
+    tasks: &[Task],
+) -> Result<HashMap<u64, Status>> {
+    let mut projects: Vec<u64> = tasks.iter().map(|t| t.project.id).collect();
+    projects.sort_unstable();
+    projects.dedup();
+
+    let mut resolved = HashMap::new();
+    for project_id in projects {
+        let statuses = client
+            .list_project_statuses(project_id)
+            .await?
+            .data
+            .statuses;
+        let status = statuses
+            .into_iter()
+            .find(|s| matches_ref(value, s.id, &[&s.name]))
+            .with_context(|| format!("Status '{}' not found in project {}", value, project_id))?;
+        resolved.insert(project_id, status);
+    }
+    Ok(resolved)
+}
+
+/// The request and visible changes for one task, or `None` when it is already in the
+/// requested state
+fn plan(task: Task, resolved: &Resolved) -> Option<Plan> {
+    let mut changes = Vec::new();
+    let operation = match resolved {
+        Resolved::Delete => Operation::Delete,
+        Resolved::Done => {
+            if task.status.is_closed {
+                return None;
+            }
+            changes.push(change("status", &task.status.name, "Done"));
+            Operation::SetStatus(TaskStatus::Done.id())
+        }
+        Resolved::AddTag(tag) | Resolved::RemoveTag(tag) => {
+            let has_tag = task.tags.iter().any(|t| t.id == tag.id);
+            let adding = matches!(resolved, Resolved::AddTag(_));
+            if has_tag == adding {
+                return None;
+            }
+            let mut tags: Vec<&Tag> = task.tags.iter().filter(|t| t.id != tag.id).collect();
+            if adding {
+                tags.push(tag);
+            }
+            changes.push(change(
+                "tags",
+                &tag_names(task.tags.iter()),
+                &tag_names(tags.iter().copied()),
+            ));
+            Operation::Update(UpdateTaskRequest {
+                tags: Some(tags.iter().map(|t| t.id).collect()),
+                ..Default::default()
+            })
+        }
+        Resolved::Update(update) => {
+            let mut request = UpdateTaskRequest::default();
+            if let Some(title) = update.title.as_ref().filter(|t| **t != task.name) {
+                changes.push(change("title", &task.name, title));
+                request.name = Some(title.clone());
+            }
+            if let Some(status) = update
+                .status
+                .get(&task.project.id)
+                .filter(|s| s.id != task.status.id)
+            {
+                changes.push(change("status", &task.status.name, &status.name));
+                request.status = Some(status.id);
+            }
+            if let Some(priority) = update.priority.filter(|p| *p != task.priority) {
+                changes.push(change(
+                    "priority",
+                    &task.priority.to_string(),
+                    &priority.to_string(),
+                ));
+                request.priority = Some(priority);
+            }
+            if let Some(user) = update
+                .assignee
+                .as_ref()
+                .filter(|u| task.responsible_user.as_ref().map(|r| r.id) != Some(u.id))
+            {
+                let from = task.responsible_user.as_ref().map(|r| r.name.as_str());
+                changes.push(change("assignee", from.unwrap_or("-"), &user.name));
+                request.responsible_user = Some(user.id);
+            }
+            if let Some(due) = update.due.filter(|d| task.due_date != Some(*d)) {
+                let from = task.due_date.map(format_date);
+                changes.push(change(
+                    "due",
+                    from.as_deref().unwrap_or("-"),
+                    &format_date(due),
+                ));
+                request.due_date = Some(due);
+            }
+            if changes.is_empty() {
+                return None;
+            }
+            Operation::Update(request)
+        }
+    };
+    Some(Plan {
+        task,
+        changes,
+        operation,
+    })
+}
+
+fn change(field: &'static str, from: &str, to: &str) -> Change {
+    Change {
+        field,
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+fn tag_names<'a>(tags: impl Iterator<Item = &'a Tag>) -> String {
+    let names: Vec<&str> = tags.map(|t| t.name.as_str()).collect();
+    if names.is_empty() {
+        "-".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+fn past_tense(resolved: &Resolved) -> &'static str {
+    match resolved {
+        Resolved::Update(_) | Resolved::AddTag(_) | Resolved::RemoveTag(_) => "Updated",
+        Resolved::Done => "Completed",
+        Resolved::Delete => "Deleted",
+    }
+}
+
+fn print_preview(plans: &[Plan], resolved: &Resolved) {
+    let verb = match resolved {
+        Resolved::Delete => "deleted",
+        Resolved::Done => "marked as done",
+        _ => "updated",
+    };
+    eprintln!(
+        "\n{}",
+        format!("{} task(s) will be {}:", plans.len(), verb).bold()
+    );
+    for plan in plans {
+        let task = &plan.task;
+        eprintln!(
+            "  {} {} {}",
+            format!("[{}]", task.project.name).cyan(),
+            format!("#{}", task.id).dimmed(),
+            task.name
+        );
+        if matches!(plan.operation, Operation::Delete) {
+            eprintln!("      {}", "delete".red());
+        }
+        for c in &plan.changes {
+            eprintln!(
+                "      {}: {} {} {}",
+                c.field,
+                c.from.red(),
+                "→".dimmed(),
+                c.to.green()
+            );
+        }
+    }
+    eprintln!();
+}
+
+/// Send every planned request, `concurrency` at a time, reporting each as it finishes
+async fn apply(
+    client: &RepsonaClient,
+    plans: Vec<Plan>,
+    concurrency: usize,
+    json: bool,
+) -> Result<Vec<BulkResult>> {
+    let mut pending = stream::iter(plans)
+        .map(|plan| async move {
+            let project_id = plan.task.project.id;
+            let task_id = plan.task.id;
+            let outcome = match &plan.operation {
+                Operation::Update(request) => client
+                    .update_task(project_id, task_id, request)
+                    .await
+                    .map(|_| ()),
+                Operation::SetStatus(status) => client
+                    .set_task_status(project_id, task_id, *status)
+                    .await
+                    .map(|_| ()),
+                Operation::Delete => client.delete_task(project_id, task_id).await,
+            };
+            BulkResult {
+                project_id,
+                task_id,
+                title: plan.task.name,
+                changes: plan.changes,
+                ok: outcome.is_ok(),
+                error: outcome.err().map(|e| e.to_string()),
+            }
+        })
+        .buffer_unordered(concurrency);
+
+    let mut results = Vec::new();
+    while let Some(result) = pending.next().await {
+        if !json {
+            match &result.error {
+                None => eprintln!(
+                    "{} {}/{} {}",
+                    "✓".green(),
+                    result.project_id,
+                    result.task_id,
+                    result.title
+                ),
+                Some(error) => eprintln!(
+                    "{} {}",
+                    "✗".red(),
+                    format!(
+                        "{}/{} {}: {}",
+                        result.project_id, result.task_id, result.title, error
+                    )
+                    .red()
+                ),
+            }
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn status(id: u64, name: &str, is_closed: bool) -> Status {
+        Status {
+            id,
+            name: name.to_string(),
+            is_closed,
+            color: None,
+        }
+    }
+
+    fn tag(id: u64, name: &str) -> Tag {
+        Tag {
+            id,
+            name: name.to_string(),
+            color: "red".to_string(),
+        }
+    }
+
+    fn task(id: u64) -> Task {
+        Task {
+            status: status(1, "Todo", false),
+            priority: 2,
+            tags: vec![tag(3, "bug")],
+            ..test_support::task(id)
+        }
+    }
+
+    #[test]
+    fn test_parse_ids() {
+        assert_eq!(
+            parse_ids("1, 2\n3/4 2", Some(9)).unwrap(),
+            vec![(9, 1), (9, 2), (3, 4)]
+        );
+        assert!(parse_ids("12", None).is_err());
+        assert!(parse_ids("x", Some(9)).is_err());
+        assert!(parse_ids(" \n", Some(9)).is_err());
+    }
+
+    #[test]
+    fn test_filter_query_needs_a_condition_or_project() {
+        let target = |project_id: Option<u64>, filter: &str| BulkTarget {
+            project_id,
+            filter: Some(filter.to_string()),
+            ids: None,
+            concurrency: 4,
+        };
+        assert!(filter_query(&target(None, "")).is_err());
+        assert!(filter_query(&target(None, " , include-closed=true")).is_err());
+        assert_eq!(
+            filter_query(&target(Some(9), "")).unwrap().projects,
+            vec![9]
+        );
+        assert!(filter_query(&target(None, "tag=bug")).is_ok());
+    }
+
+    #[test]
+    fn test_plan_update_lists_changed_fields_only() {
+        let update = Update {
+            title: Some("Task 1".to_string()),
+            status: HashMap::from([(7, status(5, "Doing", false))]),
+            priority: Some(5),
+            ..Default::default()
+        };
+        let plan = plan(task(1), &Resolved::Update(Box::new(update))).unwrap();
+        assert_eq!(
+            plan.changes,
+            vec![
+                change("status", "Todo", "Doing"),
+                change("priority", "2", "5")
+            ]
+        );
+        let Operation::Update(request) = plan.operation else {
+            panic!("expected an update");
+        };
+        assert_eq!(request.name, None);
+        assert_eq!(request.status, Some(5));
+        assert_eq!(request.priority, Some(5));
+
+        let same = Update {
+            priority: Some(2),
+            ..Default::default()
+        };
+        assert!(super::plan(task(1), &Resolved::Update(Box::new(same))).is_none());
+    }
+
+    #[test]
+    fn test_plan_skips_tasks_already_in_state() {
+        let mut done = task(1);
+        done.status = status(0, "Done", true);
+        assert!(plan(done, &Resolved::Done).is_none());
+        assert!(plan(task(1), &Resolved::AddTag(tag(3, "bug"))).is_none());
+        assert!(plan(task(1), &Resolved::RemoveTag(tag(4, "ui"))).is_none());
+
+        let added = plan(task(1), &Resolved::AddTag(tag(4, "ui"))).unwrap();
+        assert_eq!(added.changes, vec![change("tags", "bug", "bug, ui")]);
+        let removed = plan(task(1), &Resolved::RemoveTag(tag(3, "bug"))).unwrap();
+        let Operation::Update(request) = removed.operation else {
+            panic!("expected an update");
+        };
+        assert_eq!(request.tags, Some(vec![]));
+    }
+}
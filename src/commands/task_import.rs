@@ -0,0 +1,905 @@
+use crate::api::{
+    client::is_not_found,
+    endpoints::{
+        me::TaskFilter,
+        task::{CreateTaskRequest, UpdateTaskRequest},
+    },
+    types::*,
+    RepsonaClient,
+};
+use crate::cli::ImportFormat;
+use crate::commands::board::matches_ref;
+use crate::commands::project_clone::parents_first;
+use crate::output::{print, OutputFormat};
+use crate::query;
+use crate::telemetry_span;
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "task".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+/// Task fields a column can be mapped to
+const FIELDS: [&str; 11] = [
+    "key",
+    "title",
+    "description",
+    "status",
+    "priority",
+    "due",
+    "start",
+    "assignee",
+    "milestone",
+    "tags",
+    "parent",
+];
+
+pub struct ImportOptions {
+    pub project_id: u64,
+    /// CSV or JSONL file, or `-` for stdin
+    pub file: PathBuf,
+    pub format: Option<ImportFormat>,
+    /// `field=Column` pairs
+    pub map: Vec<String>,
+    /// Where keys are recorded (default: `<file>.ids.json`)
+    pub state: Option<PathBuf>,
+    /// Also write the results report here as JSON
+    pub report: Option<PathBuf>,
+}
+
+/// One input row after column mapping
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Row {
+    /// 1-based record number in the file
+    line: usize,
+    key: Option<String>,
+    title: String,
+    description: Option<String>,
+    status: Option<String>,
+    priority: Option<u32>,
+    due: Option<u64>,
+    start: Option<u64>,
+    assignee: Option<String>,
+    milestone: Option<String>,
+    tags: Vec<String>,
+    parent: Option<Parent>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Parent {
+    /// Index of another row
+    Row(usize),
+    /// Existing task ID
+    Task(u64),
+}
+
+/// Keys imported so far, so a re-run updates instead of duplicating
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ImportState {
+    project_id: u64,
+    tasks: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Created,
+    Updated,
+    Unchanged,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RowResult {
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub title: String,
+    pub outcome: Outcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Names in the project resolved to IDs
+struct Lookup {
+    statuses: Vec<Status>,
+    milestones: Vec<Milestone>,
+    tags: Vec<Tag>,
+    users: Vec<User>,
+}
+
+/// A row ready to send
+#[derive(Debug, Default)]
+struct Fields {
+    title: String,
+    description: Option<String>,
+    status: Option<u64>,
+    priority: Option<u32>,
+    due: Option<u64>,
+    start: Option<u64>,
+    assignee: Option<u64>,
+    milestone: Option<u64>,
+    tags: Option<Vec<u64>>,
+    parent: Option<u64>,
+}
+
+pub async fn handle(client: &RepsonaClient, options: ImportOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let today = chrono::Local::now().date_naive();
+
+    let validate_attrs = phase_attrs("validate_input");
+    let rows = telemetry_span::with_span_result("validate_input", &validate_attrs, || {
+        let mapping = parse_map(&options.map)?;
+        let records = read_records(&options.file, options.format)?;
+        parse_rows(&records, &mapping, today)
+    })?;
+    let state_path = state_path(&options);
+    let mut state = match &state_path {
+        Some(path) => load_state(path, options.project_id)?,
+        None => ImportState {
+            project_id: options.project_id,
+            ..Default::default()
+        },
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let (lookup, existing) =
+        telemetry_span::with_span_async_result("load_project", &exec_attrs, || {
+            load(client, options.project_id, &rows)
+        })
+        .await?;
+
+    let mut results: Vec<Option<RowResult>> = rows.iter().map(|_| None).collect();
+    let mut task_ids: HashMap<usize, u64> = HashMap::new();
+    for i in parents_first(&rows, |r| {
+        let parent = match r.parent {
+            Some(Parent::Row(p)) => Some(p as u64),
+            _ => None,
+        };
+        (i_of(r), parent, 0)
+    }) {
+        let row = &rows[i];
+        let recorded = row.key.as_ref().and_then(|k| state.tasks.get(k)).copied();
+        let current = match recorded {
+            Some(id) => recorded_task(client, options.project_id, id, &existing).await,
+            None => Ok(None),
+        };
+        let outcome = match current {
+            Ok(current) => {
+                telemetry_span::with_span_async_result("import_row", &exec_attrs, || {
+                    import_row(
+                        client,
+                        options.project_id,
+                        row,
+                        current.as_ref(),
+                        &lookup,
+                        &task_ids,
+                    )
+                })
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        let result = match outcome {
+            Ok((outcome, id)) => {
+                task_ids.insert(i, id);
+                if let Some(key) = &row.key {
+                    state.tasks.insert(key.clone(), id);
+                    // Saved after every row so an interrupted run does not lose created keys
+                    if let Some(path) = &state_path {
+                        save_state(path, &state)?;
+                    }
+                }
+                RowResult {
+                    line: row.line,
+                    key: row.key.clone(),
+                    title: row.title.clone(),
+                    outcome,
+                    task_id: Some(id),
+                    error: None,
+                }
+            }
+            Err(e) => RowResult {
+                line: row.line,
+                key: row.key.clone(),
+                title: row.title.clone(),
+                outcome: Outcome::Failed,
+                task_id: recorded,
+                error: Some(format!("{:#}", e)),
+            },
+        };
+        if !json {
+            print_result(&result);
+        }
+        results[i] = Some(result);
+    }
+    let results: Vec<RowResult> = results.into_iter().flatten().collect();
+
+    let write_attrs = phase_attrs("write_output");
+    telemetry_span::with_span_result("write_output", &write_attrs, || {
+        if let Some(path) = &options.report {
+            std::fs::write(path, serde_json::to_string_pretty(&results)? + "\n")
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok::<_, anyhow::Error>(())
+    })?;
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&results, format),
+        OutputFormat::Human => {
+            let count = |o: Outcome| results.iter().filter(|r| r.outcome == o).count();
+            println!(
+                "\n{} created, {} updated, {} unchanged, {} failed",
+                count(Outcome::Created),
+                count(Outcome::Updated),
+                count(Outcome::Unchanged),
+                count(Outcome::Failed)
+            );
+            Ok(())
+        }
+    })?;
+
+    let failed = results
+        .iter()
+        .filter(|r| r.outcome == Outcome::Failed)
+        .count();
+    if failed > 0 {
+        bail!("{} of {} row(s) failed to import", failed, results.len());
+    }
+    Ok(())
+}
+
+/// Row index, used as a stand-in ID when ordering parents before children
+fn i_of(row: &Row) -> u64 {
+    (row.line - 1) as u64
+}
+
+fn print_result(result: &RowResult) {
+    let id = result
+        .task_id
+        .map(|id| format!(" (#{})", id))
+        .unwrap_or_default();
+    match result.outcome {
+        Outcome::Failed => eprintln!(
+            "{} line {}: {}: {}",
+            "✗".red(),
+            result.line,
+            result.title,
+            result.error.as_deref().unwrap_or_default()
+        ),
+        outcome => {
+            let label = match outcome {
+                Outcome::Created => "created".green(),
+                Outcome::Updated => "updated".yellow(),
+                _ => "unchanged".dimmed(),
+            };
+            println!("{} {} {}{}", "✓".green(), label, result.title, id);
+        }
+    }
+}
+
+/// `field=Column` pairs, comma-separated or repeated
+fn parse_map(pairs: &[String]) -> Result<HashMap<String, String>> {
+    let mut mapping = HashMap::new();
+    for pair in pairs.iter().flat_map(|p| p.split(',')) {
+        if pair.trim().is_empty() {
+            continue;
+        }
+        let (field, column) = pair
+            .split_once('=')
+            .with_context(|| format!("Expected field=Column in --map, got '{}'", pair))?;
+        let field = field.trim().to_lowercase();
+        if !FIELDS.contains(&field.as_str()) {
+            bail!(
+                "Unknown --map field '{}' (expected {})",
+                field,
+                FIELDS.join(", ")
+            );
+        }
+        mapping.insert(field, column.trim().to_string());
+    }
+    Ok(mapping)
+}
+
+/// Records as column → value, in file order
+fn read_records(path: &Path, format: Option<ImportFormat>) -> Result<Vec<HashMap<String, String>>> {
+    let mut text = String::new();
+    if path == Path::new("-") {
+        std::io::stdin().read_to_string(&mut text)?;
+    } else {
+        text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+    }
+    let format = format.unwrap_or_else(|| match path.extension().and_then(|e| e.to_str()) {
+        Some("jsonl" | "ndjson") => ImportFormat::Jsonl,
+        _ => ImportFormat::Csv,
+    });
+    match format {
+        ImportFormat::Csv => parse_csv(&text),
+        ImportFormat::Jsonl => parse_jsonl(&text),
+    }
+}
+
+fn parse_csv(text: &str) -> Result<Vec<HashMap<String, String>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.trim_start_matches('\u{feff}').as_bytes());
+    let headers: Vec<String> = reader
+        .headers()?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    let mut records = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.with_context(|| format!("Invalid CSV in record {}", i + 1))?;
+        records.push(
+            headers
+                .iter()
+                .cloned()
+                .zip(record.iter().map(str::to_string))
+                .collect(),
+        );
+    }
+    Ok(records)
+}
+
+fn parse_jsonl(text: &str) -> Result<Vec<HashMap<String, String>>> {
+    let mut records = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(line)
+            .with_context(|| format!("Line {} is not a JSON object", i + 1))?;
+        records.push(
+            object
+                .into_iter()
+                .filter_map(|(k, v)| text_value(v).map(|v| (k, v)))
+                .collect(),
+        );
+    }
+    Ok(records)
+}
+
+fn text_value(value: serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Array(items) => Some(
+            items
+                .into_iter()
+                .filter_map(text_value)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Apply the column mapping and check every value that needs no API lookup
+fn parse_rows(
+    records: &[HashMap<String, String>],
+    mapping: &HashMap<String, String>,
+    today: NaiveDate,
+) -> Result<Vec<Row>> {
+    if records.is_empty() {
+        bail!("No rows to import");
+    }
+    let columns: Vec<&String> = records.iter().flat_map(|r| r.keys()).collect();
+    let column_of = |field: &str| -> Option<String> {
+        let wanted = mapping.get(field).map(String::as_str).unwrap_or(field);
+        columns
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(wanted))
+            .map(|c| c.to_string())
+    };
+    if let Some((field, column)) = mapping.iter().find(|(f, _)| column_of(f).is_none()) {
+        bail!("Column '{}' (mapped to {}) not found", column, field);
+    }
+    let title_column = column_of("title").context(
+        "No title column; name one with --map title=<Column> (e.g. --map title=Summary)",
+    )?;
+    let columns: HashMap<&str, String> = FIELDS
+        .iter()
+        .filter_map(|f| column_of(f).map(|c| (*f, c)))
+        .collect();
+
+    let mut rows = Vec::with_capacity(records.len());
+    let mut keys: HashMap<String, usize> = HashMap::new();
+    let mut parents: Vec<Option<String>> = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        let line = i + 1;
+        let get = |field: &str| {
+            columns
+                .get(field)
+                .and_then(|c| record.get(c))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+        let context = |field: &str| format!("Record {}: invalid {}", line, field);
+        let title = record
+            .get(&title_column)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .with_context(|| format!("Record {}: title is empty", line))?;
+        let priority = get("priority")
+            .map(|p| -> Result<u32> {
+                let p: u32 = p.parse().context("expected 1 to 5")?;
+                if !(1..=5).contains(&p) {
+                    bail!("must be between 1 and 5, got {}", p);
+                }
+                Ok(p)
+            })
+            .transpose()
+            .with_context(|| context("priority"))?;
+        let row = Row {
+            line,
+            key: get("key").map(str::to_string),
+            title: title.to_string(),
+            description: get("description").map(str::to_string),
+            status: get("status").map(str::to_string),
+            priority,
+            due: get("due")
+                .map(|d| parse_day(d, today))
+                .transpose()
+                .with_context(|| context("due"))?,
+            start: get("start")
+                .map(|d| parse_day(d, today))
+                .transpose()
+                .with_context(|| context("start"))?,
+            assignee: get("assignee").map(str::to_string),
+            milestone: get("milestone").map(str::to_string),
+            tags: get("tags")
+                .map(|t| {
+                    t.split([',', ';'])
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            parent: None,
+        };
+        if let Some(key) = &row.key {
+            if let Some(first) = keys.insert(key.clone(), i) {
+                bail!(
+                    "Record {}: key '{}' is already used by record {}",
+                    line,
+                    key,
+                    first + 1
+                );
+            }
+        }
+        parents.push(get("parent").map(str::to_string));
+        rows.push(row);
+    }
+
+    // A parent names a key in the file, or failing that an existing task ID
+    for (i, parent) in parents.into_iter().enumerate() {
+        let Some(parent) = parent else {
+            continue;
+        };
+        rows[i].parent = Some(match keys.get(&parent) {
+            Some(&p) if p == i => bail!("Record {}: task is its own parent", i + 1),
+            Some(&p) => Parent::Row(p),
+            None => Parent::Task(parent.trim_start_matches('#').parse().with_context(|| {
+                format!(
+                    "Record {}: parent '{}' is neither a key in the file nor a task ID",
+                    i + 1,
+                    parent
+                )
+            })?),
+        });
+    }
+    check_cycles(&rows)?;
+    Ok(rows)
+}
+
+fn check_cycles(rows: &[Row]) -> Result<()> {
+    for start in 0..rows.len() {
+        let mut seen = vec![start];
+        let mut next = &rows[start].parent;
+        while let Some(Parent::Row(p)) = next {
+            if seen.contains(p) {
+                let mut lines: Vec<String> = seen.iter().map(|i| (i + 1).to_string()).collect();
+                lines.sort();
+                bail!(
+                    "Circular parent references between records {}",
+                    lines.join(", ")
+                );
+            }
+            seen.push(*p);
+            next = &rows[*p].parent;
+        }
+    }
+    Ok(())
+}
+
+/// A date as written in a spreadsheet: `YYYY-MM-DD`, `YYYY/MM/DD`, an offset such as `+7d`,
+/// or any of those followed by a time, which is ignored
+fn parse_day(value: &str, today: NaiveDate) -> Result<u64> {
+    let date = value.split(['T', ' ']).next().unwrap_or(value);
+    if let Ok(day) = NaiveDate::parse_from_str(date, "%Y/%m/%d") {
+        return Ok(query::start_of_day(day));
+    }
+    query::parse_date(date, today)
+}
+
+fn state_path(options: &ImportOptions) -> Option<PathBuf> {
+    if let Some(state) = &options.state {
+        return Some(state.clone());
+    }
+    if options.file == Path::new("-") {
+        return None;
+    }
+    Some(options.file.with_extension("ids.json"))
+}
+
+fn load_state(path: &Path, project_id: u64) -> Result<ImportState> {
+    if !path.exists() {
+        return Ok(ImportState {
+            project_id,
+            ..Default::default()
+        });
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let state: ImportState = serde_json::from_str(&text)
+        .with_context(|| format!("Invalid import state {}", path.display()))?;
+    if state.project_id != project_id {
+        bail!(
+            "{} records an import into project {}; pass --state to start a new one",
+            path.display(),
+            state.project_id
+        );
+    }
+    Ok(state)
+}
+
+/// Write the state to a temporary file, then rename it over `path`
+fn save_state(path: &Path, state: &ImportState) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, serde_json::to_string_pretty(state)? + "\n")
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The task recorded for a key. Closed tasks and tasks beyond the listed page are fetched
+/// one by one; only a task that no longer exists is created again.
+async fn recorded_task(
+    client: &RepsonaClient,
+    project_id: u64,
+    id: u64,
+    existing: &HashMap<u64, Task>,
+) -> Result<Option<Task>> {
+    if let Some(task) = existing.get(&id) {
+        return Ok(Some(task.clone()));
+    }
+    match client.get_task(project_id, id).await {
+        Ok(response) => Ok(Some(response.data.task)),
+        Err(e) if is_not_found(&e) => {
+            eprintln!(
+                "{}",
+                format!("Task #{} no longer exists; creating it again", id).yellow()
+            );
+            Ok(None)
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to load task #{}", id)),
+    }
+}
+
+async fn load(
+    client: &RepsonaClient,
+    project_id: u64,
+    rows: &[Row],
+) -> Result<(Lookup, HashMap<u64, Task>)> {
+    let filter = TaskFilter::default();
+    let (statuses, milestones, tags, tasks) = tokio::try_join!(
+        client.list_project_statuses(project_id),
+        client.list_project_milestones(project_id),
+        client.list_tags(),
+        client.list_tasks(project_id, &filter),
+    )?;
+    let users = if rows.iter().any(|r| r.assignee.is_some()) {
+        client.list_users().await?.data.users
+    } else {
+        Vec::new()
+    };
+    let lookup = Lookup {
+        statuses: statuses.data.statuses,
+        milestones: milestones.data.milestones,
+        tags: tags.data.tags,
+        users,
+    };
+    let tasks = tasks.data.tasks.into_iter().map(|t| (t.id, t)).collect();
+    Ok((lookup, tasks))
+}
+
+/// Resolve names in `row` to IDs
+fn resolve(row: &Row, lookup: &Lookup, task_ids: &HashMap<usize, u64>) -> Result<Fields> {
+    let status = row
+        .status
+        .as_deref()
+        .map(|name| {
+            lookup
+                .statuses
+                .iter()
+                .find(|s| matches_ref(name, s.id, &[&s.name]))
+                .map(|s| s.id)
+                .with_context(|| format!("Status '{}' not found", name))
+        })
+        .transpose()?;
+    let assignee = row
+        .assignee
+        .as_deref()
+        .map(|name| {
+            lookup
+                .users
+                .iter()
+                .find(|u| matches_ref(name, u.id, &[&u.name, &u.full_name, &u.email]))
+                .map(|u| u.id)
+                .with_context(|| format!("User '{}' not found", name))
+        })
+        .transpose()?;
+    let milestone = row
+        .milestone
+        .as_deref()
+        .map(|name| {
+            lookup
+                .milestones
+                .iter()
+                .find(|m| matches_ref(name, m.id, &[&m.name]))
+                .map(|m| m.id)
+                .with_context(|| format!("Milestone '{}' not found", name))
+        })
+        .transpose()?;
+    let tags = row
+        .tags
+        .iter()
+        .map(|name| {
+            lookup
+                .tags
+                .iter()
+                .find(|t| matches_ref(name, t.id, &[&t.name]))
+                .map(|t| t.id)
+                .with_context(|| format!("Tag '{}' not found", name))
+        })
+        .collect::<Result<Vec<u64>>>()?;
+    let parent = match row.parent {
+        Some(Parent::Task(id)) => Some(id),
+        Some(Parent::Row(p)) => Some(
+            *task_ids
+                .get(&p)
+                .with_context(|| format!("Parent record {} was not imported", p + 1))?,
+        ),
+        None => None,
+    };
+    Ok(Fields {
+        title: row.title.clone(),
+        description: row.description.clone(),
+        status,
+        priority: row.priority,
+        due: row.due,
+        start: row.start,
+        assignee,
+        milestone,
+        tags: (!row.tags.is_empty()).then_some(tags),
+        parent,
+    })
+}
+
+/// The fields of `task` that differ from `fields`, or `None` when nothing does
+fn update_request(task: &Task, fields: &Fields) -> Option<UpdateTaskRequest> {
+    fn differs<T: PartialEq + Clone>(want: &Option<T>, have: Option<T>) -> Option<T> {
+        want.clone().filter(|w| Some(w) != have.as_ref())
+    }
+    let mut current_tags: Vec<u64> = task.tags.iter().map(|t| t.id).collect();
+    current_tags.sort_unstable();
+    let tags = fields.tags.clone().filter(|want| {
+        let mut want = want.clone();
+        want.sort_unstable();
+        want != current_tags
+    });
+    let request = UpdateTaskRequest {
+        name: (fields.title != task.name).then(|| fields.title.clone()),
+        description: differs(&fields.description, task.description.clone()),
+        status: differs(&fields.status, Some(task.status.id)),
+        priority: differs(&fields.priority, Some(task.priority)),
+        due_date: differs(&fields.due, task.due_date),
+        start_date: differs(&fields.start, task.start_date),
+        responsible_user: differs(
+            &fields.assignee,
+            task.responsible_user.as_ref().map(|u| u.id),
+        ),
+        milestone: differs(&fields.milestone, task.milestone.as_ref().map(|m| m.id)),
+        tags,
+        parent: differs(&fields.parent, task.parent),
+        ..Default::default()
+    };
+    let unchanged = serde_json::to_value(&request)
+        .ok()
+        .and_then(|v| v.as_object().map(|o| o.is_empty()))
+        .unwrap_or(false);
+    (!unchanged).then_some(request)
+}
+
+async fn import_row(
+    client: &RepsonaClient,
+    project_id: u64,
+    row: &Row,
+    current: Option<&Task>,
+    lookup: &Lookup,
+    task_ids: &HashMap<usize, u64>,
+) -> Result<(Outcome, u64)> {
+    let fields = resolve(row, lookup, task_ids)?;
+    if let Some(task) = current {
+        return match update_request(task, &fields) {
+            Some(request) => {
+                client.update_task(project_id, task.id, &request).await?;
+                Ok((Outcome::Updated, task.id))
+            }
+            None => Ok((Outcome::Unchanged, task.id)),
+        };
+    }
+    let request = CreateTaskRequest {
+        name: fields.title,
+        description: fields.description,
+        status: fields.status,
+        priority: fields.priority,
+        due_date: fields.due,
+        start_date: fields.start,
+        responsible_user: fields.assignee,
+        parent: fields.parent,
+        milestone: fields.milestone,
+        tags: fields.tags,
+        add_to_bottom: Some(true),
+        ..Default::default()
+    };
+    let task = client.create_task(project_id, &request).await?.data.task;
+    Ok((Outcome::Created, task.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()
+    }
+
+    #[test]
+    fn test_parse_rows_maps_columns_and_parents() {
+        let csv = "ID,Summary,Due Date,Priority,Tags,Parent\n\
+                   A-1,Epic,2024/06/10,5,\"bug, ui\",\n\
+                   A-2,Child,2024-06-12 09:00,,,A-1\n\
+                   ,Loose,,,,#345\n";
+        let records = parse_csv(csv).unwrap();
+        let mapping = parse_map(&[
+            "key=ID,title=Summary".to_string(),
+            "due=Due Date".to_string(),
+        ])
+        .unwrap();
+        let rows = parse_rows(&records, &mapping, today()).unwrap();
+
+        assert_eq!(rows[0].key.as_deref(), Some("A-1"));
+        assert_eq!(rows[0].priority, Some(5));
+        assert_eq!(rows[0].tags, vec!["bug", "ui"]);
+        assert_eq!(
+            rows[0].due,
+            Some(query::start_of_day(
+                NaiveDate::from_ymd_opt(2024, 6, 10).unwrap()
+            ))
+        );
+        assert_eq!(rows[1].parent, Some(Parent::Row(0)));
+        assert_eq!(rows[2].parent, Some(Parent::Task(345)));
+        assert_eq!(rows[2].key, None);
+
+        let order = parents_first(&rows, |r| {
+            let parent = match r.parent {
+                Some(Parent::Row(p)) => Some(p as u64),
+                _ => None,
+            };
+            (i_of(r), parent, 0)
+        });
+        assert_eq!(order, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_parse_rows_rejects_bad_input() {
+        let mapping = HashMap::new();
+        let parse = |text: &str| parse_rows(&parse_csv(text).unwrap(), &mapping, today());
+
+        let err = parse("key,title\n1,a\n1,b\n").unwrap_err();
+        assert!(err.to_string().contains("already used"), "{}", err);
+        let err = parse("key,title,parent\n1,a,2\n2,b,1\n").unwrap_err();
+        assert!(err.to_string().contains("Circular"), "{}", err);
+        let err = parse("title,priority\na,9\n").unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("between 1 and 5"),
+            "{:#}",
+            err
+        );
+        let err = parse("name\na\n").unwrap_err();
+        assert!(err.to_string().contains("--map title="), "{}", err);
+        assert!(parse_map(&["owner=Owner".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_jsonl_flattens_values() {
+        let records =
+            parse_jsonl("{\"title\":\"a\",\"priority\":3,\"tags\":[\"x\",\"y\"],\"due\":null}\n\n")
+                .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["priority"], "3");
+        assert_eq!(records[0]["tags"], "x,y");
+        assert!(!records[0].contains_key("due"));
+    }
+
+    #[test]
+    fn test_update_request_only_sends_changes() {
+        let task = Task {
+            description: Some("text".to_string()),
+            priority: 3,
+            tags: vec![Tag {
+                id: 2,
+                name: "ui".to_string(),
+                color: "red".to_string(),
+            }],
+            ..test_support::task(7).named("Spec").in_project(12, "src")
+        };
+        let same = Fields {
+            title: "Spec".to_string(),
+            priority: Some(3),
+            tags: Some(vec![2]),
+            ..Default::default()
+        };
+        assert!(update_request(&task, &same).is_none());
+
+        let changed = Fields {
+            priority: Some(5),
+            ..same
+        };
+        let request = update_request(&task, &changed).unwrap();
+        assert_eq!(request.priority, Some(5));
+        assert_eq!(request.name, None);
+        assert_eq!(request.tags, None);
+    }
+
+    #[test]
+    fn test_state_is_saved_and_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.ids.json");
+        let mut state = ImportState {
+            project_id: 3,
+            ..Default::default()
+        };
+        state.tasks.insert("A-1".to_string(), 10);
+        save_state(&path, &state).unwrap();
+        state.tasks.insert("A-2".to_string(), 11);
+        save_state(&path, &state).unwrap();
+
+        let loaded = load_state(&path, 3).unwrap();
+        assert_eq!(loaded.tasks.get("A-2"), Some(&11));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert!(load_state(&path, 4).is_err());
+    }
+}
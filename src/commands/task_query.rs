@@ -0,0 +1,143 @@
+use crate::api::{endpoints::me::TaskFilter, types::*, RepsonaClient};
+use crate::output::{print_item, OutputFormat};
+use crate::query::TaskQuery;
+use crate::telemetry_span;
+use anyhow::Result;
+use colored::Colorize;
+use futures::{stream, StreamExt};
+use std::cmp::Ordering;
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "task".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct QueryOptions {
+    pub query: TaskQuery,
+    pub limit: Option<usize>,
+    pub concurrency: usize,
+}
+
+pub async fn handle(client: &RepsonaClient, options: QueryOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let mut tasks =
+        telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || {
+            find_tasks(client, &options.query, options.concurrency)
+        })
+        .await?;
+
+    if let Some(limit) = options.limit {
+        tasks.truncate(limit);
+    }
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || {
+        print_item("task_query", &tasks, format)
+    })
+}
+
+/// Fetch tasks from every selected project concurrently and keep those matching `query`,
+/// sorted by due date, then priority.
+///
+/// A project that fails to load is reported on stderr and skipped.
+pub async fn find_tasks(
+    client: &RepsonaClient,
+    query: &TaskQuery,
+    concurrency: usize,
+) -> Result<Vec<Task>> {
+    let me = if query.needs_me() {
+        Some(client.get_me().await?.data.user.id)
+    } else {
+        None
+    };
+    let matcher = query.compile(chrono::Local::now().date_naive(), me)?;
+
+    let projects: Vec<Project> = client.list_projects().await?.data.projects;
+    let selected: Vec<&Project> = projects
+        .iter()
+        .filter(|p| {
+            if query.projects.is_empty() {
+                !p.is_closed
+            } else {
+                query.projects.contains(&p.id)
+            }
+        })
+        .collect();
+
+    let filter = TaskFilter::default();
+    let results: Vec<(&Project, Result<ApiResponse<TasksData>>)> = stream::iter(selected)
+        .map(|project| {
+            let filter = &filter;
+            async move { (project, client.list_tasks(project.id, filter).await) }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut tasks = Vec::new();
+    for (project, result) in results {
+        match result {
+            Ok(response) => tasks.extend(
+                response
+                    .data
+                    .tasks
+                    .into_iter()
+                    .filter(|t| matcher.matches(t)),
+            ),
+            Err(e) => eprintln!(
+                "{}",
+                format!("Skipping project {} ({}): {}", project.name, project.id, e).yellow()
+            ),
+        }
+    }
+    tasks.sort_by(compare_tasks);
+    Ok(tasks)
+}
+
+/// Earliest due date first (undated last), then highest priority, then project and ID
+fn compare_tasks(a: &Task, b: &Task) -> Ordering {
+    let due = match (a.due_date, b.due_date) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+    due.then_with(|| b.priority.cmp(&a.priority))
+        .then_with(|| a.project.name.cmp(&b.project.name))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn task(id: u64, due: Option<u64>, priority: u32) -> Task {
+        Task {
+            due_date: due,
+            priority,
+            ..test_support::task(id)
+        }
+    }
+
+    #[test]
+    fn test_compare_tasks_orders_by_due_then_priority() {
+        let mut tasks = [
+            task(1, None, 5),
+            task(2, Some(200), 1),
+            task(3, Some(100), 1),
+            task(4, Some(200), 3),
+        ];
+        tasks.sort_by(compare_tasks);
+        let ids: Vec<u64> = tasks.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![3, 4, 2, 1]);
+    }
+}
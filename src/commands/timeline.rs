@@ -0,0 +1,392 @@
+use crate::api::{types::*, RepsonaClient};
+use crate::commands::calendar::local_date;
+use crate::commands::task_query::find_tasks;
+use crate::markdown::RenderOptions;
+use crate::output::{pad_to_width, print, truncate_to_width, OutputFormat};
+use crate::query::{parse_date, TaskQuery};
+use crate::telemetry_span;
+use anyhow::{bail, Result};
+use chrono::{Datelike, Local, NaiveDate};
+use colored::Colorize;
+use serde::Serialize;
+
+const CONCURRENCY: usize = 4;
+const MIN_LABEL_WIDTH: usize = 16;
+const MAX_LABEL_WIDTH: usize = 40;
+const MIN_CHART_WIDTH: usize = 20;
+
+fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("command.group", "timeline".to_string()),
+        ("op.phase", phase.to_string()),
+    ]
+}
+
+pub struct TimelineOptions {
+    pub project_id: u64,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub include_closed: bool,
+}
+
+/// Tasks of one milestone (or of no milestone), in start order
+#[derive(Debug, Serialize)]
+struct Lane<'a> {
+    milestone: Option<&'a Milestone>,
+    tasks: Vec<&'a Task>,
+}
+
+/// A task's span as local dates; a task with only one of the dates covers a single day
+fn span(task: &Task) -> Option<(NaiveDate, NaiveDate)> {
+    let due = task.due_date.map(local_date);
+    let start = task.start_date.map(local_date);
+    match (start, due) {
+        (Some(start), Some(due)) if start <= due => Some((start, due)),
+        (_, Some(due)) => Some((due, due)),
+        (Some(start), None) => Some((start, start)),
+        (None, None) => None,
+    }
+}
+
+pub async fn handle(client: &RepsonaClient, options: TimelineOptions, json: bool) -> Result<()> {
+    let format = if json {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let today = Local::now().date_naive();
+    let query = TaskQuery {
+        projects: vec![options.project_id],
+        include_closed: options.include_closed,
+        ..Default::default()
+    };
+
+    let exec_attrs = phase_attrs("execute_operation");
+    let (tasks, milestones) =
+        telemetry_span::with_span_async_result("execute_operation", &exec_attrs, || async {
+            let (tasks, milestones) = tokio::join!(
+                find_tasks(client, &query, CONCURRENCY),
+                client.list_project_milestones(options.project_id)
+            );
+            Ok::<_, anyhow::Error>((tasks?, milestones?.data.milestones))
+        })
+        .await?;
+
+    let prepare_attrs = phase_attrs("prepare_request");
+    let (lanes, range, undated) =
+        telemetry_span::with_span_result("prepare_request", &prepare_attrs, || {
+            let from = options
+                .from
+                .as_deref()
+                .map(|v| parse_date(v, today).map(local_date))
+                .transpose()?;
+            let to = options
+                .to
+                .as_deref()
+                .map(|v| parse_date(v, today).map(local_date))
+                .transpose()?;
+            let undated = tasks.iter().filter(|t| span(t).is_none()).count();
+            let range = date_range(&tasks, from, to, today);
+            if let Some((first, last)) = range {
+                if first > last {
+                    bail!("--from {} is after --to {}", first, last);
+                }
+            }
+            Ok((group_by_milestone(&tasks, &milestones), range, undated))
+        })?;
+
+    let render_attrs = phase_attrs("render_output");
+    telemetry_span::with_span_result("render_output", &render_attrs, || match format {
+        OutputFormat::Json => print(&lanes, format),
+        OutputFormat::Human => {
+            match range {
+                Some(range) => {
+                    let width = RenderOptions::for_stdout().width;
+                    print!("{}", render_timeline(&lanes, range, today, width));
+                }
+                None => println!("No tasks with a start or due date."),
+            }
+            if undated > 0 {
+                println!("{} task(s) without dates not shown", undated);
+            }
+            Ok(())
+        }
+    })
+}
+
+/// Days covered by the chart: the task spans plus today, unless overridden
+fn date_range(
+    tasks: &[Task],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let spans: Vec<(NaiveDate, NaiveDate)> = tasks.iter().filter_map(span).collect();
+    if spans.is_empty() && (from.is_none() || to.is_none()) {
+        return None;
+    }
+    let first = spans.iter().map(|s| s.0).chain([today]).min()?;
+    let last = spans.iter().map(|s| s.1).chain([today]).max()?;
+    Some((from.unwrap_or(first), to.unwrap_or(last)))
+}
+
+/// Lanes in milestone due order, then tasks without a milestone
+fn group_by_milestone<'a>(tasks: &'a [Task], milestones: &'a [Milestone]) -> Vec<Lane<'a>> {
+    let mut ordered: Vec<Option<&Milestone>> = milestones.iter().map(Some).collect();
+    // Milestones missing from the list (e.g. closed ones) still get their lane
+    for task in tasks {
+        if let Some(m) = &task.milestone {
+            if !ordered.iter().flatten().any(|o| o.id == m.id) {
+                ordered.push(Some(m));
+            }
+        }
+    }
+    ordered.sort_by_key(|m| m.map(|m| (m.due_date.unwrap_or(u64::MAX), m.id)));
+    ordered.push(None);
+
+    ordered
+        .into_iter()
+        .filter_map(|milestone| {
+            let mut tasks: Vec<&Task> = tasks
+                .iter()
+                .filter(|t| span(t).is_some())
+                .filter(|t| t.milestone.as_ref().map(|m| m.id) == milestone.map(|m| m.id))
+                .collect();
+            tasks.sort_by_key(|t| (span(t), t.id));
+            (!tasks.is_empty()).then_some(Lane { milestone, tasks })
+        })
+        .collect()
+}
+
+/// Maps dates onto chart columns, several days per column when the range is long
+struct Scale {
+    first: NaiveDate,
+    days_per_column: i64,
+    columns: usize,
+}
+
+impl Scale {
+    fn new((first, last): (NaiveDate, NaiveDate), width: usize) -> Self {
+        let days = (last - first).num_days() + 1;
+        let days_per_column = (days + width as i64 - 1) / width as i64;
+        let days_per_column = days_per_column.max(1);
+        Scale {
+            first,
+            days_per_column,
+            columns: ((days + days_per_column - 1) / days_per_column) as usize,
+        }
+    }
+
+    /// Column of a date, clamped to the chart; None when the date lies outside it
+    fn column(&self, date: NaiveDate) -> Option<usize> {
+        let offset = (date - self.first).num_days();
+        if offset < 0 {
+            return None;
+        }
+        let column = (offset / self.days_per_column) as usize;
+        (column < self.columns).then_some(column)
+    }
+
+    /// Columns covered by a span, clipped to the chart
+    fn columns_of(&self, (start, end): (NaiveDate, NaiveDate)) -> Option<(usize, usize)> {
+        let last =
+            self.first + chrono::Duration::days(self.columns as i64 * self.days_per_column - 1);
+        if end < self.first || start > last {
+            return None;
+        }
+        let from = self.column(start.max(self.first))?;
+        let to = self.column(end.min(last))?;
+        Some((from, to))
+    }
+}
+
+/// A chart row: blank except for the today marker
+fn empty_row(scale: &Scale, today: NaiveDate) -> Vec<String> {
+    let mut row = vec![" ".to_string(); scale.columns];
+    if let Some(column) = scale.column(today) {
+        row[column] = "┊".dimmed().to_string();
+    }
+    row
+}
+
+fn render_timeline(
+    lanes: &[Lane],
+    range: (NaiveDate, NaiveDate),
+    today: NaiveDate,
+    width: usize,
+) -> String {
+    let label_width = (width / 3).clamp(MIN_LABEL_WIDTH, MAX_LABEL_WIDTH);
+    let chart_width = width.saturating_sub(label_width + 1).max(MIN_CHART_WIDTH);
+    let scale = Scale::new(range, chart_width);
+    let mut out = String::new();
+
+    // Axis: month labels above a ruler with ticks at month starts and today
+    let mut ticks: Vec<(usize, String)> = range
+        .0
+        .iter_days()
+        .take_while(|d| *d <= range.1)
+        .filter(|d| d.day() == 1)
+        .filter_map(|d| Some((scale.column(d)?, d.format("%b %-d").to_string())))
+        .collect();
+    // Label the first day too, unless a month start is right next to it
+    let first_label = range.0.format("%b %-d").to_string();
+    if ticks
+        .first()
+        .is_none_or(|(column, _)| *column > first_label.len())
+    {
+        ticks.insert(0, (0, first_label));
+    }
+    let mut labels = vec![' '; scale.columns];
+    let mut ruler = vec!["─".to_string(); scale.columns];
+    ruler[0] = "┬".to_string();
+    let mut next_free = 0;
+    for (column, text) in ticks {
+        if column >= next_free && column + text.len() <= scale.columns {
+            for (i, c) in text.chars().enumerate() {
+                labels[column + i] = c;
+            }
+            next_free = column + text.len() + 1;
+        }
+        ruler[column] = "┬".to_string();
+    }
+    if let Some(column) = scale.column(today) {
+        ruler[column] = "▼".bold().to_string();
+    }
+    out.push_str(&format!(
+        "{} {}\n",
+        " ".repeat(label_width),
+        labels.iter().collect::<String>()
+    ));
+    out.push_str(&format!("{} {}\n", " ".repeat(label_width), ruler.concat()));
+
+    for lane in lanes {
+        let title = match lane.milestone {
+            Some(m) => format!("◆ {}", m.name),
+            None => "No milestone".to_string(),
+        };
+        let mut row = empty_row(&scale, today);
+        if let Some(column) = lane
+            .milestone
+            .and_then(|m| m.due_date)
+            .and_then(|due| scale.column(local_date(due)))
+        {
+            row[column] = "◆".bold().to_string();
+        }
+        out.push_str(&format!(
+            "{} {}\n",
+            pad_to_width(&truncate_to_width(&title, label_width), label_width).bold(),
+            row.concat()
+        ));
+
+        for task in &lane.tasks {
+            let Some((start, due)) = span(task) else {
+                continue;
+            };
+            let overdue = !task.status.is_closed && task.due_date.is_some() && due < today;
+            let marker = if task.status.is_closed {
+                "✓"
+            } else if overdue {
+                "!"
+            } else {
+                " "
+            };
+            let label = pad_to_width(
+                &truncate_to_width(
+                    &format!("{} #{} {}", marker, task.id, task.name),
+                    label_width,
+                ),
+                label_width,
+            );
+            let mut row = empty_row(&scale, today);
+            if let Some((from, to)) = scale.columns_of((start, due)) {
+                for cell in &mut row[from..=to] {
+                    *cell = if task.status.is_closed {
+                        "█".dimmed().to_string()
+                    } else if overdue {
+                        "█".red().to_string()
+                    } else {
+                        "█".to_string()
+                    };
+                }
+            }
+            let label = if overdue {
+                label.red().to_string()
+            } else {
+                label
+            };
+            out.push_str(&format!("{} {}\n", label, row.concat()));
+        }
+    }
+
+    out.push_str(&format!(
+        "\n{} to {}{}  █ task  ◆ milestone  ┊ today  ! overdue\n",
+        range.0,
+        range.1,
+        if scale.days_per_column > 1 {
+            format!(" ({} days per column)", scale.days_per_column)
+        } else {
+            String::new()
+        }
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+    use crate::output::test_support::color_override;
+
+    #[test]
+    fn test_scale_columns() {
+        let day = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        let scale = Scale::new((day(11, 1), day(11, 30)), 60);
+        assert_eq!((scale.days_per_column, scale.columns), (1, 30));
+        assert_eq!(scale.column(day(11, 3)), Some(2));
+        assert_eq!(scale.column(day(12, 1)), None);
+        assert_eq!(scale.columns_of((day(10, 20), day(11, 2))), Some((0, 1)));
+        assert_eq!(scale.columns_of((day(12, 2), day(12, 5))), None);
+
+        // 90 days in 40 columns: three days per column
+        let scale = Scale::new((day(10, 1), day(12, 29)), 40);
+        assert_eq!((scale.days_per_column, scale.columns), (3, 30));
+        assert_eq!(scale.column(day(10, 4)), Some(1));
+    }
+
+    fn task(id: u64, start: Option<NaiveDate>, due: Option<NaiveDate>) -> Task {
+        Task {
+            due_date: due.map(crate::query::start_of_day),
+            start_date: start.map(crate::query::start_of_day),
+            ..test_support::task(id)
+        }
+    }
+
+    #[test]
+    fn test_render_axis_and_lanes() {
+        let _color = color_override(false);
+        let day = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        let tasks = vec![
+            task(2, Some(day(11, 4)), Some(day(11, 6))),
+            task(1, None, Some(day(10, 30))),
+            task(3, None, None),
+        ];
+        let lanes = group_by_milestone(&tasks, &[]);
+        assert_eq!(lanes.len(), 1);
+        assert_eq!(
+            lanes[0].tasks.iter().map(|t| t.id).collect::<Vec<_>>(),
+            [1, 2]
+        );
+
+        let grid = render_timeline(&lanes, (day(10, 28), day(11, 10)), day(11, 2), 60);
+        let lines: Vec<&str> = grid.lines().collect();
+        let chart = |line: &str| line.chars().skip(21).collect::<String>();
+        assert_eq!(chart(lines[0]), "    Nov 1     ");
+        assert_eq!(chart(lines[1]), "┬───┬▼────────");
+        assert!(lines[2].starts_with("No milestone"));
+        // Overdue task on Oct 30, the other spanning Nov 4-6; ┊ marks today
+        assert!(lines[3].starts_with("! #1 Task 1"));
+        assert_eq!(chart(lines[3]), "  █  ┊        ");
+        assert_eq!(chart(lines[4]), "     ┊ ███    ");
+        assert!(grid.contains("2026-10-28 to 2026-11-10  █ task"));
+    }
+}
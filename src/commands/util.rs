@@ -1,7 +1,9 @@
 use crate::api::RepsonaClient;
 use crate::telemetry_span;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::Colorize;
+use std::io::Read;
+use std::path::PathBuf;
 
 fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
     vec![
@@ -10,6 +12,29 @@ fn phase_attrs(phase: &str) -> Vec<(&'static str, String)> {
     ]
 }
 
+/// Text given inline or with `--body-file`, where "-" reads stdin in either form
+pub fn read_body(text: Option<String>, file: Option<PathBuf>) -> Result<Option<String>> {
+    let source = match (text, file) {
+        (Some(text), _) if text != "-" => return Ok(Some(text)),
+        (Some(_), _) => None,
+        (None, Some(path)) if path.as_os_str() != "-" => Some(path),
+        (None, Some(_)) => None,
+        (None, None) => return Ok(None),
+    };
+    let body = match source {
+        Some(path) => std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?,
+        None => {
+            let mut body = String::new();
+            std::io::stdin()
+                .read_to_string(&mut body)
+                .context("Failed to read stdin")?;
+            body
+        }
+    };
+    Ok(Some(body))
+}
+
 pub fn handle_version() {
     let attrs = phase_attrs("render_output");
     telemetry_span::with_span("render_output", &attrs, || {
@@ -34,3 +59,26 @@ pub async fn handle_ping(client: &RepsonaClient) -> Result<()> {
     });
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_body_from_text_or_file() {
+        assert_eq!(read_body(None, None).unwrap(), None);
+        assert_eq!(
+            read_body(Some("inline".to_string()), None).unwrap(),
+            Some("inline".to_string())
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.md");
+        std::fs::write(&path, "line 1\n\"quoted\" $HOME\n").unwrap();
+        assert_eq!(
+            read_body(None, Some(path)).unwrap(),
+            Some("line 1\n\"quoted\" $HOME\n".to_string())
+        );
+        assert!(read_body(None, Some(dir.path().join("missing.md"))).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Completion engine shared by `rpsn complete-words` (shell scripts) and `rpsn shell`.
+//!
+//! [`analyze`] walks the clap command tree over the words typed so far and decides
+//! what the current word can be; callers supply the dynamic values (IDs and names).
+
+use serde::{Deserialize, Serialize};
+
+/// An ID with a human-readable label, offered as a completion candidate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub id: u64,
+    pub label: String,
+}
+
+impl Entry {
+    pub fn new(id: u64, label: impl Into<String>) -> Self {
+        Self {
+            id,
+            label: label.into(),
+        }
+    }
+}
+
+/// Where dynamic values for the current word come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Projects,
+    Tasks(u64),
+    Notes(u64),
+    Users,
+}
+
+/// What the current word can be completed with
+#[derive(Debug, PartialEq)]
+pub enum Completion {
+    /// Fixed words (subcommands or flags), not yet filtered by the partial word
+    Words(Vec<String>),
+    /// IDs from a dynamic source
+    Dynamic(Source),
+    Nothing,
+}
+
+/// A completion value with an optional description (e.g. a task title)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub value: String,
+    pub description: Option<String>,
+}
+
+fn source_for(arg_id: &str, project_id: Option<u64>) -> Option<Source> {
+    match arg_id {
+        "project_id" => Some(Source::Projects),
+        "task_id" => project_id.map(Source::Tasks),
+        "note_id" => project_id.map(Source::Notes),
+        "assignee" | "user_id" => Some(Source::Users),
+        _ => None,
+    }
+}
+
+/// Decide what the word after `prior` can be.
+///
+/// `context_project` fills a leading `project_id` positional, as `rpsn shell` does.
+pub fn analyze(
+    command: &clap::Command,
+    prior: &[&str],
+    partial: &str,
+    context_project: Option<u64>,
+) -> Completion {
+    let mut cmd = command;
+    let mut positionals: Vec<&str> = Vec::new();
+    let mut pending_value: Option<String> = None;
+    for word in prior {
+        if pending_value.take().is_some() {
+            continue;
+        }
+        if let Some(flag) = word.strip_prefix("--") {
+            if !flag.contains('=') {
+                pending_value = cmd
+                    .get_arguments()
+                    .find(|a| a.get_long() == Some(flag))
+                    .filter(|a| a.get_action().takes_values())
+                    .map(|a| a.get_id().to_string());
+            }
+            continue;
+        }
+        if word.starts_with('-') {
+            continue;
+        }
+        if positionals.is_empty() {
+            if let Some(sub) = cmd.find_subcommand(word) {
+                cmd = sub;
+                continue;
+            }
+        }
+        positionals.push(word);
+    }
+
+    let args: Vec<&clap::Arg> = cmd.get_positionals().collect();
+    let context =
+        context_project.filter(|_| args.first().is_some_and(|a| a.get_id() == "project_id"));
+    let offset = usize::from(context.is_some());
+    let project_id = context.or_else(|| {
+        args.iter()
+            .skip(offset)
+            .zip(&positionals)
+            .find(|(arg, _)| arg.get_id() == "project_id")
+            .and_then(|(_, value)| value.parse().ok())
+    });
+
+    if let Some(arg_id) = pending_value {
+        return source_for(&arg_id, project_id)
+            .map(Completion::Dynamic)
+            .unwrap_or(Completion::Nothing);
+    }
+    if partial.starts_with('-') {
+        return Completion::Words(
+            cmd.get_arguments()
+                .filter(|a| !a.is_hide_set())
+                .filter_map(|a| a.get_long().map(|l| format!("--{}", l)))
+                .collect(),
+        );
+    }
+    if positionals.is_empty() && cmd.get_subcommands().next().is_some() {
+        return Completion::Words(
+            cmd.get_subcommands()
+                .filter(|s| !s.is_hide_set())
+                .map(|s| s.get_name().to_string())
+                .collect(),
+        );
+    }
+
+    match args.get(offset + positionals.len()) {
+        Some(arg) => source_for(arg.get_id().as_str(), project_id)
+            .map(Completion::Dynamic)
+            .unwrap_or(Completion::Nothing),
+        None => Completion::Nothing,
+    }
+}
+
+/// Words starting with `partial`
+pub fn matching_words<S: AsRef<str>>(words: &[S], partial: &str) -> Vec<Candidate> {
+    words
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|w| w.starts_with(partial))
+        .map(|w| Candidate {
+            value: w.to_string(),
+            description: None,
+        })
+        .collect()
+}
+
+/// Entries whose ID starts with `partial` or whose label contains it (case-insensitive)
+pub fn matching_entries(entries: &[Entry], partial: &str) -> Vec<Candidate> {
+    let needle = partial.to_lowercase();
+    entries
+        .iter()
+        .filter(|e| {
+            e.id.to_string().starts_with(partial) || e.label.to_lowercase().contains(&needle)
+        })
+        .map(|e| Candidate {
+            value: e.id.to_string(),
+            description: Some(e.label.replace(['\t', '\n', '\r'], " ")),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::CommandFactory;
+
+    fn command() -> clap::Command {
+        let mut command = Cli::command();
+        command.build();
+        command
+    }
+
+    fn words(completion: Completion) -> Vec<String> {
+        match completion {
+            Completion::Words(words) => words,
+            other => panic!("expected words, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_analyze_subcommands() {
+        let command = command();
+        let top = words(analyze(&command, &[], "", None));
+        assert!(top.contains(&"task".to_string()));
+        assert!(!top.contains(&"complete-words".to_string()));
+
+        let task = words(analyze(&command, &["task"], "", None));
+        assert!(task.contains(&"done".to_string()));
+    }
+
+    #[test]
+    fn test_analyze_skips_global_flags() {
+        let command = command();
+        let task = words(analyze(&command, &["--json", "task"], "", None));
+        assert!(task.contains(&"get".to_string()));
+    }
+
+    #[test]
+    fn test_analyze_positional_sources() {
+        let command = command();
+        assert_eq!(
+            analyze(&command, &["task", "get"], "", None),
+            Completion::Dynamic(Source::Projects)
+        );
+        assert_eq!(
+            analyze(&command, &["task", "get", "12"], "", None),
+            Completion::Dynamic(Source::Tasks(12))
+        );
+        assert_eq!(
+            analyze(&command, &["note", "get", "12"], "", None),
+            Completion::Dynamic(Source::Notes(12))
+        );
+        assert_eq!(
+            analyze(&command, &["task", "get", "12", "345"], "", None),
+            Completion::Nothing
+        );
+    }
+
+    #[test]
+    fn test_analyze_with_context_project() {
+        let command = command();
+        assert_eq!(
+            analyze(&command, &["task", "done"], "", Some(12)),
+            Completion::Dynamic(Source::Tasks(12))
+        );
+        // Commands without a project positional are unaffected
+        assert_eq!(
+            analyze(&command, &["me"], "", Some(12)),
+            Completion::Words(words(analyze(&command, &["me"], "", None)))
+        );
+    }
+
+    #[test]
+    fn test_analyze_option_values() {
+        let command = command();
+        assert_eq!(
+            analyze(
+                &command,
+                &["task", "update", "12", "345", "--assignee"],
+                "",
+                None
+            ),
+            Completion::Dynamic(Source::Users)
+        );
+        assert_eq!(
+            analyze(&command, &["board", "12", "--assignee"], "", None),
+            Completion::Dynamic(Source::Users)
+        );
+        assert_eq!(
+            analyze(&command, &["task", "create", "12", "--title"], "", None),
+            Completion::Nothing
+        );
+    }
+
+    #[test]
+    fn test_analyze_flags() {
+        let command = command();
+        let flags = words(analyze(&command, &["task", "create", "12"], "--ti", None));
+        assert!(flags.contains(&"--title".to_string()));
+    }
+
+    #[test]
+    fn test_matching_entries_by_id_or_label() {
+        let entries = vec![Entry::new(345, "Fix login"), Entry::new(346, "Write\tdocs")];
+        let by_id = matching_entries(&entries, "34");
+        assert_eq!(by_id.len(), 2);
+        assert_eq!(by_id[1].description.as_deref(), Some("Write docs"));
+
+        let by_label = matching_entries(&entries, "LOG");
+        assert_eq!(by_label.len(), 1);
+        assert_eq!(by_label[0].value, "345");
+    }
+}
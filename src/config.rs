@@ -1,11 +1,13 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(unix)]
-use std::os::unix::fs::PermissionsExt;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+
+use crate::query::TaskQuery;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
@@ -31,19 +33,38 @@ impl Default for AiConfig {
     }
 }
 
+/// `[cache]` section: per-resource TTL overrides in seconds, e.g. `ttl = { projects = 60 }`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub ttl: HashMap<String, u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub profiles: HashMap<String, Profile>,
     pub current_profile: String,
     #[serde(default)]
     pub ai: AiConfig,
+    #[serde(default, skip_serializing_if = "CacheConfig::is_empty")]
+    pub cache: CacheConfig,
+    /// Saved task queries, used as `rpsn task query @name`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub queries: BTreeMap<String, TaskQuery>,
+    /// Command aliases: `name = "task query overdue --assignee $1"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub alias: BTreeMap<String, String>,
+}
+
+impl CacheConfig {
+    fn is_empty(&self) -> bool {
+        self.ttl.is_empty()
+    }
 }
 
 impl Config {
     fn config_path() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
-        Ok(config_dir.join("rpsn").join("config.toml"))
+        Ok(config_dir()?.join("config.toml"))
     }
 
     pub fn load() -> Result<Self> {
@@ -148,10 +169,61 @@ impl Default for Config {
             profiles,
             current_profile: "default".to_string(),
             ai: AiConfig::default(),
+            cache: CacheConfig::default(),
+            queries: BTreeMap::new(),
+            alias: BTreeMap::new(),
         }
     }
 }
 
+/// Directory holding rpsn's config and local state (`~/.config/rpsn`)
+pub fn config_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+    Ok(config_dir.join("rpsn"))
+}
+
+/// Create `dir` and any missing parents, accessible by the owner only (0700) on Unix
+pub fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    builder.mode(0o700);
+    builder
+        .create(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    // The mode above only applies to directories created just now
+    #[cfg(unix)]
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+        .with_context(|| format!("Failed to set directory permissions: {}", dir.display()))?;
+    Ok(())
+}
+
+/// Open `path` for writing, creating it readable and writable by the owner only (0600) on Unix
+pub fn open_private_file(path: &Path, truncate: bool) -> Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(truncate);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let file = options
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    // Tighten files left by older versions, which were created with the default mode
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("Failed to set file permissions: {}", path.display()))?;
+    Ok(file)
+}
+
+/// Name of the profile in use: `env` when both `REPSONA_SPACE` and `REPSONA_TOKEN` are set,
+/// otherwise the current profile of the config file
+pub fn current_profile_name() -> Result<String> {
+    if std::env::var("REPSONA_SPACE").is_ok() && std::env::var("REPSONA_TOKEN").is_ok() {
+        return Ok("env".to_string());
+    }
+    Ok(Config::load()?.current_profile)
+}
+
 pub fn load_credentials() -> Result<(String, String)> {
     let space_id = std::env::var("REPSONA_SPACE");
     let api_token = std::env::var("REPSONA_TOKEN");
@@ -181,6 +253,15 @@ pub fn load_credentials() -> Result<(String, String)> {
     Ok((space_id, api_token))
 }
 
+/// Space ID and API token of a named profile, ignoring `REPSONA_SPACE`/`REPSONA_TOKEN`
+pub fn load_profile_credentials(name: &str) -> Result<(String, String)> {
+    let config = Config::load()?;
+    let profile = config
+        .get_profile(name)
+        .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+    Ok((profile.space_id.clone(), profile.api_token.clone()))
+}
+
 /// Anthropic APIキーをロードする
 /// 環境変数 ANTHROPIC_API_KEY が優先、設定ファイルがフォールバック
 pub fn load_anthropic_api_key() -> Result<String> {
@@ -197,6 +278,32 @@ pub fn load_anthropic_api_key() -> Result<String> {
         .ok_or_else(|| anyhow::anyhow!("Anthropic API key not configured. Set ANTHROPIC_API_KEY environment variable or configure it in config.toml"))
 }
 
+/// Cache TTL overrides from the config file (empty when there is no config)
+pub fn load_cache_config() -> CacheConfig {
+    Config::load().map(|c| c.cache).unwrap_or_default()
+}
+
+/// Command aliases from the config file (empty when there is no config)
+pub fn load_aliases() -> BTreeMap<String, String> {
+    Config::load().map(|c| c.alias).unwrap_or_default()
+}
+
+/// Saved query `[queries.<name>]`
+pub fn load_saved_query(name: &str) -> Result<TaskQuery> {
+    let config = Config::load()?;
+    config.queries.get(name).cloned().with_context(|| {
+        let names: Vec<&str> = config.queries.keys().map(String::as_str).collect();
+        if names.is_empty() {
+            format!(
+                "No saved query '{}' (add one under [queries.{}] in config.toml)",
+                name, name
+            )
+        } else {
+            format!("No saved query '{}' (saved: {})", name, names.join(", "))
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -327,6 +434,64 @@ mod tests {
         assert_eq!(deserialized.profiles.len(), 2);
     }
 
+    #[test]
+    fn test_cache_config_parsing() {
+        let config: Config = toml::from_str(
+            r#"
+current_profile = "default"
+
+[profiles.default]
+space_id = "s"
+api_token = "t"
+
+[cache.ttl]
+projects = 60
+users = 0
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.cache.ttl.get("projects"), Some(&60));
+        assert_eq!(config.cache.ttl.get("users"), Some(&0));
+
+        // Empty cache, query, and alias sections are omitted when saving
+        let serialized = toml::to_string(&Config::default()).unwrap();
+        assert!(!serialized.contains("[cache"));
+        assert!(!serialized.contains("[queries"));
+        assert!(!serialized.contains("[alias"));
+    }
+
+    #[test]
+    fn test_queries_and_aliases_round_trip() {
+        let config: Config = toml::from_str(
+            r#"
+current_profile = "default"
+
+[profiles.default]
+space_id = "s"
+api_token = "t"
+
+[queries.my-bugs]
+assignee = "me"
+tag = "bug"
+projects = [12, 34]
+include-closed = true
+
+[alias]
+od = "task query overdue --assignee $1"
+"#,
+        )
+        .unwrap();
+        let query = &config.queries["my-bugs"];
+        assert_eq!(query.assignee.as_deref(), Some("me"));
+        assert_eq!(query.projects, vec![12, 34]);
+        assert!(query.include_closed);
+        assert_eq!(config.alias["od"], "task query overdue --assignee $1");
+
+        let reparsed: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(reparsed.queries, config.queries);
+        assert_eq!(reparsed.alias, config.alias);
+    }
+
     #[test]
     fn test_load_credentials_from_env() {
         let _guard = ENV_LOCK.lock().unwrap();
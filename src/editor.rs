@@ -0,0 +1,106 @@
+//! Editing text in the user's editor (`task edit`, `note edit`, `comment-update --edit`).
+//!
+//! Documents are Markdown with an optional YAML front matter block between `---` lines.
+
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// The editor command from `$VISUAL` or `$EDITOR`, falling back to `vi`
+fn editor_command() -> Result<Vec<String>> {
+    let value = std::env::var("VISUAL")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    match shlex::split(&value) {
+        Some(words) if !words.is_empty() => Ok(words),
+        _ => bail!("Cannot parse editor command: {}", value),
+    }
+}
+
+/// Open `initial` in the editor and return the saved text.
+///
+/// The temporary file is removed afterwards; use [`keep`] to save text that could not
+/// be sent.
+pub fn edit(initial: &str) -> Result<String> {
+    let mut file = tempfile::Builder::new()
+        .prefix("rpsn-edit-")
+        .suffix(".md")
+        .tempfile()
+        .context("Failed to create a temporary file")?;
+    file.write_all(initial.as_bytes())?;
+    file.flush()?;
+
+    let command = editor_command()?;
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .arg(file.path())
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'", command[0]))?;
+    if !status.success() {
+        bail!("Editor exited with {}; nothing was changed", status);
+    }
+    std::fs::read_to_string(file.path()).context("Failed to read the edited file")
+}
+
+/// Save edited text that could not be sent, so it is not lost
+pub fn keep(text: &str) -> Result<PathBuf> {
+    let (_, path) = tempfile::Builder::new()
+        .prefix("rpsn-edit-")
+        .suffix(".md")
+        .tempfile()?
+        .keep()
+        .context("Failed to save the edited text")?;
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Build a document from YAML front matter and a Markdown body
+pub fn join(front_matter: &str, body: &str) -> String {
+    format!("---\n{}---\n\n{}", front_matter, body)
+}
+
+/// Split a document into its front matter (without the `---` lines) and body.
+///
+/// A document that does not start with `---` has no front matter.
+pub fn split(text: &str) -> Result<(Option<&str>, &str)> {
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return Ok((None, text));
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let body = &rest[offset + line.len()..];
+            let body = body
+                .strip_prefix("\r\n")
+                .or_else(|| body.strip_prefix('\n'))
+                .unwrap_or(body);
+            return Ok((Some(&rest[..offset]), body));
+        }
+        offset += line.len();
+    }
+    bail!("Front matter is not closed with a '---' line")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_front_matter() {
+        let text = join("title: Fix\ntags: [bug]\n", "Body\n---\nmore\n");
+        let (front, body) = split(&text).unwrap();
+        assert_eq!(front, Some("title: Fix\ntags: [bug]\n"));
+        assert_eq!(body, "Body\n---\nmore\n");
+
+        assert_eq!(split("Just text").unwrap(), (None, "Just text"));
+        assert_eq!(split("---\n---\n").unwrap(), (Some(""), ""));
+        assert!(split("---\ntitle: x\n").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! iCalendar (RFC 5545) output for `rpsn calendar export` / `serve`.
+//!
+//! Entries are all-day: tasks and milestones carry dates, not times. UIDs are derived from
+//! Repsona IDs so that calendar apps update entries on re-import instead of duplicating them.
+
+use chrono::{Duration, NaiveDate};
+
+const PRODID: &str = "-//rpsn//Repsona calendar//EN";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Event,
+    Todo,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub uid: String,
+    pub kind: Kind,
+    pub summary: String,
+    pub description: Option<String>,
+    pub categories: Vec<String>,
+    pub start: Option<NaiveDate>,
+    pub due: NaiveDate,
+    pub completed: bool,
+    /// Last change, as a Unix timestamp
+    pub modified: u64,
+}
+
+fn date(value: NaiveDate) -> String {
+    value.format("%Y%m%d").to_string()
+}
+
+fn timestamp(value: u64) -> String {
+    chrono::DateTime::from_timestamp(value as i64, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets without splitting a UTF-8 character
+fn fold(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+pub fn render(name: &str, entries: &[Entry]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+    for entry in entries {
+        let component = match entry.kind {
+            Kind::Event => "VEVENT",
+            Kind::Todo => "VTODO",
+        };
+        lines.push(format!("BEGIN:{}", component));
+        lines.push(format!("UID:{}", entry.uid));
+        lines.push(format!("DTSTAMP:{}", timestamp(entry.modified)));
+        lines.push(format!("LAST-MODIFIED:{}", timestamp(entry.modified)));
+        lines.push(format!("SUMMARY:{}", escape(&entry.summary)));
+        if let Some(description) = entry.description.as_deref().filter(|d| !d.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+        if !entry.categories.is_empty() {
+            let categories: Vec<String> = entry.categories.iter().map(|c| escape(c)).collect();
+            lines.push(format!("CATEGORIES:{}", categories.join(",")));
+        }
+        let start = entry.start.filter(|s| *s <= entry.due);
+        match entry.kind {
+            Kind::Event => {
+                let start = start.unwrap_or(entry.due);
+                lines.push(format!("DTSTART;VALUE=DATE:{}", date(start)));
+                lines.push(format!(
+                    "DTEND;VALUE=DATE:{}",
+                    date(entry.due + Duration::days(1))
+                ));
+                lines.push("TRANSP:TRANSPARENT".to_string());
+            }
+            Kind::Todo => {
+                if let Some(start) = start {
+                    lines.push(format!("DTSTART;VALUE=DATE:{}", date(start)));
+                }
+                lines.push(format!("DUE;VALUE=DATE:{}", date(entry.due)));
+                let status = if entry.completed {
+                    "COMPLETED"
+                } else {
+                    "NEEDS-ACTION"
+                };
+                lines.push(format!("STATUS:{}", status));
+            }
+        }
+        lines.push(format!("END:{}", component));
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in &lines {
+        fold(line, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_events_and_todos() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+        let entries = vec![
+            Entry {
+                uid: "task-7@acme.repsona".to_string(),
+                kind: Kind::Event,
+                summary: "Fix login; again, now".to_string(),
+                description: Some("line 1\nline 2".to_string()),
+                categories: vec!["web".to_string()],
+                start: Some(day(3)),
+                due: day(5),
+                completed: false,
+                modified: 1_717_243_200,
+            },
+            Entry {
+                uid: "task-8@acme.repsona".to_string(),
+                kind: Kind::Todo,
+                summary: "長いタイトル".repeat(10),
+                description: None,
+                categories: Vec::new(),
+                start: Some(day(9)),
+                due: day(5),
+                completed: true,
+                modified: 0,
+            },
+        ];
+        let ics = render("Tasks", &entries);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("SUMMARY:Fix login\\; again\\, now\r\n"));
+        assert!(ics.contains("DESCRIPTION:line 1\\nline 2\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20240603\r\nDTEND;VALUE=DATE:20240606\r\n"));
+        assert!(ics.contains("DTSTAMP:20240601T120000Z\r\n"));
+        // A start after the due date is dropped
+        assert!(ics.contains("DUE;VALUE=DATE:20240605\r\nSTATUS:COMPLETED\r\n"));
+        assert!(!ics.contains("20240609"));
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= 75, "{}", line);
+        }
+    }
+}
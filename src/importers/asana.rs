@@ -0,0 +1,178 @@
+//! Asana project JSON (Export/Print → JSON, or a `GET /projects/{id}/tasks` response).
+//!
+//! Sections become statuses and tags stay tags. Subtasks nested in the export become
+//! subtasks, and comment stories become comments.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::{parse_time, text, Export, ImportedAttachment, ImportedComment, ImportedTask};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum File {
+    Wrapped { data: Vec<Task> },
+    Bare(Vec<Task>),
+}
+
+#[derive(Deserialize)]
+struct Task {
+    name: String,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    completed: bool,
+    due_on: Option<String>,
+    due_at: Option<String>,
+    assignee: Option<Person>,
+    #[serde(default)]
+    memberships: Vec<Membership>,
+    #[serde(default)]
+    tags: Vec<Named>,
+    #[serde(default)]
+    subtasks: Vec<Task>,
+    #[serde(default)]
+    stories: Vec<Story>,
+    #[serde(default)]
+    attachments: Vec<Attachment>,
+}
+
+#[derive(Deserialize)]
+struct Person {
+    #[serde(default)]
+    name: String,
+    email: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Membership {
+    section: Option<Named>,
+    project: Option<Named>,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Story {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    resource_subtype: String,
+    text: Option<String>,
+    created_at: Option<String>,
+    created_by: Option<Person>,
+}
+
+#[derive(Deserialize)]
+struct Attachment {
+    name: String,
+    download_url: Option<String>,
+    permanent_url: Option<String>,
+}
+
+pub fn parse(input: &str) -> Result<Export> {
+    let file: File = serde_json::from_str(input).context("Not an Asana JSON export")?;
+    let tasks = match file {
+        File::Wrapped { data } => data,
+        File::Bare(tasks) => tasks,
+    };
+    let name = tasks
+        .iter()
+        .flat_map(|t| &t.memberships)
+        .find_map(|m| m.project.as_ref())
+        .map(|p| p.name.clone());
+    Ok(Export {
+        name,
+        tasks: tasks.into_iter().map(convert).collect(),
+    })
+}
+
+fn convert(task: Task) -> ImportedTask {
+    let status = task
+        .memberships
+        .iter()
+        .find_map(|m| m.section.as_ref())
+        .map(|s| s.name.clone());
+    let comments = task
+        .stories
+        .iter()
+        .filter(|s| s.kind == "comment" || s.resource_subtype == "comment_added")
+        .filter_map(|s| {
+            Some(ImportedComment {
+                author: s
+                    .created_by
+                    .as_ref()
+                    .map(|p| p.name.clone())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                created_at: s.created_at.as_deref().and_then(parse_time),
+                body: text(s.text.as_deref())?,
+            })
+        })
+        .collect();
+    ImportedTask {
+        title: task.name,
+        description: text(task.notes.as_deref()),
+        status,
+        closed: task.completed,
+        priority: None,
+        due: task
+            .due_at
+            .as_deref()
+            .or(task.due_on.as_deref())
+            .and_then(parse_time),
+        assignee: task
+            .assignee
+            .map(|p| p.email.filter(|e| !e.is_empty()).unwrap_or(p.name)),
+        labels: task.tags.into_iter().map(|t| t.name).collect(),
+        comments,
+        attachments: task
+            .attachments
+            .into_iter()
+            .map(|a| ImportedAttachment {
+                location: a
+                    .download_url
+                    .or(a.permanent_url)
+                    .unwrap_or_else(|| a.name.clone()),
+                name: a.name,
+            })
+            .collect(),
+        subtasks: task.subtasks.into_iter().map(convert).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_asana_export() {
+        let json = r#"{ "data": [
+            { "name": "Plan", "notes": "  ", "completed": false, "due_on": "2024-06-01",
+              "assignee": { "name": "Alice", "email": "alice@example.com" },
+              "memberships": [{ "project": { "name": "Launch" }, "section": { "name": "Next" } }],
+              "tags": [{ "name": "q3" }],
+              "stories": [
+                { "type": "system", "text": "changed the due date" },
+                { "type": "comment", "text": "ok", "created_by": { "name": "Bob" },
+                  "created_at": "2024-06-01T12:00:00.000Z" }
+              ],
+              "subtasks": [{ "name": "Draft", "completed": true }] }
+        ] }"#;
+        let export = parse(json).unwrap();
+        assert_eq!(export.name.as_deref(), Some("Launch"));
+        let task = &export.tasks[0];
+        assert_eq!(task.status.as_deref(), Some("Next"));
+        assert_eq!(task.description, None);
+        assert_eq!(task.assignee.as_deref(), Some("alice@example.com"));
+        assert_eq!(task.labels, vec!["q3"]);
+        assert_eq!(task.comments.len(), 1);
+        assert_eq!(task.comments[0].author, "Bob");
+        assert_eq!(task.subtasks[0].title, "Draft");
+        assert!(task.subtasks[0].closed);
+
+        assert_eq!(parse("[]").unwrap().tasks.len(), 0);
+    }
+}
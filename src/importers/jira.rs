@@ -0,0 +1,214 @@
+//! Jira issue CSV (Filters → Export → CSV (all fields)).
+//!
+//! Jira repeats a column once per value for labels, comments and attachments, so
+//! columns are read by header name with every value kept. Sub-tasks are nested under
+//! the issue their `Parent` or `Parent id` column names.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+use super::{parse_time, text, Export, ImportedAttachment, ImportedComment, ImportedTask};
+
+/// One CSV row: lower-cased header → non-empty values in column order
+type Record = HashMap<String, Vec<String>>;
+
+pub fn parse(input: &str) -> Result<Export> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(input.trim_start_matches('\u{feff}').as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .context("Not a Jira CSV export")?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    if !headers.iter().any(|h| h == "summary") {
+        anyhow::bail!("Not a Jira CSV export (no Summary column)");
+    }
+
+    let mut records: Vec<Record> = Vec::new();
+    for (i, row) in reader.records().enumerate() {
+        let row = row.with_context(|| format!("Invalid CSV in issue {}", i + 1))?;
+        let mut record = Record::new();
+        for (header, value) in headers.iter().zip(row.iter()) {
+            if !value.trim().is_empty() {
+                record
+                    .entry(header.clone())
+                    .or_default()
+                    .push(value.trim().to_string());
+            }
+        }
+        records.push(record);
+    }
+
+    // Parents are named by issue ID in current exports and by key in some older ones
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (i, record) in records.iter().enumerate() {
+        for column in ["issue id", "issue key"] {
+            if let Some(id) = first(record, column) {
+                index.insert(id.to_string(), i);
+            }
+        }
+    }
+    let parents: Vec<Option<usize>> = records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            first(record, "parent id")
+                .or_else(|| first(record, "parent"))
+                .and_then(|p| index.get(p).copied())
+                .filter(|&p| p != i)
+        })
+        .collect();
+
+    let mut tasks: Vec<Option<ImportedTask>> = records.iter().map(|r| Some(convert(r))).collect();
+    // Attach children from the bottom of the tree up, so each subtree is complete when moved
+    let depth = |mut i: usize| {
+        let mut depth = 0;
+        while let Some(p) = parents[i] {
+            depth += 1;
+            i = p;
+            if depth > records.len() {
+                break;
+            }
+        }
+        depth
+    };
+    let mut nested: Vec<usize> = (0..records.len())
+        .filter(|&i| parents[i].is_some())
+        .collect();
+    nested.sort_by_key(|&i| (std::cmp::Reverse(depth(i)), std::cmp::Reverse(i)));
+    for i in nested {
+        let (Some(p), Some(child)) = (parents[i], tasks[i].take()) else {
+            continue;
+        };
+        if let Some(parent) = tasks[p].as_mut() {
+            parent.subtasks.insert(0, child);
+        } else {
+            tasks[i] = Some(child);
+        }
+    }
+
+    Ok(Export {
+        name: first(
+            &records.first().cloned().unwrap_or_default(),
+            "project name",
+        )
+        .map(str::to_string),
+        tasks: tasks.into_iter().flatten().collect(),
+    })
+}
+
+fn first<'a>(record: &'a Record, column: &str) -> Option<&'a str> {
+    record
+        .get(column)
+        .and_then(|v| v.first())
+        .map(String::as_str)
+}
+
+fn all<'a>(record: &'a Record, column: &str) -> impl Iterator<Item = &'a String> {
+    record.get(column).into_iter().flatten()
+}
+
+fn convert(record: &Record) -> ImportedTask {
+    let resolution = first(record, "resolution");
+    let closed = resolution.is_some_and(|r| !r.eq_ignore_ascii_case("unresolved"))
+        || first(record, "status category").is_some_and(|c| c.eq_ignore_ascii_case("done"));
+    ImportedTask {
+        title: first(record, "summary")
+            .unwrap_or("(no summary)")
+            .to_string(),
+        description: text(first(record, "description")),
+        status: first(record, "status").map(str::to_string),
+        closed,
+        priority: first(record, "priority").and_then(priority),
+        due: first(record, "due date")
+            .or_else(|| first(record, "due"))
+            .and_then(parse_time),
+        assignee: first(record, "assignee").map(str::to_string),
+        labels: all(record, "labels").cloned().collect(),
+        comments: all(record, "comment").filter_map(|c| comment(c)).collect(),
+        attachments: all(record, "attachment")
+            .filter_map(|a| attachment(a))
+            .collect(),
+        subtasks: Vec::new(),
+    }
+}
+
+fn priority(name: &str) -> Option<u32> {
+    match name.to_lowercase().as_str() {
+        "highest" | "blocker" => Some(5),
+        "high" | "critical" => Some(4),
+        "medium" | "major" => Some(3),
+        "low" | "minor" => Some(2),
+        "lowest" | "trivial" => Some(1),
+        _ => None,
+    }
+}
+
+/// `date;author;body`, where the body may itself contain `;`
+fn comment(value: &str) -> Option<ImportedComment> {
+    let mut parts = value.splitn(3, ';');
+    let (date, author, body) = (parts.next()?, parts.next(), parts.next());
+    match (author, body) {
+        (Some(author), Some(body)) => Some(ImportedComment {
+            author: author.to_string(),
+            created_at: parse_time(date),
+            body: body.to_string(),
+        }),
+        _ => Some(ImportedComment {
+            author: "unknown".to_string(),
+            created_at: None,
+            body: value.to_string(),
+        }),
+    }
+}
+
+/// `date;author;file name;url`
+fn attachment(value: &str) -> Option<ImportedAttachment> {
+    let parts: Vec<&str> = value.splitn(4, ';').collect();
+    match parts.as_slice() {
+        [_, _, name, url] => Some(ImportedAttachment {
+            name: name.to_string(),
+            location: url.to_string(),
+        }),
+        _ => text(Some(value)).map(|v| ImportedAttachment {
+            name: v.rsplit('/').next().unwrap_or(&v).to_string(),
+            location: v,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jira_csv() {
+        let csv = "Summary,Issue key,Issue id,Parent id,Status,Resolution,Priority,Assignee,Labels,Labels,Comment,Comment,Attachment\n\
+            Epic,ABC-1,100,,In Progress,,High,alice,ui,bug,\"01/Jun/24 10:00 AM;alice;Looks; fine\",,\n\
+            Sub,ABC-2,101,100,Done,Done,Lowest,,,,,,01/Jun/24 10:00 AM;bob;log.txt;https://jira/att/1\n\
+            Subsub,ABC-3,102,101,To Do,Unresolved,,,,,,,\n\
+            Other,ABC-4,103,999,To Do,,,,,,,,\n";
+        let export = parse(csv).unwrap();
+        let titles: Vec<&str> = export.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Epic", "Other"]);
+
+        let epic = &export.tasks[0];
+        assert_eq!(epic.status.as_deref(), Some("In Progress"));
+        assert!(!epic.closed);
+        assert_eq!(epic.priority, Some(4));
+        assert_eq!(epic.labels, vec!["ui", "bug"]);
+        assert_eq!(epic.comments[0].author, "alice");
+        assert_eq!(epic.comments[0].body, "Looks; fine");
+
+        let sub = &epic.subtasks[0];
+        assert!(sub.closed);
+        assert_eq!(sub.priority, Some(1));
+        assert_eq!(sub.attachments[0].name, "log.txt");
+        assert_eq!(sub.subtasks[0].title, "Subsub");
+        assert!(!sub.subtasks[0].closed);
+
+        assert!(parse("Name\nx\n").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! Readers for other tools' export files (`rpsn import`).
+//!
+//! Each reader turns its format into the same [`Export`]: a tree of tasks whose
+//! status, labels and assignee are still names from the source tool. Matching them
+//! to Repsona statuses, tags and users happens in `commands::import`.
+
+mod asana;
+mod jira;
+mod trello;
+
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime, TimeZone};
+use std::path::Path;
+
+use crate::cli::ImportSource;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Export {
+    /// Board or project name, used when a new project is created
+    pub name: Option<String>,
+    pub tasks: Vec<ImportedTask>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedTask {
+    pub title: String,
+    pub description: Option<String>,
+    /// Trello list, Asana section or Jira status
+    pub status: Option<String>,
+    /// Done, completed or resolved in the source
+    pub closed: bool,
+    /// 1 (lowest) to 5 (highest)
+    pub priority: Option<u32>,
+    pub due: Option<u64>,
+    /// Name, username or email
+    pub assignee: Option<String>,
+    pub labels: Vec<String>,
+    pub comments: Vec<ImportedComment>,
+    pub attachments: Vec<ImportedAttachment>,
+    /// Subtasks and checklist items
+    pub subtasks: Vec<ImportedTask>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedComment {
+    pub author: String,
+    pub created_at: Option<u64>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedAttachment {
+    pub name: String,
+    /// URL or path as recorded in the export
+    pub location: String,
+}
+
+impl Export {
+    /// Every task and subtask, depth first
+    pub fn all_tasks(&self) -> Vec<&ImportedTask> {
+        fn walk<'a>(tasks: &'a [ImportedTask], out: &mut Vec<&'a ImportedTask>) {
+            for task in tasks {
+                out.push(task);
+                walk(&task.subtasks, out);
+            }
+        }
+        let mut out = Vec::new();
+        walk(&self.tasks, &mut out);
+        out
+    }
+}
+
+pub fn read(source: ImportSource, path: &Path) -> Result<Export> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let mut export = match source {
+        ImportSource::Trello => trello::parse(&text)?,
+        ImportSource::Asana => asana::parse(&text)?,
+        ImportSource::Jira => jira::parse(&text)?,
+    };
+    if export.name.is_none() {
+        export.name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+    }
+    Ok(export)
+}
+
+/// Parse the date and time formats the supported tools write, as a Unix timestamp.
+///
+/// Date-only values become local midnight.
+pub(crate) fn parse_time(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.timestamp().max(0) as u64);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(crate::query::start_of_day(date));
+    }
+    // Jira writes `01/Jun/24 10:00 AM` by default, and `2024-06-01 10:00` in ISO mode
+    for format in ["%d/%b/%y %I:%M %p", "%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return chrono::Local
+                .from_local_datetime(&dt)
+                .earliest()
+                .map(|dt| dt.timestamp().max(0) as u64);
+        }
+    }
+    for format in ["%d/%b/%y", "%Y/%m/%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return Some(crate::query::start_of_day(date));
+        }
+    }
+    None
+}
+
+/// Non-empty trimmed text
+fn text(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time_formats() {
+        assert_eq!(parse_time("2024-06-01T12:00:00.000Z"), Some(1_717_243_200));
+        let day = crate::query::start_of_day(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+        assert_eq!(parse_time("2024-06-01"), Some(day));
+        assert_eq!(parse_time("01/Jun/24"), Some(day));
+        assert_eq!(parse_time("01/Jun/24 10:00 AM"), Some(day + 10 * 3600));
+        assert_eq!(parse_time(""), None);
+        assert_eq!(parse_time("soon"), None);
+    }
+}
@@ -0,0 +1,286 @@
+//! Trello board JSON (Menu → Print, export and share → Export as JSON).
+//!
+//! Lists become statuses, labels are matched to existing tags by name, and checklist
+//! items become subtasks.
+//! Archived cards and cards on archived lists are skipped.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::{parse_time, text, Export, ImportedAttachment, ImportedComment, ImportedTask};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Board {
+    name: Option<String>,
+    #[serde(default)]
+    lists: Vec<List>,
+    #[serde(default)]
+    cards: Vec<Card>,
+    #[serde(default)]
+    checklists: Vec<Checklist>,
+    #[serde(default)]
+    actions: Vec<Action>,
+    #[serde(default)]
+    members: Vec<Member>,
+}
+
+#[derive(Deserialize)]
+struct List {
+    id: String,
+    name: String,
+    #[serde(default)]
+    closed: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Card {
+    id: String,
+    name: String,
+    #[serde(default)]
+    desc: String,
+    id_list: String,
+    #[serde(default)]
+    closed: bool,
+    due: Option<String>,
+    #[serde(default)]
+    due_complete: bool,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(default)]
+    id_members: Vec<String>,
+    #[serde(default)]
+    attachments: Vec<Attachment>,
+    #[serde(default)]
+    pos: f64,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Attachment {
+    name: String,
+    #[serde(default)]
+    url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Checklist {
+    id_card: String,
+    #[serde(default)]
+    check_items: Vec<CheckItem>,
+}
+
+#[derive(Deserialize)]
+struct CheckItem {
+    name: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    pos: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Action {
+    #[serde(rename = "type")]
+    kind: String,
+    date: Option<String>,
+    data: ActionData,
+    member_creator: Option<Member>,
+}
+
+#[derive(Deserialize)]
+struct ActionData {
+    text: Option<String>,
+    card: Option<CardRef>,
+}
+
+#[derive(Deserialize)]
+struct CardRef {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Member {
+    id: String,
+    #[serde(default)]
+    full_name: String,
+    #[serde(default)]
+    username: String,
+}
+
+pub fn parse(text_in: &str) -> Result<Export> {
+    let board: Board = serde_json::from_str(text_in).context("Not a Trello board export")?;
+    let lists: HashMap<&str, &List> = board.lists.iter().map(|l| (l.id.as_str(), l)).collect();
+    let members: HashMap<&str, &Member> =
+        board.members.iter().map(|m| (m.id.as_str(), m)).collect();
+
+    let mut comments: HashMap<&str, Vec<ImportedComment>> = HashMap::new();
+    // Trello lists actions newest first
+    for action in board.actions.iter().rev() {
+        if action.kind != "commentCard" {
+            continue;
+        }
+        let (Some(card), Some(body)) = (&action.data.card, &action.data.text) else {
+            continue;
+        };
+        comments
+            .entry(card.id.as_str())
+            .or_default()
+            .push(ImportedComment {
+                author: action
+                    .member_creator
+                    .as_ref()
+                    .map(|m| display_name(m).to_string())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                created_at: action.date.as_deref().and_then(parse_time),
+                body: body.clone(),
+            });
+    }
+
+    let mut cards: Vec<&Card> = board
+        .cards
+        .iter()
+        .filter(|c| !c.closed)
+        .filter(|c| lists.get(c.id_list.as_str()).is_some_and(|l| !l.closed))
+        .collect();
+    cards.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+    let mut tasks = Vec::new();
+    for card in cards {
+        let mut items: Vec<&CheckItem> = board
+            .checklists
+            .iter()
+            .filter(|c| c.id_card == card.id)
+            .flat_map(|c| &c.check_items)
+            .collect();
+        items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+        tasks.push(ImportedTask {
+            title: card.name.clone(),
+            description: text(Some(&card.desc)),
+            status: lists.get(card.id_list.as_str()).map(|l| l.name.clone()),
+            closed: card.due_complete,
+            due: card.due.as_deref().and_then(parse_time),
+            assignee: card
+                .id_members
+                .first()
+                .and_then(|id| members.get(id.as_str()))
+                .map(|m| display_name(m).to_string()),
+            labels: card
+                .labels
+                .iter()
+                .filter_map(|l| text(Some(&l.name)).or_else(|| l.color.clone()))
+                .collect(),
+            comments: comments.remove(card.id.as_str()).unwrap_or_default(),
+            attachments: card
+                .attachments
+                .iter()
+                .map(|a| ImportedAttachment {
+                    name: a.name.clone(),
+                    location: a.url.clone(),
+                })
+                .collect(),
+            subtasks: items
+                .into_iter()
+                .map(|item| ImportedTask {
+                    title: item.name.clone(),
+                    closed: item.state == "complete",
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        });
+    }
+
+    Ok(Export {
+        name: board.name,
+        tasks,
+    })
+}
+
+fn display_name(member: &Member) -> &str {
+    if member.full_name.is_empty() {
+        &member.username
+    } else {
+        &member.full_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trello_board() {
+        let json = r#"{
+            "name": "Launch",
+            "lists": [
+                { "id": "l1", "name": "Doing", "closed": false },
+                { "id": "l2", "name": "Old", "closed": true }
+            ],
+            "cards": [
+                { "id": "c2", "name": "Second", "desc": "", "idList": "l1", "pos": 2,
+                  "labels": [{ "name": "", "color": "red" }], "idMembers": ["m1"] },
+                { "id": "c1", "name": "First", "desc": "Body", "idList": "l1", "pos": 1,
+                  "due": "2024-06-01T12:00:00.000Z", "dueComplete": true,
+                  "labels": [{ "name": "bug", "color": "green" }],
+                  "attachments": [{ "name": "spec.pdf", "url": "https://trello.com/spec.pdf" }] },
+                { "id": "c3", "name": "Archived", "idList": "l1", "closed": true },
+                { "id": "c4", "name": "On old list", "idList": "l2" }
+            ],
+            "checklists": [
+                { "id": "k1", "idCard": "c1", "checkItems": [
+                    { "name": "b", "state": "incomplete", "pos": 2 },
+                    { "name": "a", "state": "complete", "pos": 1 }
+                ] }
+            ],
+            "actions": [
+                { "type": "commentCard", "date": "2024-06-02T00:00:00.000Z",
+                  "data": { "text": "newer", "card": { "id": "c1" } },
+                  "memberCreator": { "id": "m1", "fullName": "Alice", "username": "alice" } },
+                { "type": "commentCard", "date": "2024-06-01T00:00:00.000Z",
+                  "data": { "text": "older", "card": { "id": "c1" } },
+                  "memberCreator": { "id": "m1", "fullName": "", "username": "alice" } },
+                { "type": "updateCard", "data": {} }
+            ],
+            "members": [{ "id": "m1", "fullName": "Alice", "username": "alice" }]
+        }"#;
+        let export = parse(json).unwrap();
+        assert_eq!(export.name.as_deref(), Some("Launch"));
+        let titles: Vec<&str> = export.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["First", "Second"]);
+
+        let first = &export.tasks[0];
+        assert_eq!(first.status.as_deref(), Some("Doing"));
+        assert!(first.closed);
+        assert_eq!(first.due, Some(1_717_243_200));
+        assert_eq!(first.labels, vec!["bug"]);
+        assert_eq!(first.attachments[0].name, "spec.pdf");
+        let comments: Vec<(&str, &str)> = first
+            .comments
+            .iter()
+            .map(|c| (c.author.as_str(), c.body.as_str()))
+            .collect();
+        assert_eq!(comments, vec![("alice", "older"), ("Alice", "newer")]);
+        let items: Vec<(&str, bool)> = first
+            .subtasks
+            .iter()
+            .map(|s| (s.title.as_str(), s.closed))
+            .collect();
+        assert_eq!(items, vec![("a", true), ("b", false)]);
+
+        assert_eq!(export.tasks[1].labels, vec!["red"]);
+        assert_eq!(export.tasks[1].assignee.as_deref(), Some("Alice"));
+    }
+}
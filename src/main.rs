@@ -1,13 +1,25 @@
 mod ai;
+mod alias;
 mod api;
+mod archive;
+mod cache;
 mod cli;
 mod commands;
+mod completion;
 mod config;
+mod editor;
 mod error_report;
+mod ical;
+mod importers;
+mod markdown;
+mod mirror;
 mod output;
+mod plan;
+mod query;
 mod skills;
 mod telemetry;
 mod telemetry_span;
+mod tui;
 
 use anyhow::Result;
 use clap::{ArgMatches, CommandFactory, FromArgMatches};
@@ -16,11 +28,9 @@ use colored::Colorize;
 use std::ffi::OsString;
 
 use api::RepsonaClient;
-use cli::{Cli, Commands, Shell as ClapShell, UtilCommands};
-use commands::{
-    config as config_cmd, file, idlink, inbox, me, note, project, report, space, tag, task, user,
-    util, webhook,
-};
+use cache::{Cache, CacheMode};
+use cli::{Cli, Commands, ReportCommands, Shell as ClapShell, UtilCommands};
+use commands::{complete, report, shell, util};
 
 fn generate_shell_completion(shell: ClapShell) {
     let mut cmd = Cli::command();
@@ -116,8 +126,19 @@ async fn run_cli() -> Result<RunOutcome> {
         });
     }
 
+    let args = telemetry_span::with_span_result("expand_alias", &[], || {
+        let builtins = alias::builtin_commands(&cmd);
+        alias::expand(
+            std::env::args_os()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect(),
+            &config::load_aliases(),
+            &builtins,
+        )
+    })?;
+
     let (matches, cli) = telemetry_span::with_span_result("parse_args", &[], || {
-        let matches = cmd.get_matches();
+        let matches = cmd.get_matches_from(args);
         let cli = Cli::from_arg_matches(&matches)?;
         Ok::<(ArgMatches, Cli), clap::Error>((matches, cli))
     })?;
@@ -146,16 +167,25 @@ async fn run_cli() -> Result<RunOutcome> {
             });
             Ok(RunOutcome::Success)
         }
-        Commands::Completion { shell } => {
+        Commands::Completion { shell, static_only } => {
             let attrs = vec![
                 ("command.group", command_group.clone()),
                 ("op.phase", "execute_operation".to_string()),
             ];
-            telemetry_span::with_span("main_operation", &attrs, || {
-                generate_shell_completion(shell);
+            telemetry_span::with_span("main_operation", &attrs, || match complete::dynamic_script(
+                shell,
+            )
+            .filter(|_| !static_only)
+            {
+                Some(script) => print!("{}", script),
+                None => generate_shell_completion(shell),
             });
             Ok(RunOutcome::Success)
         }
+        Commands::Complete { words } => {
+            complete::handle_complete(words).await?;
+            Ok(RunOutcome::Success)
+        }
         Commands::Skills { output } => {
             let attrs = vec![
                 ("command.group", command_group.clone()),
@@ -164,7 +194,12 @@ async fn run_cli() -> Result<RunOutcome> {
             telemetry_span::with_span_result("main_operation", &attrs, || skills::emit(output))?;
             Ok(RunOutcome::Success)
         }
-        Commands::Report(cmd) => {
+        Commands::Report(cmd)
+            if !matches!(
+                cmd,
+                ReportCommands::Digest { .. } | ReportCommands::Flow { .. }
+            ) =>
+        {
             let attrs = vec![
                 ("command.group", command_group.clone()),
                 ("op.phase", "execute_operation".to_string()),
@@ -186,7 +221,17 @@ async fn run_cli() -> Result<RunOutcome> {
                 return Ok(RunOutcome::Exit(1));
             }
 
-            let client = RepsonaClient::new(space_id, api_token, cli.dry_run, cli.trace);
+            let cache_mode = if cli.no_cache {
+                CacheMode::Off
+            } else if cli.refresh_cache {
+                CacheMode::Refresh
+            } else {
+                CacheMode::Normal
+            };
+            let cache = Cache::for_space(&config::current_profile_name()?, &space_id)?
+                .with_ttls(config::load_cache_config().ttl);
+            let client = RepsonaClient::new(space_id, api_token, cli.dry_run, cli.trace)
+                .with_cache(cache, cache_mode);
 
             let attrs = vec![
                 ("command.group", command_group.clone()),
@@ -194,23 +239,8 @@ async fn run_cli() -> Result<RunOutcome> {
             ];
             telemetry_span::with_span_async_result("main_operation", &attrs, || async {
                 match command {
-                    Commands::Util(UtilCommands::Version) => unreachable!(),
-                    Commands::Util(UtilCommands::Ping) => util::handle_ping(&client).await?,
-                    Commands::Config(cmd) => config_cmd::handle(cmd).await?,
-                    Commands::Me(cmd) => me::handle(&client, cmd, cli.json).await?,
-                    Commands::Project(cmd) => project::handle(&client, cmd, cli.json).await?,
-                    Commands::Task(cmd) => task::handle(&client, cmd, cli.json).await?,
-                    Commands::Note(cmd) => note::handle(&client, cmd, cli.json).await?,
-                    Commands::File(cmd) => file::handle(&client, cmd, cli.json).await?,
-                    Commands::Tag(cmd) => tag::handle(&client, cmd, cli.json).await?,
-                    Commands::Inbox(cmd) => inbox::handle(&client, cmd, cli.json).await?,
-                    Commands::Space(cmd) => space::handle(&client, cmd, cli.json).await?,
-                    Commands::User(cmd) => user::handle(&client, cmd, cli.json).await?,
-                    Commands::Webhook(cmd) => webhook::handle(&client, cmd, cli.json).await?,
-                    Commands::Idlink(cmd) => idlink::handle(&client, cmd, cli.json).await?,
-                    Commands::Completion { .. } => unreachable!(),
-                    Commands::Skills { .. } => unreachable!(),
-                    Commands::Report(_) => unreachable!(),
+                    Commands::Shell => shell::run(&client, cli.json).await?,
+                    command => commands::dispatch(&client, command, cli.json, cli.yes).await?,
                 }
 
                 Ok::<(), anyhow::Error>(())
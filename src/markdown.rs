@@ -0,0 +1,872 @@
+//! Terminal rendering for markdown task/note descriptions and comments.
+
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, ContentArrangement, Table};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use std::io::IsTerminal;
+use unicode_width::UnicodeWidthStr;
+
+const DEFAULT_WIDTH: usize = 80;
+const MIN_WIDTH: usize = 20;
+
+/// Settings for a single markdown render
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    /// Maximum line width in terminal columns
+    pub width: usize,
+    /// Emit ANSI styles (bold, colors, underline)
+    pub color: bool,
+}
+
+impl RenderOptions {
+    /// Options matching the current stdout: terminal width and NO_COLOR handling
+    pub fn for_stdout() -> Self {
+        let width = if std::io::stdout().is_terminal() {
+            crossterm::terminal::size()
+                .map(|(cols, _)| cols as usize)
+                .unwrap_or(DEFAULT_WIDTH)
+        } else {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|c| c.parse().ok())
+                .unwrap_or(DEFAULT_WIDTH)
+        };
+
+        RenderOptions {
+            width: width.max(MIN_WIDTH),
+            color: colored::control::SHOULD_COLORIZE.should_colorize(),
+        }
+    }
+}
+
+/// Render markdown source into terminal-ready text
+pub fn render(source: &str, options: &RenderOptions) -> String {
+    let mut parser_options = Options::empty();
+    parser_options.insert(Options::ENABLE_TABLES);
+    parser_options.insert(Options::ENABLE_TASKLISTS);
+    parser_options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let mut renderer = Renderer::new(options);
+    for event in Parser::new_ext(source, parser_options) {
+        renderer.event(event);
+    }
+    renderer.finish()
+}
+
+/// Render markdown source as an HTML fragment for `project publish`.
+///
+/// Raw HTML in the source is shown as text, so published pages only contain markup
+/// produced from markdown. Link and image destinations other than http(s), mailto and
+/// relative URLs (`javascript:`, `data:`, ...) are replaced with `#`.
+pub fn to_html(source: &str) -> String {
+    let mut parser_options = Options::empty();
+    parser_options.insert(Options::ENABLE_TABLES);
+    parser_options.insert(Options::ENABLE_TASKLISTS);
+    parser_options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let events = Parser::new_ext(source, parser_options).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        event => event,
+    });
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events);
+    html
+}
+
+/// `url` if it is relative or uses http, https or mailto; `#` otherwise
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    // Browsers ignore whitespace and control characters inside a scheme ("java\tscript:")
+    let compact: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect();
+    let scheme = compact
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| !scheme.contains(['/', '?', '#']));
+    match scheme {
+        None => url,
+        Some(scheme)
+            if ["http", "https", "mailto"]
+                .iter()
+                .any(|s| scheme.eq_ignore_ascii_case(s)) =>
+        {
+            url
+        }
+        Some(_) => CowStr::Borrowed("#"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    code: bool,
+    link: bool,
+    dimmed: bool,
+    heading: Option<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct Span {
+    text: String,
+    style: Style,
+}
+
+/// A run of text that must not be split across lines (a "word")
+#[derive(Debug, Default)]
+struct Unit {
+    parts: Vec<Span>,
+    width: usize,
+}
+
+enum Token {
+    Unit(Unit),
+    Break,
+}
+
+struct TableState {
+    rows: Vec<Vec<String>>,
+    header: Option<Vec<String>>,
+    current_row: Vec<String>,
+    current_cell: String,
+}
+
+struct Renderer<'a> {
+    options: &'a RenderOptions,
+    lines: Vec<String>,
+    spans: Vec<Span>,
+    style: Style,
+    bold_depth: usize,
+    italic_depth: usize,
+    strike_depth: usize,
+    links: Vec<(String, usize)>,
+    lists: Vec<Option<u64>>,
+    pending_marker: Option<String>,
+    marker_indent: Vec<usize>,
+    quote_depth: usize,
+    code_block: Option<(Option<String>, String)>,
+    table: Option<TableState>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(options: &'a RenderOptions) -> Self {
+        Renderer {
+            options,
+            lines: Vec::new(),
+            spans: Vec::new(),
+            style: Style::default(),
+            bold_depth: 0,
+            italic_depth: 0,
+            strike_depth: 0,
+            links: Vec::new(),
+            lists: Vec::new(),
+            pending_marker: None,
+            marker_indent: Vec::new(),
+            quote_depth: 0,
+            code_block: None,
+            table: None,
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) => {
+                let style = Style {
+                    code: true,
+                    ..self.style
+                };
+                let text = if self.options.color {
+                    code.to_string()
+                } else {
+                    format!("`{}`", code)
+                };
+                self.push_text(&text, style);
+            }
+            Event::Html(html) | Event::InlineHtml(html) => self.text(&html),
+            Event::SoftBreak => self.push_text(" ", self.style),
+            Event::HardBreak => self.push_text("\n", self.style),
+            Event::Rule => {
+                self.flush();
+                let width = self.available_width();
+                self.push_line("─".repeat(width), "─".repeat(width), true);
+                self.blank();
+            }
+            Event::TaskListMarker(checked) => {
+                let mark = if checked { "☑ " } else { "☐ " };
+                self.pending_marker = Some(mark.to_string());
+            }
+            Event::FootnoteReference(name) => self.text(&format!("[^{}]", name)),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => {}
+            Tag::Heading { level, .. } => {
+                self.flush();
+                let level = heading_number(level);
+                self.style.heading = Some(level);
+                if !self.options.color {
+                    self.push_text(&format!("{} ", "#".repeat(level as usize)), self.style);
+                }
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.quote_depth += 1;
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) if !lang.is_empty() => Some(lang.to_string()),
+                    _ => None,
+                };
+                self.code_block = Some((lang, String::new()));
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        let marker = format!("{}. ", n);
+                        *n += 1;
+                        marker
+                    }
+                    _ => match depth % 3 {
+                        0 => "• ".to_string(),
+                        1 => "◦ ".to_string(),
+                        _ => "▪ ".to_string(),
+                    },
+                };
+                self.marker_indent.push(marker.width());
+                self.pending_marker = Some(marker);
+            }
+            Tag::Emphasis => {
+                self.italic_depth += 1;
+                self.style.italic = true;
+            }
+            Tag::Strong => {
+                self.bold_depth += 1;
+                self.style.bold = true;
+            }
+            Tag::Strikethrough => {
+                self.strike_depth += 1;
+                self.style.strike = true;
+            }
+            Tag::Link { dest_url, .. } => {
+                self.links.push((dest_url.to_string(), self.spans.len()));
+                self.style.link = true;
+            }
+            Tag::Image { dest_url, .. } => {
+                self.links.push((dest_url.to_string(), self.spans.len()));
+                self.push_text("[image: ", self.style);
+            }
+            Tag::Table(_) => {
+                self.flush();
+                self.table = Some(TableState {
+                    rows: Vec::new(),
+                    header: None,
+                    current_row: Vec::new(),
+                    current_cell: String::new(),
+                });
+            }
+            Tag::TableHead | Tag::TableRow => {
+                if let Some(table) = self.table.as_mut() {
+                    table.current_row.clear();
+                }
+            }
+            Tag::TableCell => {
+                if let Some(table) = self.table.as_mut() {
+                    table.current_cell.clear();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                self.flush();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Heading(_) => {
+                self.flush();
+                self.style.heading = None;
+                self.blank();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                self.blank();
+            }
+            TagEnd::CodeBlock => {
+                if let Some((lang, code)) = self.code_block.take() {
+                    self.render_code_block(lang, &code);
+                }
+                self.blank();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Item => {
+                self.flush();
+                self.marker_indent.pop();
+                self.pending_marker = None;
+            }
+            TagEnd::Emphasis => {
+                self.italic_depth = self.italic_depth.saturating_sub(1);
+                self.style.italic = self.italic_depth > 0;
+            }
+            TagEnd::Strong => {
+                self.bold_depth = self.bold_depth.saturating_sub(1);
+                self.style.bold = self.bold_depth > 0;
+            }
+            TagEnd::Strikethrough => {
+                self.strike_depth = self.strike_depth.saturating_sub(1);
+                self.style.strike = self.strike_depth > 0;
+            }
+            TagEnd::Link => {
+                self.style.link = false;
+                if let Some((url, start)) = self.links.pop() {
+                    let text: String = self
+                        .spans
+                        .iter()
+                        .skip(start)
+                        .map(|s| s.text.as_str())
+                        .collect();
+                    if !url.is_empty() && self.table.is_none() && text.trim() != url {
+                        let style = Style {
+                            dimmed: true,
+                            ..self.style
+                        };
+                        self.push_text(&format!(" ({})", url), style);
+                    }
+                }
+            }
+            TagEnd::Image => {
+                if let Some((url, _)) = self.links.pop() {
+                    self.push_text(&format!("]({})", url), self.style);
+                }
+            }
+            TagEnd::TableCell => {
+                if let Some(table) = self.table.as_mut() {
+                    let cell = std::mem::take(&mut table.current_cell);
+                    table.current_row.push(cell.trim().to_string());
+                }
+            }
+            TagEnd::TableHead => {
+                if let Some(table) = self.table.as_mut() {
+                    table.header = Some(std::mem::take(&mut table.current_row));
+                }
+            }
+            TagEnd::TableRow => {
+                if let Some(table) = self.table.as_mut() {
+                    let row = std::mem::take(&mut table.current_row);
+                    table.rows.push(row);
+                }
+            }
+            TagEnd::Table => {
+                if let Some(table) = self.table.take() {
+                    self.render_table(table);
+                }
+                self.blank();
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some((_, code)) = self.code_block.as_mut() {
+            code.push_str(text);
+            return;
+        }
+        self.push_text(text, self.style);
+    }
+
+    fn push_text(&mut self, text: &str, style: Style) {
+        if let Some(table) = self.table.as_mut() {
+            table.current_cell.push_str(text);
+            return;
+        }
+        self.spans.push(Span {
+            text: text.to_string(),
+            style,
+        });
+    }
+
+    /// Prefix for the first and continuation lines of the current block
+    fn prefixes(&mut self) -> (String, String) {
+        let mut base = "│ ".repeat(self.quote_depth);
+        let outer = self.marker_indent.len().saturating_sub(1);
+        for width in self.marker_indent.iter().take(outer) {
+            base.push_str(&" ".repeat(*width));
+        }
+
+        match self.marker_indent.last() {
+            Some(width) => {
+                let marker = self
+                    .pending_marker
+                    .take()
+                    .unwrap_or_else(|| " ".repeat(*width));
+                let first = format!("{}{:<w$}", base, marker, w = *width);
+                let rest = format!("{}{}", base, " ".repeat(*width));
+                (first, rest)
+            }
+            None => (base.clone(), base),
+        }
+    }
+
+    fn available_width(&self) -> usize {
+        let indent = self.quote_depth * 2 + self.marker_indent.iter().sum::<usize>();
+        self.options.width.saturating_sub(indent).max(MIN_WIDTH / 2)
+    }
+
+    fn push_line(&mut self, prefix: String, content: String, dim_prefix: bool) {
+        let prefix = if dim_prefix && self.options.color && self.quote_depth > 0 {
+            prefix.dimmed().to_string()
+        } else {
+            prefix
+        };
+        self.lines
+            .push(format!("{}{}", prefix, content).trim_end().to_string());
+    }
+
+    fn blank(&mut self) {
+        if self.lines.last().is_some_and(|l| !l.is_empty()) {
+            self.lines.push(String::new());
+        }
+    }
+
+    /// Lay out the buffered inline spans as wrapped lines
+    fn flush(&mut self) {
+        if self.spans.iter().all(|s| s.text.trim().is_empty()) {
+            self.spans.clear();
+            return;
+        }
+
+        let spans = std::mem::take(&mut self.spans);
+        let width = self.available_width();
+        let wrapped = wrap(&tokenize(&spans), width);
+        let (first, rest) = self.prefixes();
+
+        for (i, line) in wrapped.into_iter().enumerate() {
+            let content: String = line
+                .iter()
+                .map(|span| paint(&span.text, span.style, self.options.color))
+                .collect();
+            let prefix = if i == 0 { first.clone() } else { rest.clone() };
+            self.push_line(prefix, content, true);
+        }
+    }
+
+    fn render_code_block(&mut self, lang: Option<String>, code: &str) {
+        let (_, rest) = self.prefixes();
+        let gutter = if self.options.color {
+            "│ ".dimmed().to_string()
+        } else {
+            "    ".to_string()
+        };
+
+        if let Some(lang) = lang {
+            let label = if self.options.color {
+                lang.dimmed().italic().to_string()
+            } else {
+                format!("    [{}]", lang)
+            };
+            self.push_line(rest.clone(), label, true);
+        }
+
+        for line in code.trim_end_matches('\n').lines() {
+            let line = if self.options.color {
+                line.yellow().to_string()
+            } else {
+                line.to_string()
+            };
+            self.push_line(rest.clone(), format!("{}{}", gutter, line), true);
+        }
+    }
+
+    fn render_table(&mut self, state: TableState) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(self.available_width().min(u16::MAX as usize) as u16);
+        if let Some(header) = state.header {
+            table.set_header(header);
+        }
+        for row in state.rows {
+            table.add_row(row);
+        }
+
+        let (_, rest) = self.prefixes();
+        for line in table.to_string().lines() {
+            self.push_line(rest.clone(), line.to_string(), true);
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.flush();
+        while self.lines.last().is_some_and(|l| l.is_empty()) {
+            self.lines.pop();
+        }
+        self.lines.join("\n")
+    }
+}
+
+fn heading_number(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+fn paint(text: &str, style: Style, color: bool) -> String {
+    if !color {
+        return text.to_string();
+    }
+
+    let mut out = text.normal();
+    if let Some(level) = style.heading {
+        out = match level {
+            1 => out.bold().underline().cyan(),
+            2 => out.bold().cyan(),
+            _ => out.bold(),
+        };
+    }
+    if style.code {
+        out = out.yellow();
+    }
+    if style.link {
+        out = out.blue().underline();
+    }
+    if style.bold {
+        out = out.bold();
+    }
+    if style.italic {
+        out = out.italic();
+    }
+    if style.strike {
+        out = out.strikethrough();
+    }
+    if style.dimmed {
+        out = out.dimmed();
+    }
+    out.to_string()
+}
+
+/// Split spans into unbreakable units separated by whitespace
+fn tokenize(spans: &[Span]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut unit = Unit::default();
+
+    let finish = |unit: &mut Unit, tokens: &mut Vec<Token>| {
+        if !unit.parts.is_empty() {
+            tokens.push(Token::Unit(std::mem::take(unit)));
+        }
+    };
+
+    for span in spans {
+        let mut word = String::new();
+        for ch in span.text.chars() {
+            if ch == '\n' || ch.is_whitespace() {
+                if !word.is_empty() {
+                    unit.width += word.width();
+                    unit.parts.push(Span {
+                        text: std::mem::take(&mut word),
+                        style: span.style,
+                    });
+                }
+                finish(&mut unit, &mut tokens);
+                if ch == '\n' {
+                    tokens.push(Token::Break);
+                }
+            } else {
+                word.push(ch);
+            }
+        }
+        if !word.is_empty() {
+            unit.width += word.width();
+            unit.parts.push(Span {
+                text: word,
+                style: span.style,
+            });
+        }
+    }
+    finish(&mut unit, &mut tokens);
+
+    tokens
+}
+
+/// Greedy word wrap; units wider than the line are split by character
+fn wrap(tokens: &[Token], width: usize) -> Vec<Vec<Span>> {
+    let width = width.max(1);
+    let mut lines: Vec<Vec<Span>> = Vec::new();
+    let mut line: Vec<Span> = Vec::new();
+    let mut line_width = 0;
+
+    for token in tokens {
+        let unit = match token {
+            Token::Break => {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+                continue;
+            }
+            Token::Unit(unit) => unit,
+        };
+
+        let gap = usize::from(line_width > 0);
+        if line_width + gap + unit.width <= width {
+            if gap == 1 {
+                line.push(Span {
+                    text: " ".to_string(),
+                    style: Style::default(),
+                });
+            }
+            line.extend(unit.parts.iter().cloned());
+            line_width += gap + unit.width;
+            continue;
+        }
+
+        if line_width > 0 {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0;
+        }
+
+        if unit.width <= width {
+            line.extend(unit.parts.iter().cloned());
+            line_width = unit.width;
+            continue;
+        }
+
+        for part in &unit.parts {
+            let mut chunk = String::new();
+            for ch in part.text.chars() {
+                let ch_width = ch.to_string().width();
+                if line_width + ch_width > width && line_width > 0 {
+                    if !chunk.is_empty() {
+                        line.push(Span {
+                            text: std::mem::take(&mut chunk),
+                            style: part.style,
+                        });
+                    }
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                chunk.push(ch);
+                line_width += ch_width;
+            }
+            if !chunk.is_empty() {
+                line.push(Span {
+                    text: chunk,
+                    style: part.style,
+                });
+            }
+        }
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::test_support::color_override;
+    use proptest::prelude::*;
+
+    fn plain(width: usize) -> RenderOptions {
+        RenderOptions {
+            width,
+            color: false,
+        }
+    }
+
+    #[test]
+    fn test_render_heading_without_color_keeps_marker() {
+        let out = render("# Title\n\nBody text", &plain(80));
+        assert_eq!(out, "# Title\n\nBody text");
+    }
+
+    #[test]
+    fn test_render_unordered_and_nested_lists() {
+        let out = render("- one\n- two\n  - nested", &plain(80));
+        assert_eq!(out, "• one\n• two\n  ◦ nested");
+    }
+
+    #[test]
+    fn test_render_ordered_list_numbers() {
+        let out = render("3. three\n4. four", &plain(80));
+        assert_eq!(out, "3. three\n4. four");
+    }
+
+    #[test]
+    fn test_render_task_list_checkboxes() {
+        let out = render("- [ ] todo\n- [x] done", &plain(80));
+        assert_eq!(out, "☐ todo\n☑ done");
+    }
+
+    #[test]
+    fn test_render_code_block_is_indented_and_unwrapped() {
+        let out = render("```rust\nfn main() {}\n```", &plain(20));
+        assert_eq!(out, "    [rust]\n    fn main() {}");
+    }
+
+    #[test]
+    fn test_render_inline_code_and_link() {
+        let out = render(
+            "Run `rpsn task list` or see [docs](https://example.com).",
+            &plain(80),
+        );
+        assert_eq!(
+            out,
+            "Run `rpsn task list` or see docs (https://example.com)."
+        );
+    }
+
+    #[test]
+    fn test_render_autolink_not_duplicated() {
+        let out = render("<https://example.com>", &plain(80));
+        assert_eq!(out, "https://example.com");
+    }
+
+    #[test]
+    fn test_render_blockquote_prefix() {
+        let out = render("> quoted text", &plain(80));
+        assert_eq!(out, "│ quoted text");
+    }
+
+    #[test]
+    fn test_render_table() {
+        let out = render("| A | B |\n|---|---|\n| 1 | 2 |", &plain(40));
+        assert!(out.contains('A'));
+        assert!(out.contains('2'));
+        assert!(out.lines().count() >= 5);
+    }
+
+    #[test]
+    fn test_render_wraps_to_width() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta iota kappa";
+        let out = render(text, &plain(20));
+        assert!(out.lines().count() > 1);
+        assert!(out.lines().all(|l| l.width() <= 20));
+    }
+
+    #[test]
+    fn test_render_wraps_cjk_without_spaces() {
+        let text = "日本語のテキストは空白なしで続くので文字単位で折り返す必要がある";
+        let out = render(text, &plain(20));
+        assert!(out.lines().count() > 1);
+        assert!(out.lines().all(|l| l.width() <= 20));
+    }
+
+    #[test]
+    fn test_render_color_emits_ansi() {
+        let _color = color_override(true);
+        let out = render(
+            "**bold**",
+            &RenderOptions {
+                width: 80,
+                color: true,
+            },
+        );
+        assert!(out.contains("\u{1b}["));
+    }
+
+    #[test]
+    fn test_render_empty_input() {
+        assert_eq!(render("", &plain(80)), "");
+    }
+
+    // =========================================================================
+    // Property-Based Tests
+    // =========================================================================
+
+    proptest! {
+        /// Property: 任意の入力でパニックしない
+        #[test]
+        fn prop_render_never_panics(source in "\\PC{0,200}", width in 1usize..200) {
+            let _ = render(&source, &plain(width));
+        }
+
+        /// Property: 通常の段落は指定幅を超えない
+        #[test]
+        fn prop_paragraph_respects_width(
+            words in prop::collection::vec("[a-z]{1,12}", 1..40),
+            width in 20usize..120,
+        ) {
+            let out = render(&words.join(" "), &plain(width));
+            for line in out.lines() {
+                prop_assert!(line.width() <= width);
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_html_escapes_raw_html() {
+        let html = to_html("# Title\n\n- [x] done <script>alert(1)</script>\n");
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("checked"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_to_html_drops_unsafe_urls() {
+        let html = to_html(
+            "[a](javascript:alert(1)) [b](<JAVA\tSCRIPT:x>) ![c](data:image/png;base64,AA) \
+             [d](https://example.com/?q=a:b) [e](mailto:me@example.com) [f](docs/a:b.md)",
+        );
+        assert!(!html.to_lowercase().contains("script:"));
+        assert!(!html.contains("data:"));
+        assert_eq!(html.matches("href=\"#\"").count(), 2);
+        assert!(html.contains("src=\"#\""));
+        assert!(html.contains("href=\"https://example.com/?q=a:b\""));
+        assert!(html.contains("href=\"mailto:me@example.com\""));
+        assert!(html.contains("href=\"docs/a:b.md\""));
+    }
+}
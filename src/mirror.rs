@@ -0,0 +1,868 @@
+//! Local SQLite mirror of a space for offline, cross-project full-text search.
+//!
+//! `rpsn sync` fills it incrementally: tasks and notes are rewritten only when their
+//! `updatedAt` changed, and comments are refetched only for those changed items.
+//! Titles and bodies are also copied into `documents`, an FTS5 index using the
+//! trigram tokenizer so that Japanese text without word boundaries is searchable.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::api::types::{Note, NoteComment, Project, Task, TaskComment, User};
+use crate::cache::file_name;
+use crate::config;
+
+/// Marks the start and end of a matched term in [`SearchHit::snippet`]
+pub const MATCH_START: char = '\u{2}';
+pub const MATCH_END: char = '\u{3}';
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS projects (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    full_name TEXT NOT NULL,
+    is_closed INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    full_name TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS tasks (
+    project_id INTEGER NOT NULL,
+    id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    status TEXT NOT NULL,
+    is_closed INTEGER NOT NULL,
+    responsible_user_id INTEGER,
+    due_date INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (project_id, id)
+);
+CREATE TABLE IF NOT EXISTS notes (
+    project_id INTEGER NOT NULL,
+    id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (project_id, id)
+);
+CREATE TABLE IF NOT EXISTS comments (
+    kind TEXT NOT NULL,
+    id INTEGER NOT NULL,
+    project_id INTEGER NOT NULL,
+    parent_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    body TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (kind, id)
+);
+CREATE INDEX IF NOT EXISTS comments_parent ON comments (kind, project_id, parent_id);
+
+CREATE TABLE IF NOT EXISTS documents (
+    rowid INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    project_id INTEGER NOT NULL,
+    item_id INTEGER NOT NULL,
+    parent_id INTEGER,
+    label TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (kind, project_id, item_id)
+);
+CREATE VIRTUAL TABLE IF NOT EXISTS documents_fts USING fts5(
+    title, body, content='documents', content_rowid='rowid', tokenize='trigram'
+);
+CREATE TRIGGER IF NOT EXISTS documents_ai AFTER INSERT ON documents BEGIN
+    INSERT INTO documents_fts(rowid, title, body) VALUES (new.rowid, new.title, new.body);
+END;
+CREATE TRIGGER IF NOT EXISTS documents_ad AFTER DELETE ON documents BEGIN
+    INSERT INTO documents_fts(documents_fts, rowid, title, body)
+    VALUES ('delete', old.rowid, old.title, old.body);
+END;
+CREATE TRIGGER IF NOT EXISTS documents_au AFTER UPDATE ON documents BEGIN
+    INSERT INTO documents_fts(documents_fts, rowid, title, body)
+    VALUES ('delete', old.rowid, old.title, old.body);
+    INSERT INTO documents_fts(rowid, title, body) VALUES (new.rowid, new.title, new.body);
+END;
+"#;
+
+/// Kind of a searchable document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocKind {
+    Task,
+    Note,
+    TaskComment,
+    NoteComment,
+}
+
+impl DocKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DocKind::Task => "task",
+            DocKind::Note => "note",
+            DocKind::TaskComment => "task_comment",
+            DocKind::NoteComment => "note_comment",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [
+            DocKind::Task,
+            DocKind::Note,
+            DocKind::TaskComment,
+            DocKind::NoteComment,
+        ]
+        .into_iter()
+        .find(|k| k.as_str() == value)
+    }
+}
+
+/// Changes to one project's tasks, notes and comments, written in a single transaction
+#[derive(Default)]
+pub struct ProjectChanges {
+    pub tasks: Vec<Task>,
+    pub notes: Vec<Note>,
+    /// Full comment lists for changed tasks, replacing what was stored
+    pub task_comments: Vec<(u64, Vec<TaskComment>)>,
+    pub note_comments: Vec<(u64, Vec<NoteComment>)>,
+    pub deleted_tasks: Vec<u64>,
+    pub deleted_notes: Vec<u64>,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct MirrorCounts {
+    pub projects: u64,
+    pub users: u64,
+    pub tasks: u64,
+    pub notes: u64,
+    pub comments: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchQuery {
+    pub text: String,
+    pub project_id: Option<u64>,
+    pub kinds: Vec<DocKind>,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub kind: DocKind,
+    pub project_id: u64,
+    pub project_name: Option<String>,
+    /// Task or note ID; for comments, the comment ID
+    pub item_id: u64,
+    /// For comments, the task or note they belong to
+    pub parent_id: Option<u64>,
+    pub title: String,
+    pub snippet: String,
+    pub updated_at: u64,
+}
+
+pub struct Mirror {
+    conn: Connection,
+}
+
+impl Mirror {
+    /// Database path for a space: `~/.config/rpsn/mirror/<profile>/<space_id>.db`
+    ///
+    /// Like the metadata cache, profiles never share a mirror, since their tokens may see
+    /// different projects of the same space.
+    pub fn path_for_space(profile: &str, space_id: &str) -> Result<PathBuf> {
+        Ok(config::config_dir()?
+            .join("mirror")
+            .join(file_name(profile))
+            .join(format!("{}.db", file_name(space_id))))
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            config::create_private_dir(parent)?;
+        }
+        // Create the file first so that SQLite, which gives its journal files the database
+        // file's mode, keeps the mirror private to the owner
+        config::open_private_file(path, false)?;
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open mirror database {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create mirror schema (SQLite needs FTS5 with trigram)")?;
+        Ok(Self { conn })
+    }
+
+    /// Unix time (seconds) of the last completed sync
+    pub fn synced_at(&self) -> Result<Option<u64>> {
+        let value: Option<String> = self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = 'synced_at'", [], |r| {
+                r.get(0)
+            })
+            .optional()?;
+        Ok(value.and_then(|v| v.parse().ok()))
+    }
+
+    pub fn set_synced_at(&self, secs: u64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('synced_at', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [secs.to_string()],
+        )?;
+        Ok(())
+    }
+
+    pub fn upsert_projects(&mut self, projects: &[Project]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for project in projects {
+            tx.execute(
+                "INSERT INTO projects (id, name, full_name, is_closed, updated_at, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(id) DO UPDATE SET name = excluded.name,
+                     full_name = excluded.full_name, is_closed = excluded.is_closed,
+                     updated_at = excluded.updated_at, data = excluded.data",
+                params![
+                    project.id,
+                    project.name,
+                    project.full_name,
+                    project.is_closed,
+                    project.updated_at,
+                    serde_json::to_string(project)?
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Remove projects not in `keep`, with their tasks, notes and comments.
+    /// Returns the IDs of removed projects.
+    pub fn prune_projects(&mut self, keep: &[u64]) -> Result<Vec<u64>> {
+        let tx = self.conn.transaction()?;
+        let stored: Vec<u64> = {
+            let mut stmt = tx.prepare("SELECT id FROM projects")?;
+            let ids = stmt
+                .query_map([], |r| r.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            ids
+        };
+        let removed: Vec<u64> = stored.into_iter().filter(|id| !keep.contains(id)).collect();
+        for id in &removed {
+            for table in ["tasks", "notes", "comments", "documents"] {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE project_id = ?1", table),
+                    [id],
+                )?;
+            }
+            tx.execute("DELETE FROM projects WHERE id = ?1", [id])?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    pub fn replace_users(&mut self, users: &[User]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM users", [])?;
+        for user in users {
+            tx.execute(
+                "INSERT INTO users (id, name, full_name, updated_at, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    user.id,
+                    user.name,
+                    user.full_name,
+                    user.updated_at,
+                    serde_json::to_string(user)?
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stored `updatedAt` of each task in a project
+    pub fn task_versions(&self, project_id: u64) -> Result<HashMap<u64, u64>> {
+        self.versions("tasks", project_id)
+    }
+
+    /// Stored `updatedAt` of each note in a project
+    pub fn note_versions(&self, project_id: u64) -> Result<HashMap<u64, u64>> {
+        self.versions("notes", project_id)
+    }
+
+    fn versions(&self, table: &str, project_id: u64) -> Result<HashMap<u64, u64>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, updated_at FROM {} WHERE project_id = ?1",
+            table
+        ))?;
+        let versions = stmt
+            .query_map([project_id], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(versions)
+    }
+
+    pub fn apply(&mut self, project_id: u64, changes: &ProjectChanges) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for task in &changes.tasks {
+            upsert_task(&tx, project_id, task)?;
+        }
+        for note in &changes.notes {
+            upsert_note(&tx, project_id, note)?;
+        }
+        for (task_id, comments) in &changes.task_comments {
+            let comments: Vec<_> = comments
+                .iter()
+                .map(|c| (c.id, c.user.id, c.comment.as_str(), c.created_at))
+                .collect();
+            replace_comments(&tx, DocKind::TaskComment, project_id, *task_id, &comments)?;
+        }
+        for (note_id, comments) in &changes.note_comments {
+            let comments: Vec<_> = comments
+                .iter()
+                .map(|c| (c.id, c.user.id, c.comment.as_str(), c.created_at))
+                .collect();
+            replace_comments(&tx, DocKind::NoteComment, project_id, *note_id, &comments)?;
+        }
+        for task_id in &changes.deleted_tasks {
+            tx.execute(
+                "DELETE FROM tasks WHERE project_id = ?1 AND id = ?2",
+                params![project_id, task_id],
+            )?;
+            delete_item_documents(
+                &tx,
+                DocKind::Task,
+                DocKind::TaskComment,
+                project_id,
+                *task_id,
+            )?;
+        }
+        for note_id in &changes.deleted_notes {
+            tx.execute(
+                "DELETE FROM notes WHERE project_id = ?1 AND id = ?2",
+                params![project_id, note_id],
+            )?;
+            delete_item_documents(
+                &tx,
+                DocKind::Note,
+                DocKind::NoteComment,
+                project_id,
+                *note_id,
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn counts(&self) -> Result<MirrorCounts> {
+        let count = |table: &str| -> Result<u64> {
+            Ok(self
+                .conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0))?)
+        };
+        Ok(MirrorCounts {
+            projects: count("projects")?,
+            users: count("users")?,
+            tasks: count("tasks")?,
+            notes: count("notes")?,
+            comments: count("comments")?,
+        })
+    }
+
+    /// Ranked search over task and note titles, descriptions and comments.
+    ///
+    /// Terms are ANDed. Terms of three or more characters go through the FTS index
+    /// (ranked by bm25, titles weighted higher); shorter terms, which the trigram
+    /// tokenizer cannot index, fall back to a substring match.
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>> {
+        let (fts_terms, short_terms): (Vec<&str>, Vec<&str>) = query
+            .text
+            .split_whitespace()
+            .partition(|t| t.chars().count() >= 3);
+        if fts_terms.is_empty() && short_terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conditions = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        let use_fts = !fts_terms.is_empty();
+        if use_fts {
+            values.push(fts_expression(&fts_terms).into());
+            conditions.push(format!("documents_fts MATCH ?{}", values.len()));
+        }
+        for term in &short_terms {
+            values.push(format!("%{}%", escape_like(term)).into());
+            conditions.push(format!(
+                "(d.title LIKE ?{n} ESCAPE '\\' OR d.body LIKE ?{n} ESCAPE '\\')",
+                n = values.len()
+            ));
+        }
+        if let Some(project_id) = query.project_id {
+            values.push((project_id as i64).into());
+            conditions.push(format!("d.project_id = ?{}", values.len()));
+        }
+        if !query.kinds.is_empty() {
+            let placeholders: Vec<String> = query
+                .kinds
+                .iter()
+                .map(|kind| {
+                    values.push(kind.as_str().to_string().into());
+                    format!("?{}", values.len())
+                })
+                .collect();
+            conditions.push(format!("d.kind IN ({})", placeholders.join(", ")));
+        }
+        values.push((query.limit.max(1) as i64).into());
+        let limit = values.len();
+
+        let sql = if use_fts {
+            format!(
+                "SELECT d.kind, d.project_id, p.name, d.item_id, d.parent_id, d.label,
+                        snippet(documents_fts, 1, char(2), char(3), '…', 16), d.updated_at
+                 FROM documents_fts
+                 JOIN documents d ON d.rowid = documents_fts.rowid
+                 LEFT JOIN projects p ON p.id = d.project_id
+                 WHERE {}
+                 ORDER BY bm25(documents_fts, 10.0, 1.0), d.updated_at DESC
+                 LIMIT ?{}",
+                conditions.join(" AND "),
+                limit
+            )
+        } else {
+            format!(
+                "SELECT d.kind, d.project_id, p.name, d.item_id, d.parent_id, d.label,
+                        substr(d.body, 1, 120), d.updated_at
+                 FROM documents d
+                 LEFT JOIN projects p ON p.id = d.project_id
+                 WHERE {}
+                 ORDER BY d.updated_at DESC
+                 LIMIT ?{}",
+                conditions.join(" AND "),
+                limit
+            )
+        };
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let hits = stmt
+            .query_map(params_from_iter(values), |r| {
+                let kind: String = r.get(0)?;
+                Ok(SearchHit {
+                    kind: DocKind::parse(&kind).unwrap_or(DocKind::Task),
+                    project_id: r.get(1)?,
+                    project_name: r.get(2)?,
+                    item_id: r.get(3)?,
+                    parent_id: r.get(4)?,
+                    title: r.get(5)?,
+                    snippet: r.get::<_, String>(6)?.replace('\n', " "),
+                    updated_at: r.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(hits)
+    }
+}
+
+fn upsert_task(tx: &Transaction, project_id: u64, task: &Task) -> Result<()> {
+    tx.execute(
+        "INSERT INTO tasks (project_id, id, name, description, status, is_closed,
+             responsible_user_id, due_date, created_at, updated_at, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT(project_id, id) DO UPDATE SET name = excluded.name,
+             description = excluded.description, status = excluded.status,
+             is_closed = excluded.is_closed, responsible_user_id = excluded.responsible_user_id,
+             due_date = excluded.due_date, updated_at = excluded.updated_at, data = excluded.data",
+        params![
+            project_id,
+            task.id,
+            task.name,
+            task.description,
+            task.status.name,
+            task.status.is_closed,
+            task.responsible_user.as_ref().map(|u| u.id),
+            task.due_date,
+            task.created_at,
+            task.updated_at,
+            serde_json::to_string(task)?
+        ],
+    )?;
+    upsert_document(
+        tx,
+        &Document {
+            kind: DocKind::Task,
+            project_id,
+            item_id: task.id,
+            parent_id: None,
+            label: &task.name,
+            title: &task.name,
+            body: task.description.as_deref().unwrap_or(""),
+            updated_at: task.updated_at,
+        },
+    )
+}
+
+fn upsert_note(tx: &Transaction, project_id: u64, note: &Note) -> Result<()> {
+    tx.execute(
+        "INSERT INTO notes (project_id, id, name, description, created_at, updated_at, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(project_id, id) DO UPDATE SET name = excluded.name,
+             description = excluded.description, updated_at = excluded.updated_at,
+             data = excluded.data",
+        params![
+            project_id,
+            note.id,
+            note.name,
+            note.description,
+            note.created_at,
+            note.updated_at,
+            serde_json::to_string(note)?
+        ],
+    )?;
+    upsert_document(
+        tx,
+        &Document {
+            kind: DocKind::Note,
+            project_id,
+            item_id: note.id,
+            parent_id: None,
+            label: &note.name,
+            title: &note.name,
+            body: note.description.as_deref().unwrap_or(""),
+            updated_at: note.updated_at,
+        },
+    )
+}
+
+/// Replace the comments of one task or note: `(id, user_id, body, created_at)`
+fn replace_comments(
+    tx: &Transaction,
+    kind: DocKind,
+    project_id: u64,
+    parent_id: u64,
+    comments: &[(u64, u64, &str, u64)],
+) -> Result<()> {
+    tx.execute(
+        "DELETE FROM comments WHERE kind = ?1 AND project_id = ?2 AND parent_id = ?3",
+        params![kind.as_str(), project_id, parent_id],
+    )?;
+    tx.execute(
+        "DELETE FROM documents WHERE kind = ?1 AND project_id = ?2 AND parent_id = ?3",
+        params![kind.as_str(), project_id, parent_id],
+    )?;
+
+    let parent_table = if kind == DocKind::TaskComment {
+        "tasks"
+    } else {
+        "notes"
+    };
+    let title: String = tx
+        .query_row(
+            &format!(
+                "SELECT name FROM {} WHERE project_id = ?1 AND id = ?2",
+                parent_table
+            ),
+            params![project_id, parent_id],
+            |r| r.get(0),
+        )
+        .optional()?
+        .unwrap_or_default();
+
+    for (id, user_id, body, created_at) in comments {
+        tx.execute(
+            "INSERT OR REPLACE INTO comments
+                 (kind, id, project_id, parent_id, user_id, body, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                kind.as_str(),
+                id,
+                project_id,
+                parent_id,
+                user_id,
+                body,
+                created_at
+            ],
+        )?;
+        // The parent title is shown with the hit but not indexed, so that a match
+        // on a task title does not also return every comment on that task
+        upsert_document(
+            tx,
+            &Document {
+                kind,
+                project_id,
+                item_id: *id,
+                parent_id: Some(parent_id),
+                label: &title,
+                title: "",
+                body,
+                updated_at: *created_at,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// A row of the search index
+struct Document<'a> {
+    kind: DocKind,
+    project_id: u64,
+    item_id: u64,
+    parent_id: Option<u64>,
+    /// Title shown in results (the task or note title, also for comments)
+    label: &'a str,
+    /// Indexed title; empty for comments
+    title: &'a str,
+    body: &'a str,
+    updated_at: u64,
+}
+
+fn upsert_document(tx: &Transaction, doc: &Document) -> Result<()> {
+    tx.execute(
+        "INSERT INTO documents
+             (kind, project_id, item_id, parent_id, label, title, body, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(kind, project_id, item_id) DO UPDATE SET parent_id = excluded.parent_id,
+             label = excluded.label, title = excluded.title, body = excluded.body,
+             updated_at = excluded.updated_at",
+        params![
+            doc.kind.as_str(),
+            doc.project_id,
+            doc.item_id,
+            doc.parent_id,
+            doc.label,
+            doc.title,
+            doc.body,
+            doc.updated_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// Remove a task or note and its comments from `comments` and `documents`
+fn delete_item_documents(
+    tx: &Transaction,
+    kind: DocKind,
+    comment_kind: DocKind,
+    project_id: u64,
+    item_id: u64,
+) -> Result<()> {
+    tx.execute(
+        "DELETE FROM documents WHERE kind = ?1 AND project_id = ?2 AND item_id = ?3",
+        params![kind.as_str(), project_id, item_id],
+    )?;
+    tx.execute(
+        "DELETE FROM documents WHERE kind = ?1 AND project_id = ?2 AND parent_id = ?3",
+        params![comment_kind.as_str(), project_id, item_id],
+    )?;
+    tx.execute(
+        "DELETE FROM comments WHERE kind = ?1 AND project_id = ?2 AND parent_id = ?3",
+        params![comment_kind.as_str(), project_id, item_id],
+    )?;
+    Ok(())
+}
+
+/// FTS5 expression matching every term as a literal phrase
+fn fts_expression(terms: &[&str]) -> String {
+    terms
+        .iter()
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::test_support;
+
+    fn user(id: u64) -> User {
+        User {
+            id,
+            email: format!("u{}@example.com", id),
+            name: format!("u{}", id),
+            full_name: format!("User {}", id),
+            avatar_url: None,
+            role: "member".to_string(),
+            billing_status: "active".to_string(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn project(id: u64, name: &str) -> Project {
+        Project {
+            id,
+            name: name.to_string(),
+            full_name: name.to_string(),
+            purpose: None,
+            avatar_url: None,
+            is_closed: false,
+            is_public: false,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn task(project_id: u64, id: u64, name: &str, description: &str, updated_at: u64) -> Task {
+        Task {
+            description: Some(description.to_string()),
+            updated_at,
+            ..test_support::task(id)
+                .named(name)
+                .with_status(1, "Open", false)
+                .in_project(project_id, &format!("p{}", project_id))
+        }
+    }
+
+    fn comment(id: u64, body: &str) -> TaskComment {
+        TaskComment {
+            id,
+            comment: body.to_string(),
+            user: user(1),
+            created_at: 5,
+        }
+    }
+
+    fn query(text: &str) -> SearchQuery {
+        SearchQuery {
+            text: text.to_string(),
+            limit: 20,
+            ..Default::default()
+        }
+    }
+
+    fn seeded() -> Mirror {
+        let mut mirror = Mirror::open_in_memory().unwrap();
+        mirror
+            .upsert_projects(&[project(1, "Web"), project(2, "Mobile")])
+            .unwrap();
+        mirror
+            .apply(
+                1,
+                &ProjectChanges {
+                    tasks: vec![
+                        task(1, 10, "Fix login redirect", "Users loop on /login", 100),
+                        task(1, 11, "Write docs", "Explain the login flow", 100),
+                    ],
+                    task_comments: vec![(10, vec![comment(500, "Reproduced on Safari")])],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        mirror
+            .apply(
+                2,
+                &ProjectChanges {
+                    tasks: vec![task(2, 10, "ログイン画面の修正", "会議で決定", 100)],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        mirror
+    }
+
+    #[test]
+    fn test_search_ranks_title_matches_first() {
+        let mirror = seeded();
+        let hits = mirror.search(&query("login")).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].item_id, 10);
+        assert_eq!(hits[0].project_name.as_deref(), Some("Web"));
+        assert!(hits[0].snippet.contains(MATCH_START));
+    }
+
+    #[test]
+    fn test_search_comments_and_filters() {
+        let mirror = seeded();
+        let hits = mirror.search(&query("safari")).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].kind, DocKind::TaskComment);
+        assert_eq!(hits[0].parent_id, Some(10));
+        assert_eq!(hits[0].title, "Fix login redirect");
+
+        let mut only_notes = query("login");
+        only_notes.kinds = vec![DocKind::Note];
+        assert!(mirror.search(&only_notes).unwrap().is_empty());
+
+        let mut other_project = query("login");
+        other_project.project_id = Some(2);
+        assert!(mirror.search(&other_project).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_japanese_and_short_terms() {
+        let mirror = seeded();
+        let hits = mirror.search(&query("ログイン")).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].project_id, 2);
+
+        // Two characters are below the trigram size and use a substring match
+        let hits = mirror.search(&query("会議")).unwrap();
+        assert_eq!(hits.len(), 1);
+
+        // FTS syntax in user input is treated literally
+        assert!(mirror.search(&query("\"login OR\"")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_incremental_updates_and_deletes() {
+        let mut mirror = seeded();
+        assert_eq!(mirror.task_versions(1).unwrap().get(&10), Some(&100));
+
+        mirror
+            .apply(
+                1,
+                &ProjectChanges {
+                    tasks: vec![task(1, 11, "Write docs", "Explain the signup flow", 200)],
+                    deleted_tasks: vec![10],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(mirror.search(&query("login")).unwrap().is_empty());
+        assert!(mirror.search(&query("safari")).unwrap().is_empty());
+        assert_eq!(mirror.search(&query("signup")).unwrap()[0].item_id, 11);
+
+        let counts = mirror.counts().unwrap();
+        assert_eq!((counts.tasks, counts.comments), (2, 0));
+    }
+
+    #[test]
+    fn test_removed_projects_are_purged() {
+        let mut mirror = seeded();
+        let removed = mirror.prune_projects(&[1]).unwrap();
+        assert_eq!(removed, vec![2]);
+        assert!(mirror.search(&query("ログイン")).unwrap().is_empty());
+        assert_eq!(mirror.counts().unwrap().projects, 1);
+    }
+}
@@ -1,7 +1,10 @@
 use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement, Table};
 use serde::Serialize;
+use std::io::{self, BufRead, IsTerminal, Write};
+use unicode_width::UnicodeWidthStr;
 
+use crate::markdown::{self, RenderOptions};
 use crate::telemetry_span;
 
 pub enum OutputFormat {
@@ -9,7 +12,20 @@ pub enum OutputFormat {
     Json,
 }
 
+/// Markdown `text` rendered for the terminal, or as written when `raw` is set
+fn format_markdown(text: &str, raw: bool) -> String {
+    if raw {
+        text.to_string()
+    } else {
+        markdown::render(text, &RenderOptions::for_stdout())
+    }
+}
+
 pub fn print<T: Serialize>(data: &T, format: OutputFormat) -> anyhow::Result<()> {
+    print_with(data, format, false)
+}
+
+fn print_with<T: Serialize>(data: &T, format: OutputFormat, raw: bool) -> anyhow::Result<()> {
     telemetry_span::with_span_result("write_outputs", &[], || {
         match format {
             OutputFormat::Json => {
@@ -17,23 +33,52 @@ pub fn print<T: Serialize>(data: &T, format: OutputFormat) -> anyhow::Result<()>
             }
             OutputFormat::Human => {
                 let json = serde_json::to_value(data)?;
-                print_json_value(&json);
+                print_json_value(&json, raw);
             }
         }
         Ok(())
     })
 }
 
-fn print_json_value(value: &serde_json::Value) {
+/// Print a single item, keyed so human output picks the matching renderer.
+///
+/// JSON output is the bare item, identical to `print`.
+pub fn print_item<T: Serialize>(key: &str, data: &T, format: OutputFormat) -> anyhow::Result<()> {
+    print_text_item(key, data, format, false)
+}
+
+/// Like `print_item`, for a task, note or comment list: `raw` prints descriptions and
+/// comments as written instead of rendered markdown.
+pub fn print_text_item<T: Serialize>(
+    key: &str,
+    data: &T,
+    format: OutputFormat,
+    raw: bool,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Json => print(data, format),
+        OutputFormat::Human => {
+            let mut wrapped = serde_json::Map::new();
+            wrapped.insert(key.to_string(), serde_json::to_value(data)?);
+            print_with(&serde_json::Value::Object(wrapped), format, raw)
+        }
+    }
+}
+
+fn print_json_value(value: &serde_json::Value, raw: bool) {
     if let Some(obj) = value.as_object() {
-        if obj.contains_key("user") {
+        if obj.contains_key("task_comments") {
+            print_comments(obj.get("task_comments"), raw);
+        } else if obj.contains_key("note_comments") {
+            print_comments(obj.get("note_comments"), raw);
+        } else if obj.contains_key("user") {
             print_user(obj);
         } else if obj.contains_key("project") {
             print_project(obj);
         } else if obj.contains_key("task") {
-            print_task(obj);
+            print_task(obj, raw);
         } else if obj.contains_key("note") {
-            print_note(obj);
+            print_note(obj, raw);
         } else if obj.contains_key("projects") {
             print_projects(obj);
         } else if obj.contains_key("tasks") {
@@ -46,6 +91,10 @@ fn print_json_value(value: &serde_json::Value) {
             print_tags(obj);
         } else if obj.contains_key("space") {
             print_space(obj);
+        } else if obj.contains_key("task_query") {
+            print_task_query(obj);
+        } else if obj.contains_key("cache_entries") {
+            print_cache_entries(obj);
         } else {
             println!(
                 "{}",
@@ -104,15 +153,12 @@ fn print_project(obj: &serde_json::Map<String, serde_json::Value>) {
     }
 }
 
-fn print_task(obj: &serde_json::Map<String, serde_json::Value>) {
+fn print_task(obj: &serde_json::Map<String, serde_json::Value>, raw: bool) {
     if let Some(task) = obj.get("task").and_then(|v| v.as_object()) {
         println!(
             "Title: {}",
             task.get("name").and_then(|v| v.as_str()).unwrap_or("N/A")
         );
-        if let Some(desc) = task.get("description").and_then(|v| v.as_str()) {
-            println!("Description: {}", desc);
-        }
         if let Some(status) = task.get("status").and_then(|v| v.as_object()) {
             println!(
                 "Status: {}",
@@ -138,22 +184,72 @@ fn print_task(obj: &serde_json::Map<String, serde_json::Value>) {
             "ID: {}",
             task.get("id").and_then(|v| v.as_u64()).unwrap_or(0)
         );
+        if let Some(desc) = task.get("description").and_then(|v| v.as_str()) {
+            print!("{}", render_description(desc, raw));
+        }
+    }
+}
+
+/// A description after a blank line, or nothing when it is empty
+fn render_description(desc: &str, raw: bool) -> String {
+    if desc.trim().is_empty() {
+        return String::new();
     }
+    format!("\n{}\n", format_markdown(desc, raw))
 }
 
-fn print_note(obj: &serde_json::Map<String, serde_json::Value>) {
+fn print_comments(comments: Option<&serde_json::Value>, raw: bool) {
+    if let Some(comments) = comments.and_then(|v| v.as_array()) {
+        print!("{}", render_comments(comments, raw));
+    }
+}
+
+/// Comments as `#id author date` headers, each followed by its body
+fn render_comments(comments: &[serde_json::Value], raw: bool) -> String {
+    let mut out = String::new();
+    for (i, comment) in comments.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let author = comment
+            .get("user")
+            .and_then(|u| u.get("fullName"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("N/A");
+        let created = comment
+            .get("createdAt")
+            .and_then(|v| v.as_u64())
+            .map(format_date)
+            .unwrap_or_default();
+        let id = comment.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+        out.push_str(&format!(
+            "{} {} {}\n",
+            format!("#{}", id).dimmed(),
+            author.bold(),
+            created.dimmed()
+        ));
+        let body = comment
+            .get("comment")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        out.push_str(&format!("{}\n", format_markdown(body, raw)));
+    }
+    out
+}
+
+fn print_note(obj: &serde_json::Map<String, serde_json::Value>, raw: bool) {
     if let Some(note) = obj.get("note").and_then(|v| v.as_object()) {
         println!(
             "Name: {}",
             note.get("name").and_then(|v| v.as_str()).unwrap_or("N/A")
         );
-        if let Some(desc) = note.get("description").and_then(|v| v.as_str()) {
-            println!("Description: {}", desc);
-        }
         println!(
             "ID: {}",
             note.get("id").and_then(|v| v.as_u64()).unwrap_or(0)
         );
+        if let Some(desc) = note.get("description").and_then(|v| v.as_str()) {
+            print!("{}", render_description(desc, raw));
+        }
     }
 }
 
@@ -369,14 +465,196 @@ fn print_space(obj: &serde_json::Map<String, serde_json::Value>) {
     }
 }
 
+/// Format a Unix timestamp (seconds) as a local calendar date
+pub fn format_date(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Truncate text to a display width, marking the cut with an ellipsis
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut out = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let ch_width = ch.to_string().width();
+        if used + ch_width + 1 > width {
+            break;
+        }
+        out.push(ch);
+        used += ch_width;
+    }
+    out.push('…');
+    out
+}
+
+/// Right-pad text with spaces up to a display width
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
+
+fn print_task_query(obj: &serde_json::Map<String, serde_json::Value>) {
+    let Some(tasks) = obj.get("task_query").and_then(|v| v.as_array()) else {
+        return;
+    };
+    if tasks.is_empty() {
+        println!("{}", "No matching tasks".dimmed());
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Project", "ID", "Title", "Status", "Assignee", "Priority", "Due",
+        ]);
+
+    let str_at = |task: &serde_json::Value, path: &[&str]| -> String {
+        path.iter()
+            .try_fold(task, |v, key| v.get(key))
+            .and_then(|v| v.as_str())
+            .unwrap_or("-")
+            .to_string()
+    };
+    for task in tasks {
+        table.add_row(vec![
+            str_at(task, &["project", "name"]),
+            task.get("id")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+                .to_string(),
+            str_at(task, &["name"]),
+            str_at(task, &["status", "name"]),
+            str_at(task, &["responsibleUser", "fullName"]),
+            task.get("priority")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+                .to_string(),
+            task.get("dueDate")
+                .and_then(|v| v.as_u64())
+                .map(format_date)
+                .unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+
+    println!("{}", table);
+    println!("{}", format!("{} tasks", tasks.len()).dimmed());
+}
+
+fn print_cache_entries(obj: &serde_json::Map<String, serde_json::Value>) {
+    let Some(entries) = obj.get("cache_entries").and_then(|v| v.as_array()) else {
+        return;
+    };
+    if entries.is_empty() {
+        println!("{}", "Cache is empty".dimmed());
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Key", "Resource", "Age", "TTL", "Fresh", "Size"]);
+
+    for entry in entries {
+        let secs = |field: &str| {
+            entry
+                .get(field)
+                .and_then(|v| v.as_u64())
+                .map(|s| format!("{}s", s))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let fresh = entry.get("fresh").and_then(|v| v.as_bool()) == Some(true);
+        table.add_row(vec![
+            Cell::new(entry.get("key").and_then(|v| v.as_str()).unwrap_or("N/A")),
+            Cell::new(
+                entry
+                    .get("resource")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("-"),
+            ),
+            Cell::new(secs("age_secs")),
+            Cell::new(secs("ttl_secs")),
+            if fresh {
+                Cell::new("yes").fg(Color::Green)
+            } else {
+                Cell::new("stale").fg(Color::Yellow)
+            },
+            Cell::new(format!(
+                "{} B",
+                entry.get("bytes").and_then(|v| v.as_u64()).unwrap_or(0)
+            )),
+        ]);
+    }
+
+    println!("{}", table);
+}
+
 pub fn print_success(message: &str) {
     telemetry_span::with_span("write_outputs", &[], || {
         println!("{}", message.green().bold());
     });
 }
 
+/// Ask a yes/no question on the terminal, even when stdin carries piped input
+pub fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+
+    let mut input = String::new();
+    if io::stdin().is_terminal() {
+        io::stdin().read_line(&mut input)?;
+    } else {
+        let tty = std::fs::File::open("/dev/tty").map_err(|_| {
+            anyhow::anyhow!("No terminal to confirm on; pass --yes to proceed without asking")
+        })?;
+        io::BufReader::new(tty).read_line(&mut input)?;
+    }
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
+
+/// Serializes tests that force coloring, since the `colored` override is process-wide
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::sync::{Mutex, MutexGuard};
+
+    static COLOR: Mutex<()> = Mutex::new(());
+
+    /// Keeps coloring forced on or off until dropped
+    pub struct ColorOverride {
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl Drop for ColorOverride {
+        fn drop(&mut self) {
+            colored::control::unset_override();
+        }
+    }
+
+    pub fn color_override(enabled: bool) -> ColorOverride {
+        // A test that panicked while holding the lock has already restored the override
+        let lock = COLOR.lock().unwrap_or_else(|e| e.into_inner());
+        colored::control::set_override(enabled);
+        ColorOverride { _lock: lock }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::test_support::color_override;
     use super::*;
     use proptest::prelude::*;
     use serde_json::json;
@@ -617,6 +895,59 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_print_item_task_with_markdown_description() {
+        let task = json!({
+            "id": 1,
+            "name": "Task",
+            "description": "# Plan\n\n- [ ] first\n- [x] second",
+            "status": {"name": "Open"},
+            "priority": 0
+        });
+
+        assert!(print_item("task", &task, OutputFormat::Human).is_ok());
+
+        let _color = color_override(false);
+        let description = task["description"].as_str().unwrap();
+        let rendered = render_description(description, false);
+        assert!(rendered.starts_with("\n# Plan\n"), "{}", rendered);
+        assert!(rendered.contains("☐ first\n☑ second"));
+        assert_eq!(
+            render_description(description, true),
+            format!("\n{}\n", description)
+        );
+        assert_eq!(render_description("  \n", false), "");
+    }
+
+    #[test]
+    fn test_print_item_comments_list() {
+        let comments = json!([
+            {
+                "id": 1,
+                "comment": "Looks good, see `main.rs`",
+                "user": {"fullName": "User 1"},
+                "createdAt": 1640000000
+            },
+            {
+                "id": 2,
+                "comment": "",
+                "user": null,
+                "createdAt": 1640000001
+            }
+        ]);
+
+        assert!(print_item("task_comments", &comments, OutputFormat::Human).is_ok());
+        assert!(print_item("note_comments", &comments, OutputFormat::Json).is_ok());
+
+        let _color = color_override(false);
+        let rendered = render_comments(comments.as_array().unwrap(), false);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], format!("#1 User 1 {}", format_date(1640000000)));
+        assert_eq!(lines[1], "Looks good, see `main.rs`");
+        assert_eq!(lines[3], format!("#2 N/A {}", format_date(1640000001)));
+        assert!(!rendered.contains("1640000000"));
+    }
+
     #[test]
     fn test_print_task_with_null_fields() {
         let task_with_nulls = json!({
@@ -650,6 +981,14 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("hello", 10), "hello");
+        assert_eq!(truncate_to_width("hello world", 6), "hello…");
+        assert_eq!(truncate_to_width("日本語テキスト", 7), "日本語…");
+        assert_eq!(pad_to_width("ab", 4), "ab  ");
+    }
+
     // =========================================================================
     // Property-Based Tests
     // =========================================================================
@@ -0,0 +1,475 @@
+//! Change plans for `rpsn apply`.
+//!
+//! A plan is a YAML or JSON file listing creates, updates, deletes and comments on
+//! projects, tasks and notes. A create may name its new item with `ref`, and later
+//! changes refer to it as `@name`; [`ChangePlan::validate`] checks every reference and
+//! returns an order in which each item exists before it is used.
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::query;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChangePlan {
+    /// Project for task and note changes that do not name one
+    #[serde(default)]
+    pub project: Option<Ref>,
+    pub changes: Vec<Change>,
+}
+
+/// An existing item's ID, or `@name` of an item created earlier in the plan
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawRef")]
+pub enum Ref {
+    Id(u64),
+    Symbol(String),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawRef {
+    Id(u64),
+    Text(String),
+}
+
+impl TryFrom<RawRef> for Ref {
+    type Error = String;
+
+    fn try_from(raw: RawRef) -> std::result::Result<Self, Self::Error> {
+        match raw {
+            RawRef::Id(id) => Ok(Ref::Id(id)),
+            RawRef::Text(text) => match text.strip_prefix('@') {
+                Some(name) if !name.is_empty() => Ok(Ref::Symbol(name.to_string())),
+                _ => text
+                    .parse()
+                    .map(Ref::Id)
+                    .map_err(|_| format!("expected an ID or @name, got '{}'", text)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Ref {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ref::Id(id) => write!(f, "{}", id),
+            Ref::Symbol(name) => write!(f, "@{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Project,
+    Task,
+    Note,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+            Action::Comment => "comment",
+        })
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Kind::Project => "project",
+            Kind::Task => "task",
+            Kind::Note => "note",
+        })
+    }
+}
+
+/// One entry of `changes`; which fields apply depends on `action` and `type`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Change {
+    pub action: Action,
+    #[serde(rename = "type")]
+    pub kind: Kind,
+    /// Name for the item a create makes, referenced elsewhere as `@name`
+    #[serde(rename = "ref")]
+    pub symbol: Option<String>,
+    /// Item to update, delete or comment on
+    pub id: Option<Ref>,
+    pub project: Option<Ref>,
+    pub parent: Option<Ref>,
+    #[serde(alias = "title")]
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub full_name: Option<String>,
+    pub purpose: Option<String>,
+    pub status: Option<u64>,
+    pub priority: Option<u32>,
+    /// Date (`2024-06-01`, `+7d`)
+    pub due: Option<String>,
+    pub start: Option<String>,
+    pub assignee: Option<u64>,
+    pub milestone: Option<u64>,
+    pub tags: Option<Vec<u64>>,
+    /// Comment text
+    pub body: Option<String>,
+}
+
+impl Change {
+    /// The `ref` this create defines, without a leading `@`
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol
+            .as_deref()
+            .map(|s| s.strip_prefix('@').unwrap_or(s))
+    }
+
+    /// Fields this change sets, for display, in a fixed order
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = Vec::new();
+        let mut push = |name: &'static str, value: Option<String>| {
+            if let Some(value) = value {
+                fields.push((name, value));
+            }
+        };
+        push("name", self.name.clone());
+        push("full-name", self.full_name.clone());
+        push("purpose", self.purpose.clone());
+        push("description", self.description.clone());
+        push("parent", self.parent.as_ref().map(Ref::to_string));
+        push("status", self.status.map(|s| s.to_string()));
+        push("priority", self.priority.map(|p| p.to_string()));
+        push("due", self.due.clone());
+        push("start", self.start.clone());
+        push("assignee", self.assignee.map(|a| a.to_string()));
+        push("milestone", self.milestone.map(|m| m.to_string()));
+        push("tags", self.tags.as_ref().map(|t| join_ids(t)));
+        push("body", self.body.clone());
+        fields
+    }
+
+    /// `(field, kinds it applies to)` for every field this change sets
+    fn set_fields(&self) -> Vec<(&'static str, &'static [Kind])> {
+        const ITEMS: &[Kind] = &[Kind::Task, Kind::Note];
+        const TASK: &[Kind] = &[Kind::Task];
+        const PROJECT: &[Kind] = &[Kind::Project];
+        [
+            ("description", self.description.is_some(), ITEMS),
+            ("tags", self.tags.is_some(), ITEMS),
+            ("full-name", self.full_name.is_some(), PROJECT),
+            ("purpose", self.purpose.is_some(), PROJECT),
+            ("status", self.status.is_some(), TASK),
+            ("priority", self.priority.is_some(), TASK),
+            ("due", self.due.is_some(), TASK),
+            ("start", self.start.is_some(), TASK),
+            ("assignee", self.assignee.is_some(), TASK),
+            ("milestone", self.milestone.is_some(), TASK),
+        ]
+        .into_iter()
+        .filter(|(_, set, _)| *set)
+        .map(|(name, _, kinds)| (name, kinds))
+        .collect()
+    }
+}
+
+pub fn join_ids(ids: &[u64]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl ChangePlan {
+    pub fn parse(text: &str) -> Result<ChangePlan> {
+        // YAML is a superset of JSON, so one parser reads both
+        serde_yaml::from_str(text).context("Invalid change plan")
+    }
+
+    /// The project a task or note change belongs to
+    pub fn project_of<'a>(&'a self, change: &'a Change) -> Option<&'a Ref> {
+        change.project.as_ref().or(self.project.as_ref())
+    }
+
+    /// Check every change and return their indices in execution order: file order,
+    /// except that a change referring to `@name` runs after the create that defines it.
+    pub fn validate(&self, today: NaiveDate) -> Result<Vec<usize>> {
+        let mut symbols: HashMap<&str, (usize, Kind)> = HashMap::new();
+        for (i, change) in self.changes.iter().enumerate() {
+            if let Some(symbol) = change.symbol() {
+                if change.action != Action::Create {
+                    bail!("Change {}: only creates can define a ref", i + 1);
+                }
+                if symbol.is_empty() {
+                    bail!("Change {}: ref must not be empty", i + 1);
+                }
+                if let Some((first, _)) = symbols.insert(symbol, (i, change.kind)) {
+                    bail!(
+                        "Change {}: ref '{}' is already defined by change {}",
+                        i + 1,
+                        symbol,
+                        first + 1
+                    );
+                }
+            }
+        }
+
+        let mut deps: Vec<Vec<usize>> = vec![Vec::new(); self.changes.len()];
+        for (i, change) in self.changes.iter().enumerate() {
+            self.check(change, today)
+                .with_context(|| format!("Change {} ({} {})", i + 1, change.action, change.kind))?;
+
+            let mut refs = vec![(change.id.as_ref(), change.kind, "id")];
+            if change.kind != Kind::Project {
+                refs.push((self.project_of(change), Kind::Project, "project"));
+                refs.push((change.parent.as_ref(), change.kind, "parent"));
+            }
+            for (reference, expected, field) in refs {
+                let Some(Ref::Symbol(name)) = reference else {
+                    continue;
+                };
+                let Some(&(source, kind)) = symbols.get(name.as_str()) else {
+                    bail!(
+                        "Change {}: {} @{} is not defined in this plan",
+                        i + 1,
+                        field,
+                        name
+                    );
+                };
+                if kind != expected {
+                    bail!(
+                        "Change {}: {} @{} is a {}, expected a {}",
+                        i + 1,
+                        field,
+                        name,
+                        kind,
+                        expected
+                    );
+                }
+                if source == i {
+                    bail!("Change {}: {} refers to the item it creates", i + 1, field);
+                }
+                deps[i].push(source);
+            }
+        }
+
+        topological_order(&deps)
+    }
+
+    fn check(&self, change: &Change, today: NaiveDate) -> Result<()> {
+        let (action, kind) = (change.action, change.kind);
+
+        for (field, kinds) in change.set_fields() {
+            if !kinds.contains(&kind) {
+                bail!("'{}' does not apply to a {}", field, kind);
+            }
+        }
+        for date in [&change.due, &change.start].into_iter().flatten() {
+            query::parse_date(date, today)?;
+        }
+
+        match action {
+            Action::Create => {
+                if change.id.is_some() {
+                    bail!("A create cannot have an id");
+                }
+                if change.name.is_none() {
+                    bail!("A create needs a name");
+                }
+            }
+            Action::Update | Action::Delete | Action::Comment => {
+                if change.id.is_none() {
+                    bail!("An {} needs the id of the item", action);
+                }
+            }
+        }
+        if action == Action::Update && change.fields().is_empty() {
+            bail!("An update needs at least one field to change");
+        }
+        if action == Action::Comment {
+            if kind == Kind::Project {
+                bail!("Only tasks and notes take comments");
+            }
+            if change.body.is_none() {
+                bail!("A comment needs a body");
+            }
+        } else if change.body.is_some() {
+            bail!("'body' only applies to comments");
+        }
+        if action == Action::Delete && !change.fields().is_empty() {
+            bail!("A delete takes only an id");
+        }
+
+        if kind == Kind::Project {
+            if change.project.is_some() || change.parent.is_some() {
+                bail!("A project has no project or parent");
+            }
+        } else if self.project_of(change).is_none() {
+            bail!(
+                "A {} needs a project (on the change or at the top of the plan)",
+                kind
+            );
+        }
+        let parent_allowed = matches!(
+            (action, kind),
+            (Action::Create, Kind::Task | Kind::Note) | (Action::Update, Kind::Task)
+        );
+        if change.parent.is_some() && !parent_allowed {
+            bail!("'parent' does not apply to a {} {}", kind, action);
+        }
+        Ok(())
+    }
+}
+
+/// Kahn's algorithm, always taking the earliest ready change so file order is kept
+/// where dependencies allow
+fn topological_order(deps: &[Vec<usize>]) -> Result<Vec<usize>> {
+    let mut remaining: Vec<usize> = deps.iter().map(Vec::len).collect();
+    let mut done = vec![false; deps.len()];
+    let mut order = Vec::with_capacity(deps.len());
+    while order.len() < deps.len() {
+        let Some(next) = (0..deps.len()).find(|&i| !done[i] && remaining[i] == 0) else {
+            let stuck: Vec<String> = (0..deps.len())
+                .filter(|&i| !done[i])
+                .map(|i| (i + 1).to_string())
+                .collect();
+            bail!("Circular references between changes {}", stuck.join(", "));
+        };
+        done[next] = true;
+        order.push(next);
+        for (i, d) in deps.iter().enumerate() {
+            remaining[i] -= d.iter().filter(|&&source| source == next).count();
+        }
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, 15).unwrap()
+    }
+
+    #[test]
+    fn test_parse_yaml_and_json() {
+        let yaml = r#"
+project: 12
+changes:
+  - action: create
+    type: task
+    ref: child
+    parent: "@epic"
+    title: Write copy
+  - action: create
+    type: task
+    ref: epic
+    name: Launch site
+    priority: 4
+  - action: comment
+    type: task
+    id: "@epic"
+    body: Kicking off
+"#;
+        let plan = ChangePlan::parse(yaml).unwrap();
+        assert_eq!(plan.project, Some(Ref::Id(12)));
+        assert_eq!(plan.changes[0].name.as_deref(), Some("Write copy"));
+        assert_eq!(
+            plan.changes[0].parent,
+            Some(Ref::Symbol("epic".to_string()))
+        );
+        assert_eq!(plan.validate(today()).unwrap(), vec![1, 0, 2]);
+
+        let json = r#"{"changes": [{"action": "delete", "type": "project", "id": "7"}]}"#;
+        let plan = ChangePlan::parse(json).unwrap();
+        assert_eq!(plan.changes[0].id, Some(Ref::Id(7)));
+        assert_eq!(plan.validate(today()).unwrap(), vec![0]);
+
+        assert!(ChangePlan::parse("changes: [{action: create, type: task, colour: red}]").is_err());
+    }
+
+    #[test]
+    fn test_symbols_create_project_then_items() {
+        let plan = ChangePlan::parse(
+            r#"
+changes:
+  - {action: create, type: note, project: "@launch", name: Plan}
+  - {action: create, type: project, ref: launch, name: Launch}
+"#,
+        )
+        .unwrap();
+        assert_eq!(plan.validate(today()).unwrap(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_references() {
+        let undefined = ChangePlan::parse(
+            "changes: [{action: update, type: task, project: 1, id: '@nope', priority: 2}]",
+        )
+        .unwrap();
+        let err = undefined.validate(today()).unwrap_err();
+        assert!(err.to_string().contains("@nope is not defined"));
+
+        let wrong_kind = ChangePlan::parse(
+            r#"
+changes:
+  - {action: create, type: note, ref: doc, project: 1, name: Doc}
+  - {action: create, type: task, project: "@doc", name: T}
+"#,
+        )
+        .unwrap();
+        let err = wrong_kind.validate(today()).unwrap_err();
+        assert!(err.to_string().contains("is a note, expected a project"));
+
+        let cycle = ChangePlan::parse(
+            r#"
+project: 1
+changes:
+  - {action: create, type: task, ref: a, parent: "@b", name: A}
+  - {action: create, type: task, ref: b, parent: "@a", name: B}
+"#,
+        )
+        .unwrap();
+        let err = cycle.validate(today()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Circular references between changes 1, 2"));
+    }
+
+    #[test]
+    fn test_validate_checks_fields_per_action_and_type() {
+        let cases = [
+            "{action: create, type: project, name: P, priority: 3}",
+            "{action: create, type: task, name: T}",
+            "{action: update, type: task, project: 1, id: 2}",
+            "{action: delete, type: note, project: 1, id: 2, name: N}",
+            "{action: comment, type: project, id: 2, body: hi}",
+            "{action: comment, type: task, project: 1, id: 2}",
+            "{action: create, type: task, project: 1, name: T, due: someday}",
+        ];
+        for case in cases {
+            let plan = ChangePlan::parse(&format!("changes: [{}]", case)).unwrap();
+            assert!(
+                plan.validate(today()).is_err(),
+                "{} should be rejected",
+                case
+            );
+        }
+    }
+}
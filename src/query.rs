@@ -0,0 +1,626 @@
+//! Task filters shared by `rpsn task query` and the task bulk commands.
+//!
+//! A [`TaskQuery`] holds filters as the user wrote them; [`TaskQuery::compile`]
+//! resolves dates and comparisons once, then [`Matcher::matches`] checks each task.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::api::types::Task;
+use crate::commands::board::matches_ref;
+
+/// Days without an update after which an open task counts as stale
+pub const STALE_DAYS: i64 = 14;
+
+/// Built-in task views
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum View {
+    /// Open tasks due before today
+    Overdue,
+    /// Open tasks due from today through Sunday
+    DueThisWeek,
+    /// Open tasks with nobody responsible
+    Unassigned,
+    /// Open tasks not updated for `STALE_DAYS` days
+    Stale,
+}
+
+impl View {
+    pub const ALL: [View; 4] = [
+        View::Overdue,
+        View::DueThisWeek,
+        View::Unassigned,
+        View::Stale,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            View::Overdue => "overdue",
+            View::DueThisWeek => "due-this-week",
+            View::Unassigned => "unassigned",
+            View::Stale => "stale",
+        }
+    }
+}
+
+impl fmt::Display for View {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for View {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        View::ALL
+            .into_iter()
+            .find(|v| v.name() == s)
+            .with_context(|| {
+                let names: Vec<_> = View::ALL.iter().map(|v| v.name()).collect();
+                format!(
+                    "Unknown view '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Task filters as written on the command line; every set field must match
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TaskQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view: Option<View>,
+    /// Projects to search; empty means every open project
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<u64>,
+    /// Responsible user: ID, name, `me`, or `none`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Status ID or name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Tag ID or name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Date (`2024-05-01`, `today`, `+7d`); tasks due strictly before it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_before: Option<String>,
+    /// Exact priority (`3`) or a comparison (`>=3`, `<2`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// Date (`2024-05-01`, `7d` for seven days ago); tasks updated on or after it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_since: Option<String>,
+    /// Also match tasks in a closed status (implied by `status`)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_closed: bool,
+}
+
+/// Comparison against a task's priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityFilter {
+    Eq(u32),
+    Ge(u32),
+    Gt(u32),
+    Le(u32),
+    Lt(u32),
+}
+
+impl PriorityFilter {
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let (op, number): (fn(u32) -> Self, &str) = if let Some(n) = value.strip_prefix(">=") {
+            (PriorityFilter::Ge, n)
+        } else if let Some(n) = value.strip_prefix("<=") {
+            (PriorityFilter::Le, n)
+        } else if let Some(n) = value.strip_prefix('>') {
+            (PriorityFilter::Gt, n)
+        } else if let Some(n) = value.strip_prefix('<') {
+            (PriorityFilter::Lt, n)
+        } else {
+            (PriorityFilter::Eq, value.strip_prefix('=').unwrap_or(value))
+        };
+        let number = number
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid priority filter '{}'", value))?;
+        Ok(op(number))
+    }
+
+    pub fn matches(self, priority: u32) -> bool {
+        match self {
+            PriorityFilter::Eq(n) => priority == n,
+            PriorityFilter::Ge(n) => priority >= n,
+            PriorityFilter::Gt(n) => priority > n,
+            PriorityFilter::Le(n) => priority <= n,
+            PriorityFilter::Lt(n) => priority < n,
+        }
+    }
+}
+
+/// Parse a date as a Unix timestamp at local midnight.
+///
+/// Accepts `YYYY-MM-DD`, `today`, `tomorrow`, `yesterday`, and day/week offsets from
+/// today: `+3d` and `+2w` are in the future, `-3d` and a bare `3d` are in the past.
+pub fn parse_date(value: &str, today: NaiveDate) -> Result<u64> {
+    let value = value.trim();
+    let date = match value {
+        "today" => today,
+        "tomorrow" => today + Duration::days(1),
+        "yesterday" => today - Duration::days(1),
+        _ => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => parse_offset(value)
+                .and_then(|offset| today.checked_add_signed(offset))
+                .ok_or_else(|| invalid_date(value))?,
+        },
+    };
+    Ok(start_of_day(date))
+}
+
+fn invalid_date(value: &str) -> anyhow::Error {
+    anyhow!(
+        "Invalid date '{}' (use YYYY-MM-DD, today, tomorrow, or offsets like +3d, 7d, 2w)",
+        value
+    )
+}
+
+/// Signed offset for `+3d`, `-2w` or `7d`; `None` when malformed or out of range
+fn parse_offset(value: &str) -> Option<Duration> {
+    let (sign, rest) = match value.strip_prefix('+') {
+        Some(rest) => (1, rest),
+        None => (-1, value.strip_prefix('-').unwrap_or(value)),
+    };
+    let (number, unit_days) = if let Some(n) = rest.strip_suffix('d') {
+        (n, 1)
+    } else if let Some(n) = rest.strip_suffix('w') {
+        (n, 7)
+    } else {
+        return None;
+    };
+    let days = number.parse::<i64>().ok()?.checked_mul(sign * unit_days)?;
+    Duration::try_days(days)
+}
+
+/// Unix timestamp of local midnight at the start of `date`
+pub fn start_of_day(date: NaiveDate) -> u64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|dt| dt.timestamp().max(0) as u64)
+        .unwrap_or_default()
+}
+
+/// Whom the assignee filter refers to
+#[derive(Debug, Clone, PartialEq)]
+enum AssigneeFilter {
+    Nobody,
+    User(String),
+}
+
+/// A compiled [`TaskQuery`]
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    assignee: Option<AssigneeFilter>,
+    status: Option<String>,
+    tag: Option<String>,
+    due_before: Option<u64>,
+    due_from: Option<u64>,
+    priority: Option<PriorityFilter>,
+    updated_since: Option<u64>,
+    updated_before: Option<u64>,
+    include_closed: bool,
+}
+
+impl TaskQuery {
+    /// Parse a `--where` expression: comma-separated `key=value` pairs such as
+    /// `status=Todo,tag=bug,priority>=3`. Keys are the `rpsn task query` flag names.
+    pub fn parse_where(expr: &str) -> Result<TaskQuery> {
+        let mut query = TaskQuery::default();
+        for clause in expr.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            let Some(split) = clause.find(['=', '<', '>']) else {
+                bail!("Expected key=value in --where, got '{}'", clause);
+            };
+            let key = clause[..split].trim();
+            let rest = &clause[split..];
+            let value = rest.strip_prefix('=').unwrap_or(rest).trim().to_string();
+            if key != "priority" && !rest.starts_with('=') {
+                bail!("Only '=' is supported for '{}' in --where", key);
+            }
+            match key {
+                "view" => query.view = Some(value.parse()?),
+                "project" => query.projects.push(
+                    value
+                        .parse()
+                        .with_context(|| format!("Invalid project ID '{}'", value))?,
+                ),
+                "assignee" => query.assignee = Some(value),
+                "status" => query.status = Some(value),
+                "tag" => query.tag = Some(value),
+                "due-before" => query.due_before = Some(value),
+                "priority" => query.priority = Some(rest.trim().to_string()),
+                "updated-since" => query.updated_since = Some(value),
+                "include-closed" => {
+                    query.include_closed = value
+                        .parse()
+                        .with_context(|| format!("Expected true or false, got '{}'", value))?
+                }
+                _ => bail!(
+                    "Unknown --where key '{}' (expected view, project, assignee, status, tag, \
+                     due-before, priority, updated-since or include-closed)",
+                    key
+                ),
+            }
+        }
+        Ok(query)
+    }
+
+    /// This query with every field set in `overrides` replacing its own
+    pub fn merged_with(&self, overrides: TaskQuery) -> TaskQuery {
+        TaskQuery {
+            view: overrides.view.or(self.view),
+            projects: if overrides.projects.is_empty() {
+                self.projects.clone()
+            } else {
+                overrides.projects
+            },
+            assignee: overrides.assignee.or_else(|| self.assignee.clone()),
+            status: overrides.status.or_else(|| self.status.clone()),
+            tag: overrides.tag.or_else(|| self.tag.clone()),
+            due_before: overrides.due_before.or_else(|| self.due_before.clone()),
+            priority: overrides.priority.or_else(|| self.priority.clone()),
+            updated_since: overrides
+                .updated_since
+                .or_else(|| self.updated_since.clone()),
+            include_closed: self.include_closed || overrides.include_closed,
+        }
+    }
+
+    /// True when nothing narrows the query, so it would select every open task in the space
+    pub fn is_unfiltered(&self) -> bool {
+        *self
+            == TaskQuery {
+                include_closed: self.include_closed,
+                ..Default::default()
+            }
+    }
+
+    /// True when the query needs the current user's ID (`assignee = "me"`)
+    pub fn needs_me(&self) -> bool {
+        self.assignee
+            .as_deref()
+            .is_some_and(|a| a.eq_ignore_ascii_case("me"))
+    }
+
+    /// Resolve dates relative to `today`; `me` is the current user's ID for `assignee = "me"`
+    pub fn compile(&self, today: NaiveDate, me: Option<u64>) -> Result<Matcher> {
+        let mut matcher = Matcher {
+            assignee: None,
+            status: self.status.clone(),
+            tag: self.tag.clone(),
+            due_before: None,
+            due_from: None,
+            priority: self
+                .priority
+                .as_deref()
+                .map(PriorityFilter::parse)
+                .transpose()?,
+            updated_since: self
+                .updated_since
+                .as_deref()
+                .map(|d| parse_date(d, today))
+                .transpose()?,
+            updated_before: None,
+            include_closed: self.include_closed,
+        };
+
+        matcher.assignee = match self.assignee.as_deref().map(str::trim) {
+            None => None,
+            Some(a) if a.eq_ignore_ascii_case("none") => Some(AssigneeFilter::Nobody),
+            Some(a) if a.eq_ignore_ascii_case("me") => {
+                let id = me.context("Could not resolve 'me' to a user")?;
+                Some(AssigneeFilter::User(id.to_string()))
+            }
+            Some(a) => Some(AssigneeFilter::User(a.to_string())),
+        };
+
+        let mut due_before = self
+            .due_before
+            .as_deref()
+            .map(|d| parse_date(d, today))
+            .transpose()?;
+        match self.view {
+            Some(View::Overdue) => {
+                due_before = Some(min_some(due_before, start_of_day(today)));
+            }
+            Some(View::DueThisWeek) => {
+                let days_left = 7 - i64::from(today.weekday().num_days_from_monday());
+                let next_monday = today + Duration::days(days_left);
+                matcher.due_from = Some(start_of_day(today));
+                due_before = Some(min_some(due_before, start_of_day(next_monday)));
+            }
+            Some(View::Unassigned) => matcher.assignee = Some(AssigneeFilter::Nobody),
+            Some(View::Stale) => {
+                matcher.updated_before = Some(start_of_day(today - Duration::days(STALE_DAYS)));
+            }
+            None => {}
+        }
+        matcher.due_before = due_before;
+        Ok(matcher)
+    }
+}
+
+fn min_some(current: Option<u64>, value: u64) -> u64 {
+    current.map_or(value, |c| c.min(value))
+}
+
+impl Matcher {
+    pub fn matches(&self, task: &Task) -> bool {
+        // Naming a status asks for exactly that status, even a closed one
+        if !self.include_closed && self.status.is_none() && task.status.is_closed {
+            return false;
+        }
+        match &self.assignee {
+            Some(AssigneeFilter::Nobody) if task.responsible_user.is_some() => return false,
+            Some(AssigneeFilter::User(filter)) => {
+                let matched = task
+                    .responsible_user
+                    .as_ref()
+                    .is_some_and(|u| matches_ref(filter, u.id, &[&u.name, &u.full_name]));
+                if !matched {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+        if let Some(status) = &self.status {
+            if !matches_ref(status, task.status.id, &[&task.status.name]) {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if !task.tags.iter().any(|t| matches_ref(tag, t.id, &[&t.name])) {
+                return false;
+            }
+        }
+        if self.due_before.is_some() || self.due_from.is_some() {
+            let Some(due) = task.due_date else {
+                return false;
+            };
+            if self.due_before.is_some_and(|before| due >= before)
+                || self.due_from.is_some_and(|from| due < from)
+            {
+                return false;
+            }
+        }
+        if let Some(priority) = self.priority {
+            if !priority.matches(task.priority) {
+                return false;
+            }
+        }
+        if self
+            .updated_since
+            .is_some_and(|since| task.updated_at < since)
+            || self
+                .updated_before
+                .is_some_and(|before| task.updated_at >= before)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::{test_support, Tag, User};
+
+    fn today() -> NaiveDate {
+        // A Wednesday
+        NaiveDate::from_ymd_opt(2024, 5, 15).unwrap()
+    }
+
+    fn day(offset: i64) -> u64 {
+        start_of_day(today() + Duration::days(offset))
+    }
+
+    fn user(id: u64, name: &str) -> User {
+        User {
+            id,
+            email: format!("{}@example.com", name),
+            name: name.to_string(),
+            full_name: name.to_string(),
+            avatar_url: None,
+            role: "member".to_string(),
+            billing_status: "active".to_string(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn task(id: u64) -> Task {
+        Task {
+            updated_at: day(0),
+            ..test_support::task(id)
+        }
+    }
+
+    fn matches(query: &TaskQuery, task: &Task) -> bool {
+        query.compile(today(), Some(42)).unwrap().matches(task)
+    }
+
+    #[test]
+    fn test_parse_date_forms() {
+        assert_eq!(parse_date("today", today()).unwrap(), day(0));
+        assert_eq!(parse_date("tomorrow", today()).unwrap(), day(1));
+        assert_eq!(parse_date("+2w", today()).unwrap(), day(14));
+        assert_eq!(parse_date("7d", today()).unwrap(), day(-7));
+        assert_eq!(parse_date("-1d", today()).unwrap(), day(-1));
+        assert_eq!(parse_date("2024-05-20", today()).unwrap(), day(5));
+        assert!(parse_date("soon", today()).is_err());
+        assert!(parse_date("999999999d", today()).is_err());
+        assert!(parse_date("+9223372036854775807w", today()).is_err());
+    }
+
+    #[test]
+    fn test_priority_filter() {
+        assert_eq!(PriorityFilter::parse("3").unwrap(), PriorityFilter::Eq(3));
+        assert!(PriorityFilter::parse(">=2").unwrap().matches(2));
+        assert!(!PriorityFilter::parse(">2").unwrap().matches(2));
+        assert!(PriorityFilter::parse("<2").unwrap().matches(1));
+        assert!(PriorityFilter::parse("high").is_err());
+    }
+
+    #[test]
+    fn test_view_names_round_trip() {
+        for view in View::ALL {
+            assert_eq!(view.name().parse::<View>().unwrap(), view);
+        }
+        assert!("later".parse::<View>().is_err());
+    }
+
+    #[test]
+    fn test_overdue_and_due_this_week() {
+        let overdue = TaskQuery {
+            view: Some(View::Overdue),
+            ..Default::default()
+        };
+        let week = TaskQuery {
+            view: Some(View::DueThisWeek),
+            ..Default::default()
+        };
+        let mut t = task(1);
+        assert!(!matches(&overdue, &t), "no due date");
+
+        t.due_date = Some(day(-1));
+        assert!(matches(&overdue, &t));
+        assert!(!matches(&week, &t));
+
+        t.due_date = Some(day(0));
+        assert!(!matches(&overdue, &t));
+        assert!(matches(&week, &t));
+
+        // Wednesday + 4 = Sunday is in this week, Monday is not
+        t.due_date = Some(day(4));
+        assert!(matches(&week, &t));
+        t.due_date = Some(day(5));
+        assert!(!matches(&week, &t));
+
+        t.due_date = Some(day(-1));
+        t.status.is_closed = true;
+        assert!(!matches(&overdue, &t));
+    }
+
+    #[test]
+    fn test_unassigned_stale_and_assignee() {
+        let mut t = task(1);
+        let unassigned = TaskQuery {
+            view: Some(View::Unassigned),
+            ..Default::default()
+        };
+        assert!(matches(&unassigned, &t));
+
+        let stale = TaskQuery {
+            view: Some(View::Stale),
+            ..Default::default()
+        };
+        assert!(!matches(&stale, &t));
+        t.updated_at = day(-STALE_DAYS - 1);
+        assert!(matches(&stale, &t));
+
+        t.responsible_user = Some(user(42, "alice"));
+        assert!(!matches(&unassigned, &t));
+        let me = TaskQuery {
+            assignee: Some("me".to_string()),
+            ..Default::default()
+        };
+        assert!(me.needs_me());
+        assert!(matches(&me, &t));
+        let bob = TaskQuery {
+            assignee: Some("bob".to_string()),
+            ..Default::default()
+        };
+        assert!(!matches(&bob, &t));
+    }
+
+    #[test]
+    fn test_merged_with_prefers_overrides() {
+        let saved = TaskQuery {
+            view: Some(View::Overdue),
+            projects: vec![1, 2],
+            tag: Some("bug".to_string()),
+            assignee: Some("me".to_string()),
+            ..Default::default()
+        };
+        let merged = saved.merged_with(TaskQuery {
+            assignee: Some("alice".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(merged.view, Some(View::Overdue));
+        assert_eq!(merged.projects, vec![1, 2]);
+        assert_eq!(merged.tag.as_deref(), Some("bug"));
+        assert_eq!(merged.assignee.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn test_parse_where() {
+        let query = TaskQuery::parse_where("status=Todo, tag=bug,priority>=3,project=12").unwrap();
+        assert_eq!(query.status.as_deref(), Some("Todo"));
+        assert_eq!(query.tag.as_deref(), Some("bug"));
+        assert_eq!(query.priority.as_deref(), Some(">=3"));
+        assert_eq!(query.projects, vec![12]);
+
+        let query = TaskQuery::parse_where("priority=2,view=stale,include-closed=true").unwrap();
+        assert_eq!(query.priority.as_deref(), Some("=2"));
+        assert_eq!(query.view, Some(View::Stale));
+        assert!(query.include_closed);
+
+        assert!(TaskQuery::parse_where("colour=red").is_err());
+        assert!(TaskQuery::parse_where("status>Todo").is_err());
+        assert!(TaskQuery::parse_where("bug").is_err());
+    }
+
+    #[test]
+    fn test_field_filters_combine() {
+        let mut t = task(1);
+        t.priority = 4;
+        t.tags = vec![Tag {
+            id: 3,
+            name: "bug".to_string(),
+            color: "red".to_string(),
+        }];
+        let query = TaskQuery {
+            status: Some("todo".to_string()),
+            tag: Some("bug".to_string()),
+            priority: Some(">=3".to_string()),
+            updated_since: Some("7d".to_string()),
+            ..Default::default()
+        };
+        assert!(matches(&query, &t));
+
+        t.tags.clear();
+        assert!(!matches(&query, &t));
+    }
+
+    #[test]
+    fn test_closed_status_filter_matches_closed_tasks() {
+        let mut t = task(1);
+        t.status.name = "Done".to_string();
+        t.status.is_closed = true;
+        assert!(!matches(&TaskQuery::default(), &t));
+        assert!(matches(&TaskQuery::parse_where("status=Done").unwrap(), &t));
+        assert!(!matches(
+            &TaskQuery::parse_where("status=Todo").unwrap(),
+            &t
+        ));
+    }
+}
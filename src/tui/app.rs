@@ -0,0 +1,990 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::api::types::{History, InboxItem, Project, Status, Task, TaskComment, TaskStatus, User};
+use crate::api::RateLimit;
+
+/// Pane that receives navigation keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Projects,
+    Tasks,
+    Detail,
+    Inbox,
+}
+
+/// Section shown in the task detail pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailTab {
+    Description,
+    Comments,
+    History,
+}
+
+impl DetailTab {
+    pub const ALL: [DetailTab; 3] = [
+        DetailTab::Description,
+        DetailTab::Comments,
+        DetailTab::History,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            DetailTab::Description => "Description",
+            DetailTab::Comments => "Comments",
+            DetailTab::History => "History",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            DetailTab::Description => DetailTab::Comments,
+            DetailTab::Comments => DetailTab::History,
+            DetailTab::History => DetailTab::Description,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            DetailTab::Description => DetailTab::History,
+            DetailTab::Comments => DetailTab::Description,
+            DetailTab::History => DetailTab::Comments,
+        }
+    }
+}
+
+/// Input mode; anything other than `Normal` captures keystrokes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Filter,
+    Comment(String),
+    StatusPicker(usize),
+    AssigneePicker(usize),
+    Help,
+}
+
+/// Work the event loop should perform against the API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Projects,
+    Tasks(u64),
+    Detail {
+        project_id: u64,
+        task_id: u64,
+    },
+    Statuses(u64),
+    Members(u64),
+    Inbox,
+    SetStatus {
+        project_id: u64,
+        task_id: u64,
+        status_id: u64,
+    },
+    Reassign {
+        project_id: u64,
+        task_id: u64,
+        user_id: u64,
+    },
+    Comment {
+        project_id: u64,
+        task_id: u64,
+        text: String,
+    },
+    MarkRead(u64),
+    MarkAllRead,
+}
+
+/// Result of a completed [`Request`]
+#[derive(Debug)]
+pub enum Response {
+    Projects(Vec<Project>),
+    Tasks {
+        project_id: u64,
+        tasks: Vec<Task>,
+    },
+    Detail {
+        task: Box<Task>,
+        comments: Vec<TaskComment>,
+        history: Vec<History>,
+    },
+    Statuses {
+        project_id: u64,
+        statuses: Vec<Status>,
+    },
+    Members {
+        project_id: u64,
+        users: Vec<User>,
+    },
+    Inbox(Vec<InboxItem>),
+    TaskUpdated(Box<Task>),
+    Commented {
+        project_id: u64,
+        task_id: u64,
+    },
+    InboxRead(u64),
+    InboxAllRead,
+    Failed(String),
+}
+
+/// Loaded task detail: the task itself plus its comments and history
+#[derive(Debug, Clone)]
+pub struct TaskDetail {
+    pub task: Task,
+    pub comments: Vec<TaskComment>,
+    pub history: Vec<History>,
+}
+
+/// Complete TUI state; rendering reads it and key handling mutates it
+pub struct App {
+    pub projects: Vec<Project>,
+    pub project_index: usize,
+    pub current_project: Option<u64>,
+    pub tasks: Vec<Task>,
+    pub task_index: usize,
+    pub filter: String,
+    pub detail: Option<TaskDetail>,
+    pub detail_tab: DetailTab,
+    pub detail_scroll: u16,
+    pub statuses: Vec<Status>,
+    pub members: Vec<User>,
+    pub inbox: Vec<InboxItem>,
+    pub inbox_index: usize,
+    pub focus: Focus,
+    pub mode: Mode,
+    pub message: Option<String>,
+    pub pending: usize,
+    pub rate_limit: Option<RateLimit>,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            projects: Vec::new(),
+            project_index: 0,
+            current_project: None,
+            tasks: Vec::new(),
+            task_index: 0,
+            filter: String::new(),
+            detail: None,
+            detail_tab: DetailTab::Description,
+            detail_scroll: 0,
+            statuses: Vec::new(),
+            members: Vec::new(),
+            inbox: Vec::new(),
+            inbox_index: 0,
+            focus: Focus::Projects,
+            mode: Mode::Normal,
+            message: None,
+            pending: 0,
+            rate_limit: None,
+            should_quit: false,
+        }
+    }
+
+    /// Requests issued once at startup
+    pub fn initial_requests(&self) -> Vec<Request> {
+        vec![Request::Projects, Request::Inbox]
+    }
+
+    /// Requests issued by the periodic background refresh
+    pub fn refresh_requests(&self) -> Vec<Request> {
+        let mut requests = vec![Request::Inbox];
+        if let Some(project_id) = self.current_project {
+            requests.push(Request::Tasks(project_id));
+        }
+        if let Some(detail) = &self.detail {
+            requests.push(Request::Detail {
+                project_id: detail.task.project.id,
+                task_id: detail.task.id,
+            });
+        }
+        requests
+    }
+
+    /// Tasks matching the live filter, in list order
+    pub fn visible_tasks(&self) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| task_matches(task, &self.filter))
+            .collect()
+    }
+
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.visible_tasks().get(self.task_index).copied()
+    }
+
+    pub fn selected_project(&self) -> Option<&Project> {
+        self.projects.get(self.project_index)
+    }
+
+    pub fn selected_inbox(&self) -> Option<&InboxItem> {
+        self.inbox.get(self.inbox_index)
+    }
+
+    pub fn project_name(&self, project_id: u64) -> Option<&str> {
+        self.projects
+            .iter()
+            .find(|p| p.id == project_id)
+            .map(|p| p.name.as_str())
+    }
+
+    /// Task targeted by quick actions: the open detail, else the list selection
+    fn action_target(&self) -> Option<(u64, u64)> {
+        if self.focus == Focus::Detail {
+            if let Some(detail) = &self.detail {
+                return Some((detail.task.project.id, detail.task.id));
+            }
+        }
+        self.selected_task().map(|t| (t.project.id, t.id))
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) -> Vec<Request> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return Vec::new();
+        }
+
+        match self.mode.clone() {
+            Mode::Normal => self.on_normal_key(key),
+            Mode::Help => {
+                self.mode = Mode::Normal;
+                Vec::new()
+            }
+            Mode::Filter => {
+                self.on_filter_key(key);
+                Vec::new()
+            }
+            Mode::Comment(text) => self.on_comment_key(key, text),
+            Mode::StatusPicker(index) => self.on_status_picker_key(key, index),
+            Mode::AssigneePicker(index) => self.on_assignee_picker_key(key, index),
+        }
+    }
+
+    fn on_normal_key(&mut self, key: KeyEvent) -> Vec<Request> {
+        match key.code {
+            KeyCode::Char('q') => {
+                self.should_quit = true;
+                Vec::new()
+            }
+            KeyCode::Char('?') => {
+                self.mode = Mode::Help;
+                Vec::new()
+            }
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Projects => Focus::Tasks,
+                    Focus::Tasks => Focus::Detail,
+                    Focus::Detail | Focus::Inbox => Focus::Projects,
+                };
+                Vec::new()
+            }
+            KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Projects => Focus::Detail,
+                    Focus::Tasks | Focus::Inbox => Focus::Projects,
+                    Focus::Detail => Focus::Tasks,
+                };
+                Vec::new()
+            }
+            KeyCode::Char('i') => {
+                self.focus = if self.focus == Focus::Inbox {
+                    Focus::Projects
+                } else {
+                    Focus::Inbox
+                };
+                Vec::new()
+            }
+            KeyCode::Esc => {
+                if self.focus == Focus::Inbox {
+                    self.focus = Focus::Projects;
+                } else if !self.filter.is_empty() {
+                    self.filter.clear();
+                    self.task_index = 0;
+                }
+                Vec::new()
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.move_selection(1);
+                Vec::new()
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.move_selection(-1);
+                Vec::new()
+            }
+            KeyCode::Char('r') => self.refresh_requests(),
+            KeyCode::Enter => self.on_enter(),
+            KeyCode::Char('/') if self.focus != Focus::Inbox => {
+                self.mode = Mode::Filter;
+                self.focus = Focus::Tasks;
+                Vec::new()
+            }
+            KeyCode::Char(']') => {
+                self.detail_tab = self.detail_tab.next();
+                self.detail_scroll = 0;
+                Vec::new()
+            }
+            KeyCode::Char('[') => {
+                self.detail_tab = self.detail_tab.prev();
+                self.detail_scroll = 0;
+                Vec::new()
+            }
+            KeyCode::Char('m') if self.focus == Focus::Inbox => self
+                .selected_inbox()
+                .map(|item| vec![Request::MarkRead(item.id)])
+                .unwrap_or_default(),
+            KeyCode::Char('M') if self.focus == Focus::Inbox => vec![Request::MarkAllRead],
+            KeyCode::Char('d') => self.status_action(TaskStatus::Done.id()),
+            KeyCode::Char('o') => self.status_action(TaskStatus::Open.id()),
+            KeyCode::Char('s') => {
+                if self.action_target().is_some() {
+                    if self.statuses.is_empty() {
+                        self.message = Some("No statuses loaded for this project".to_string());
+                    } else {
+                        self.mode = Mode::StatusPicker(0);
+                    }
+                }
+                Vec::new()
+            }
+            KeyCode::Char('a') => {
+                if self.action_target().is_some() {
+                    if self.members.is_empty() {
+                        self.message = Some("No members loaded for this project".to_string());
+                    } else {
+                        self.mode = Mode::AssigneePicker(0);
+                    }
+                }
+                Vec::new()
+            }
+            KeyCode::Char('c') => {
+                if self.action_target().is_some() {
+                    self.mode = Mode::Comment(String::new());
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn on_enter(&mut self) -> Vec<Request> {
+        match self.focus {
+            Focus::Projects => {
+                let Some(project_id) = self.selected_project().map(|p| p.id) else {
+                    return Vec::new();
+                };
+                self.open_project(project_id);
+                self.focus = Focus::Tasks;
+                vec![
+                    Request::Tasks(project_id),
+                    Request::Statuses(project_id),
+                    Request::Members(project_id),
+                ]
+            }
+            Focus::Tasks => {
+                let Some((project_id, task_id)) =
+                    self.selected_task().map(|t| (t.project.id, t.id))
+                else {
+                    return Vec::new();
+                };
+                self.focus = Focus::Detail;
+                self.detail_scroll = 0;
+                vec![Request::Detail {
+                    project_id,
+                    task_id,
+                }]
+            }
+            Focus::Detail => Vec::new(),
+            Focus::Inbox => {
+                let Some(task) = self.selected_inbox().and_then(|item| item.task.clone()) else {
+                    return Vec::new();
+                };
+                let project_id = task.project.id;
+                let mut requests = Vec::new();
+                if self.current_project != Some(project_id) {
+                    self.open_project(project_id);
+                    if let Some(index) = self.projects.iter().position(|p| p.id == project_id) {
+                        self.project_index = index;
+                    }
+                    requests.extend([
+                        Request::Tasks(project_id),
+                        Request::Statuses(project_id),
+                        Request::Members(project_id),
+                    ]);
+                }
+                self.focus = Focus::Detail;
+                self.detail_scroll = 0;
+                requests.push(Request::Detail {
+                    project_id,
+                    task_id: task.id,
+                });
+                requests
+            }
+        }
+    }
+
+    fn open_project(&mut self, project_id: u64) {
+        if self.current_project != Some(project_id) {
+            self.current_project = Some(project_id);
+            self.tasks.clear();
+            self.statuses.clear();
+            self.members.clear();
+            self.detail = None;
+            self.task_index = 0;
+            self.filter.clear();
+        }
+    }
+
+    fn status_action(&mut self, status_id: u64) -> Vec<Request> {
+        match self.action_target() {
+            Some((project_id, task_id)) => vec![Request::SetStatus {
+                project_id,
+                task_id,
+                status_id,
+            }],
+            None => Vec::new(),
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        match self.focus {
+            Focus::Projects => {
+                self.project_index = step(self.project_index, delta, self.projects.len());
+            }
+            Focus::Tasks => {
+                self.task_index = step(self.task_index, delta, self.visible_tasks().len());
+            }
+            Focus::Detail => {
+                self.detail_scroll = if delta < 0 {
+                    self.detail_scroll.saturating_sub(1)
+                } else {
+                    self.detail_scroll.saturating_add(1)
+                };
+            }
+            Focus::Inbox => {
+                self.inbox_index = step(self.inbox_index, delta, self.inbox.len());
+            }
+        }
+    }
+
+    fn on_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Enter => self.mode = Mode::Normal,
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => {}
+        }
+        self.task_index = 0;
+    }
+
+    fn on_comment_key(&mut self, key: KeyEvent, mut text: String) -> Vec<Request> {
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+                Vec::new()
+            }
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                let text = text.trim().to_string();
+                match self.action_target() {
+                    Some((project_id, task_id)) if !text.is_empty() => vec![Request::Comment {
+                        project_id,
+                        task_id,
+                        text,
+                    }],
+                    _ => Vec::new(),
+                }
+            }
+            KeyCode::Backspace => {
+                text.pop();
+                self.mode = Mode::Comment(text);
+                Vec::new()
+            }
+            KeyCode::Char(c) => {
+                text.push(c);
+                self.mode = Mode::Comment(text);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn on_status_picker_key(&mut self, key: KeyEvent, index: usize) -> Vec<Request> {
+        match key.code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.mode = Mode::StatusPicker(step(index, 1, self.statuses.len()));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.mode = Mode::StatusPicker(step(index, -1, self.statuses.len()));
+            }
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                if let Some(status_id) = self.statuses.get(index).map(|s| s.id) {
+                    return self.status_action(status_id);
+                }
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn on_assignee_picker_key(&mut self, key: KeyEvent, index: usize) -> Vec<Request> {
+        match key.code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.mode = Mode::AssigneePicker(step(index, 1, self.members.len()));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.mode = Mode::AssigneePicker(step(index, -1, self.members.len()));
+            }
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                if let (Some(user_id), Some((project_id, task_id))) =
+                    (self.members.get(index).map(|u| u.id), self.action_target())
+                {
+                    return vec![Request::Reassign {
+                        project_id,
+                        task_id,
+                        user_id,
+                    }];
+                }
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Fold a finished request into the state, returning any follow-up requests
+    pub fn apply(&mut self, response: Response) -> Vec<Request> {
+        match response {
+            Response::Projects(projects) => {
+                self.projects = projects;
+                self.project_index = clamp_index(self.project_index, self.projects.len());
+            }
+            Response::Tasks { project_id, tasks } => {
+                if self.current_project == Some(project_id) {
+                    let selected = self.selected_task().map(|t| t.id);
+                    self.tasks = tasks;
+                    self.reselect_task(selected);
+                }
+            }
+            Response::Detail {
+                task,
+                comments,
+                history,
+            } => {
+                self.replace_task(&task);
+                if !self.wants_detail(&task) {
+                    return Vec::new();
+                }
+                self.detail = Some(TaskDetail {
+                    task: *task,
+                    comments,
+                    history,
+                });
+            }
+            Response::Statuses {
+                project_id,
+                statuses,
+            } => {
+                if self.current_project == Some(project_id) {
+                    self.statuses = statuses;
+                }
+            }
+            Response::Members { project_id, users } => {
+                if self.current_project == Some(project_id) {
+                    self.members = users;
+                }
+            }
+            Response::Inbox(items) => {
+                self.inbox = items;
+                self.inbox_index = clamp_index(self.inbox_index, self.inbox.len());
+            }
+            Response::TaskUpdated(task) => {
+                self.message = Some(format!("Updated #{} ({})", task.id, task.status.name));
+                self.replace_task(&task);
+                if let Some(detail) = self.detail.as_mut().filter(|d| d.task.id == task.id) {
+                    detail.task = *task;
+                }
+            }
+            Response::Commented {
+                project_id,
+                task_id,
+            } => {
+                self.message = Some(format!("Comment added to #{}", task_id));
+                return vec![Request::Detail {
+                    project_id,
+                    task_id,
+                }];
+            }
+            Response::InboxRead(id) => {
+                self.inbox.retain(|item| item.id != id);
+                self.inbox_index = clamp_index(self.inbox_index, self.inbox.len());
+                self.message = Some("Marked as read".to_string());
+            }
+            Response::InboxAllRead => {
+                self.inbox.clear();
+                self.inbox_index = 0;
+                self.message = Some("All inbox items marked as read".to_string());
+            }
+            Response::Failed(message) => self.message = Some(message),
+        }
+        Vec::new()
+    }
+
+    /// Whether a loaded detail is for the task being shown: the open detail, the selected task,
+    /// or the task of the selected inbox item, in the current project. Anything else arrived
+    /// after the user moved on.
+    fn wants_detail(&self, task: &Task) -> bool {
+        if self.current_project != Some(task.project.id) {
+            return false;
+        }
+        let open = self.detail.as_ref().map(|d| d.task.id);
+        let selected = self.selected_task().map(|t| t.id);
+        let inbox = self
+            .selected_inbox()
+            .and_then(|item| item.task.as_ref())
+            .map(|t| t.id);
+        [open, selected, inbox].contains(&Some(task.id))
+    }
+
+    fn replace_task(&mut self, task: &Task) {
+        if let Some(existing) = self.tasks.iter_mut().find(|t| t.id == task.id) {
+            *existing = task.clone();
+        }
+    }
+
+    /// Keep the cursor on the same task after the list is reloaded
+    fn reselect_task(&mut self, selected: Option<u64>) {
+        let visible = self.visible_tasks();
+        self.task_index = selected
+            .and_then(|id| visible.iter().position(|t| t.id == id))
+            .unwrap_or_else(|| clamp_index(self.task_index, visible.len()));
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Case-insensitive match of the live filter against a task's visible fields
+pub fn task_matches(task: &Task, filter: &str) -> bool {
+    let filter = filter.trim().to_lowercase();
+    if filter.is_empty() {
+        return true;
+    }
+    let assignee = task
+        .responsible_user
+        .as_ref()
+        .map(|u| format!("{} {}", u.name, u.full_name))
+        .unwrap_or_default();
+    let tags = task
+        .tags
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let haystack = format!(
+        "#{} {} {} {} {}",
+        task.id, task.name, task.status.name, assignee, tags
+    )
+    .to_lowercase();
+    filter
+        .split_whitespace()
+        .all(|term| haystack.contains(term))
+}
+
+fn step(index: usize, delta: isize, len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    index.saturating_add_signed(delta).min(len - 1)
+}
+
+fn clamp_index(index: usize, len: usize) -> usize {
+    index.min(len.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::{test_support, Tag};
+
+    fn status(id: u64, name: &str) -> Status {
+        Status {
+            id,
+            name: name.to_string(),
+            is_closed: false,
+            color: None,
+        }
+    }
+
+    fn user(id: u64, name: &str) -> User {
+        User {
+            id,
+            email: format!("{}@example.com", name),
+            name: name.to_string(),
+            full_name: name.to_string(),
+            avatar_url: None,
+            role: "member".to_string(),
+            billing_status: "active".to_string(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn task(id: u64, name: &str) -> Task {
+        Task {
+            status: status(1, "Open"),
+            ..test_support::task(id).named(name)
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn app_with_tasks() -> App {
+        let mut app = App::new();
+        app.current_project = Some(7);
+        app.focus = Focus::Tasks;
+        app.tasks = vec![
+            task(1, "Write docs"),
+            task(2, "Fix login"),
+            task(3, "Deploy"),
+        ];
+        app
+    }
+
+    #[test]
+    fn test_task_matches_fields() {
+        let mut t = task(42, "Fix login bug");
+        t.responsible_user = Some(user(3, "alice"));
+        t.tags = vec![Tag {
+            id: 1,
+            name: "backend".to_string(),
+            color: "red".to_string(),
+        }];
+
+        assert!(task_matches(&t, ""));
+        assert!(task_matches(&t, "LOGIN"));
+        assert!(task_matches(&t, "#42"));
+        assert!(task_matches(&t, "alice backend"));
+        assert!(!task_matches(&t, "alice frontend"));
+    }
+
+    #[test]
+    fn test_live_filter_narrows_tasks() {
+        let mut app = app_with_tasks();
+        app.on_key(key(KeyCode::Char('/')));
+        assert_eq!(app.mode, Mode::Filter);
+        for c in "fix".chars() {
+            app.on_key(key(KeyCode::Char(c)));
+        }
+        let visible: Vec<u64> = app.visible_tasks().iter().map(|t| t.id).collect();
+        assert_eq!(visible, vec![2]);
+
+        app.on_key(key(KeyCode::Esc));
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.visible_tasks().len(), 3);
+    }
+
+    #[test]
+    fn test_selection_is_clamped() {
+        let mut app = app_with_tasks();
+        for _ in 0..10 {
+            app.on_key(key(KeyCode::Char('j')));
+        }
+        assert_eq!(app.task_index, 2);
+        for _ in 0..10 {
+            app.on_key(key(KeyCode::Up));
+        }
+        assert_eq!(app.task_index, 0);
+    }
+
+    #[test]
+    fn test_enter_on_project_loads_project_data() {
+        let mut app = App::new();
+        app.projects = vec![serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "name": "p7",
+            "fullName": "Project 7",
+            "purpose": null,
+            "avatarUrl": null,
+            "isClosed": false,
+            "isPublic": false,
+            "createdAt": 0,
+            "updatedAt": 0
+        }))
+        .unwrap()];
+
+        let requests = app.on_key(key(KeyCode::Enter));
+        assert_eq!(
+            requests,
+            vec![Request::Tasks(7), Request::Statuses(7), Request::Members(7)]
+        );
+        assert_eq!(app.current_project, Some(7));
+        assert_eq!(app.focus, Focus::Tasks);
+    }
+
+    #[test]
+    fn test_quick_actions_target_selected_task() {
+        let mut app = app_with_tasks();
+        app.on_key(key(KeyCode::Char('j')));
+
+        assert_eq!(
+            app.on_key(key(KeyCode::Char('d'))),
+            vec![Request::SetStatus {
+                project_id: 7,
+                task_id: 2,
+                status_id: TaskStatus::Done.id(),
+            }]
+        );
+        assert_eq!(
+            app.on_key(key(KeyCode::Char('o'))),
+            vec![Request::SetStatus {
+                project_id: 7,
+                task_id: 2,
+                status_id: TaskStatus::Open.id(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_status_picker_sets_chosen_status() {
+        let mut app = app_with_tasks();
+        app.statuses = vec![status(10, "Todo"), status(11, "Doing")];
+        app.on_key(key(KeyCode::Char('s')));
+        assert_eq!(app.mode, Mode::StatusPicker(0));
+        app.on_key(key(KeyCode::Down));
+        let requests = app.on_key(key(KeyCode::Enter));
+        assert_eq!(
+            requests,
+            vec![Request::SetStatus {
+                project_id: 7,
+                task_id: 1,
+                status_id: 11,
+            }]
+        );
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn test_reassign_without_members_reports_message() {
+        let mut app = app_with_tasks();
+        assert!(app.on_key(key(KeyCode::Char('a'))).is_empty());
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.message.is_some());
+
+        app.members = vec![user(5, "bob")];
+        app.on_key(key(KeyCode::Char('a')));
+        let requests = app.on_key(key(KeyCode::Enter));
+        assert_eq!(
+            requests,
+            vec![Request::Reassign {
+                project_id: 7,
+                task_id: 1,
+                user_id: 5,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_comment_prompt_collects_text() {
+        let mut app = app_with_tasks();
+        app.on_key(key(KeyCode::Char('c')));
+        for c in "lgtm".chars() {
+            app.on_key(key(KeyCode::Char(c)));
+        }
+        // Quick-action keys are plain text while typing a comment
+        app.on_key(key(KeyCode::Char('q')));
+        app.on_key(key(KeyCode::Backspace));
+        assert!(!app.should_quit);
+
+        let requests = app.on_key(key(KeyCode::Enter));
+        assert_eq!(
+            requests,
+            vec![Request::Comment {
+                project_id: 7,
+                task_id: 1,
+                text: "lgtm".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_refreshed_tasks_keep_selection() {
+        let mut app = app_with_tasks();
+        app.on_key(key(KeyCode::Char('j')));
+        app.on_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected_task().map(|t| t.id), Some(3));
+
+        app.apply(Response::Tasks {
+            project_id: 7,
+            tasks: vec![task(3, "Deploy"), task(4, "New")],
+        });
+        assert_eq!(app.selected_task().map(|t| t.id), Some(3));
+    }
+
+    #[test]
+    fn test_stale_project_responses_are_ignored() {
+        let mut app = app_with_tasks();
+        app.apply(Response::Tasks {
+            project_id: 99,
+            tasks: Vec::new(),
+        });
+        assert_eq!(app.tasks.len(), 3);
+    }
+
+    #[test]
+    fn test_stale_detail_responses_are_ignored() {
+        let mut app = app_with_tasks();
+        let detail = |task: Task| Response::Detail {
+            task: Box::new(task),
+            comments: Vec::new(),
+            history: Vec::new(),
+        };
+        app.on_key(key(KeyCode::Char('j')));
+        app.apply(detail(task(1, "Write docs")));
+        assert!(app.detail.is_none());
+
+        app.apply(detail(task(2, "Fix login")));
+        assert_eq!(app.detail.as_ref().map(|d| d.task.id), Some(2));
+
+        let mut other = task(2, "Fix login");
+        other.project.id = 8;
+        app.apply(detail(other));
+        assert_eq!(app.detail.as_ref().map(|d| d.task.project.id), Some(7));
+    }
+
+    #[test]
+    fn test_inbox_mark_read() {
+        let mut app = App::new();
+        app.inbox = serde_json::from_value(serde_json::json!([
+            {"id": 1, "task": null, "note": null, "comment": null, "readAt": null, "createdAt": 0},
+            {"id": 2, "task": null, "note": null, "comment": null, "readAt": null, "createdAt": 0}
+        ]))
+        .unwrap();
+        app.on_key(key(KeyCode::Char('i')));
+        assert_eq!(app.focus, Focus::Inbox);
+
+        assert_eq!(
+            app.on_key(key(KeyCode::Char('m'))),
+            vec![Request::MarkRead(1)]
+        );
+        app.apply(Response::InboxRead(1));
+        assert_eq!(app.inbox.len(), 1);
+        assert_eq!(
+            app.on_key(key(KeyCode::Char('M'))),
+            vec![Request::MarkAllRead]
+        );
+    }
+}
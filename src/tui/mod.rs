@@ -0,0 +1,200 @@
+mod app;
+mod ui;
+
+use anyhow::{bail, Result};
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use std::future::Future;
+use std::io::IsTerminal;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use crate::api::endpoints::me::TaskFilter;
+use crate::api::endpoints::task::UpdateTaskRequest;
+use crate::api::RepsonaClient;
+use app::{App, Request, Response};
+
+type Pending<'a> = FuturesUnordered<Pin<Box<dyn Future<Output = Response> + 'a>>>;
+
+/// Run the full-screen interface until the user quits.
+///
+/// `refresh_secs` sets the background refresh interval (0 disables it).
+pub async fn run(client: &RepsonaClient, refresh_secs: u64) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        bail!("rpsn tui requires an interactive terminal");
+    }
+
+    // Rate-limit notices on stderr would tear the alternate screen
+    client.set_quiet(true);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, client, refresh_secs).await;
+    ratatui::restore();
+    client.set_quiet(false);
+    result
+}
+
+async fn event_loop(
+    terminal: &mut ratatui::DefaultTerminal,
+    client: &RepsonaClient,
+    refresh_secs: u64,
+) -> Result<()> {
+    let mut app = App::new();
+    let mut events = EventStream::new();
+    let mut pending: Pending<'_> = FuturesUnordered::new();
+    let mut ticker = tokio::time::interval(Duration::from_secs(refresh_secs.max(1)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // The first tick completes immediately; startup loading is issued below
+    ticker.tick().await;
+
+    let initial = app.initial_requests();
+    submit(&mut app, &mut pending, client, initial);
+
+    while !app.should_quit {
+        app.rate_limit = client.rate_limit();
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    app.message = None;
+                    let requests = app.on_key(key);
+                    submit(&mut app, &mut pending, client, requests);
+                }
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err.into()),
+                None => break,
+            },
+            Some(response) = pending.next(), if !pending.is_empty() => {
+                app.pending = app.pending.saturating_sub(1);
+                let requests = app.apply(response);
+                submit(&mut app, &mut pending, client, requests);
+            }
+            _ = ticker.tick(), if refresh_secs > 0 => {
+                if app.pending > 0 {
+                    continue;
+                }
+                match client.rate_limit() {
+                    Some(rate) if !rate.allows_background(Instant::now()) => {
+                        app.message =
+                            Some("Background refresh paused until the rate limit resets".to_string());
+                    }
+                    _ => {
+                        let requests = app.refresh_requests();
+                        submit(&mut app, &mut pending, client, requests);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn submit<'a>(
+    app: &mut App,
+    pending: &mut Pending<'a>,
+    client: &'a RepsonaClient,
+    requests: Vec<Request>,
+) {
+    for request in requests {
+        app.pending += 1;
+        pending.push(Box::pin(execute(client, request)));
+    }
+}
+
+async fn execute(client: &RepsonaClient, request: Request) -> Response {
+    let result: Result<Response> = async {
+        Ok(match request {
+            Request::Projects => Response::Projects(client.list_projects().await?.data.projects),
+            Request::Tasks(project_id) => Response::Tasks {
+                project_id,
+                tasks: client
+                    .list_tasks(project_id, &TaskFilter::default())
+                    .await?
+                    .data
+                    .tasks,
+            },
+            Request::Detail {
+                project_id,
+                task_id,
+            } => {
+                let (task, comments, history) = tokio::join!(
+                    client.get_task(project_id, task_id),
+                    client.list_task_comments(project_id, task_id),
+                    client.get_task_history(project_id, task_id),
+                );
+                Response::Detail {
+                    task: Box::new(task?.data.task),
+                    comments: comments?.data.task_comments,
+                    history: history?.data.history,
+                }
+            }
+            Request::Statuses(project_id) => Response::Statuses {
+                project_id,
+                statuses: client
+                    .list_project_statuses(project_id)
+                    .await?
+                    .data
+                    .statuses,
+            },
+            Request::Members(project_id) => Response::Members {
+                project_id,
+                users: client.list_project_members(project_id).await?.data.users,
+            },
+            Request::Inbox => Response::Inbox(client.list_inbox().await?.data.inbox),
+            Request::SetStatus {
+                project_id,
+                task_id,
+                status_id,
+            } => Response::TaskUpdated(Box::new(
+                client
+                    .set_task_status(project_id, task_id, status_id)
+                    .await?
+                    .data
+                    .task,
+            )),
+            Request::Reassign {
+                project_id,
+                task_id,
+                user_id,
+            } => {
+                let request = UpdateTaskRequest {
+                    responsible_user: Some(user_id),
+                    ..Default::default()
+                };
+                Response::TaskUpdated(Box::new(
+                    client
+                        .update_task(project_id, task_id, &request)
+                        .await?
+                        .data
+                        .task,
+                ))
+            }
+            Request::Comment {
+                project_id,
+                task_id,
+                text,
+            } => {
+                client
+                    .add_task_comment(project_id, task_id, text, None)
+                    .await?;
+                Response::Commented {
+                    project_id,
+                    task_id,
+                }
+            }
+            Request::MarkRead(inbox_id) => {
+                client.update_inbox(inbox_id, "archived").await?;
+                Response::InboxRead(inbox_id)
+            }
+            Request::MarkAllRead => {
+                client.mark_inbox_all_read().await?;
+                Response::InboxAllRead
+            }
+        })
+    }
+    .await;
+
+    result.unwrap_or_else(|err| Response::Failed(format!("Error: {:#}", err)))
+}
@@ -0,0 +1,508 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap};
+use ratatui::Frame;
+use std::time::Instant;
+
+use super::app::{App, DetailTab, Focus, Mode};
+use crate::api::types::{InboxItem, Task};
+use crate::markdown::{self, RenderOptions};
+use crate::output::format_date;
+
+const HELP: &[(&str, &str)] = &[
+    ("Tab / Shift-Tab", "Switch pane"),
+    ("j/k, ↑/↓", "Move selection / scroll detail"),
+    ("Enter", "Open project, task or inbox item"),
+    ("/", "Filter tasks (Esc clears)"),
+    ("[ / ]", "Previous / next detail tab"),
+    ("d / o", "Mark done / reopen"),
+    ("s", "Change status"),
+    ("a", "Reassign"),
+    ("c", "Add comment"),
+    ("i", "Toggle inbox"),
+    ("m / M", "Mark inbox item / all read"),
+    ("r", "Refresh now"),
+    ("q, Ctrl-C", "Quit"),
+];
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    draw_header(frame, header, app);
+
+    if app.focus == Focus::Inbox {
+        let [list, detail] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(body);
+        draw_inbox(frame, list, app);
+        draw_inbox_preview(frame, detail, app);
+    } else {
+        let [projects, tasks, detail] = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Percentage(35),
+            Constraint::Percentage(45),
+        ])
+        .areas(body);
+        draw_projects(frame, projects, app);
+        draw_tasks(frame, tasks, app);
+        draw_detail(frame, detail, app);
+    }
+
+    draw_footer(frame, footer, app);
+
+    match &app.mode {
+        Mode::StatusPicker(index) => {
+            let items = app.statuses.iter().map(|s| s.name.clone()).collect();
+            draw_picker(frame, "Change status", items, *index);
+        }
+        Mode::AssigneePicker(index) => {
+            let items = app
+                .members
+                .iter()
+                .map(|u| format!("{} ({})", u.full_name, u.name))
+                .collect();
+            draw_picker(frame, "Reassign to", items, *index);
+        }
+        Mode::Comment(text) => draw_comment_prompt(frame, text),
+        Mode::Help => draw_help(frame),
+        Mode::Normal | Mode::Filter => {}
+    }
+}
+
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+}
+
+fn highlight() -> Style {
+    Style::default()
+        .bg(Color::DarkGray)
+        .add_modifier(Modifier::BOLD)
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
+    let project = app
+        .current_project
+        .and_then(|id| app.project_name(id))
+        .unwrap_or("no project selected");
+    let mut spans = vec![
+        Span::styled(" rpsn ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!("│ {} ", project)),
+        Span::styled(
+            format!("│ inbox: {} ", app.inbox.len()),
+            Style::default().fg(if app.inbox.is_empty() {
+                Color::DarkGray
+            } else {
+                Color::Yellow
+            }),
+        ),
+    ];
+    if app.pending > 0 {
+        spans.push(Span::styled(
+            "│ loading… ",
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let line = match &app.mode {
+        Mode::Filter => Line::from(vec![
+            Span::styled("/", Style::default().fg(Color::Yellow)),
+            Span::raw(app.filter.clone()),
+            Span::styled("█", Style::default().fg(Color::DarkGray)),
+        ]),
+        _ => {
+            let mut spans = Vec::new();
+            if let Some(message) = &app.message {
+                spans.push(Span::styled(
+                    format!("{}  ", message),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            if let Some(rate) = &app.rate_limit {
+                let style = if rate.allows_background(Instant::now()) {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default().fg(Color::Red)
+                };
+                spans.push(Span::styled(
+                    format!("API {}/{}  ", rate.remaining, rate.limit),
+                    style,
+                ));
+            }
+            spans.push(Span::styled(
+                "? help  q quit",
+                Style::default().fg(Color::DarkGray),
+            ));
+            Line::from(spans)
+        }
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn draw_projects(frame: &mut Frame, area: Rect, app: &App) {
+    let items: Vec<ListItem> = app
+        .projects
+        .iter()
+        .map(|p| {
+            let style = if Some(p.id) == app.current_project {
+                Style::default().fg(Color::Cyan)
+            } else if p.is_closed {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Span::styled(p.name.clone(), style))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane(
+            format!("Projects ({})", app.projects.len()),
+            app.focus == Focus::Projects,
+        ))
+        .highlight_style(highlight());
+    let mut state = ListState::default().with_selected(Some(app.project_index));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn task_line(task: &Task) -> Line<'static> {
+    let status_style = if task.status.is_closed {
+        Style::default().fg(Color::DarkGray)
+    } else {
+        Style::default().fg(Color::Green)
+    };
+    let mut spans = vec![
+        Span::styled(format!("#{} ", task.id), Style::default().fg(Color::Cyan)),
+        Span::raw(task.name.clone()),
+        Span::styled(format!(" [{}]", task.status.name), status_style),
+    ];
+    if let Some(user) = &task.responsible_user {
+        spans.push(Span::styled(
+            format!(" @{}", user.name),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn draw_tasks(frame: &mut Frame, area: Rect, app: &App) {
+    let visible = app.visible_tasks();
+    let items: Vec<ListItem> = visible
+        .iter()
+        .map(|t| ListItem::new(task_line(t)))
+        .collect();
+    let title = if app.filter.is_empty() {
+        format!("Tasks ({})", app.tasks.len())
+    } else {
+        format!(
+            "Tasks ({}/{}) /{}",
+            visible.len(),
+            app.tasks.len(),
+            app.filter
+        )
+    };
+    let list = List::new(items)
+        .block(pane(title, app.focus == Focus::Tasks))
+        .highlight_style(highlight());
+    let mut state = ListState::default().with_selected(Some(app.task_index));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_detail(frame: &mut Frame, area: Rect, app: &App) {
+    let block = pane("Detail".to_string(), app.focus == Focus::Detail);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(detail) = &app.detail else {
+        frame.render_widget(
+            Paragraph::new("Select a task and press Enter")
+                .style(Style::default().fg(Color::DarkGray)),
+            inner,
+        );
+        return;
+    };
+
+    let [tabs_area, meta_area, content_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(3),
+        Constraint::Min(1),
+    ])
+    .areas(inner);
+
+    let selected = DetailTab::ALL
+        .iter()
+        .position(|tab| *tab == app.detail_tab)
+        .unwrap_or(0);
+    let tabs = Tabs::new(DetailTab::ALL.iter().map(|tab| tab.title()))
+        .select(selected)
+        .highlight_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    frame.render_widget(tabs, tabs_area);
+
+    let task = &detail.task;
+    let assignee = task
+        .responsible_user
+        .as_ref()
+        .map(|u| u.full_name.clone())
+        .unwrap_or_else(|| "-".to_string());
+    let due = task
+        .due_date
+        .map(format_date)
+        .unwrap_or_else(|| "-".to_string());
+    let meta = Text::from(vec![
+        Line::from(vec![
+            Span::styled(format!("#{} ", task.id), Style::default().fg(Color::Cyan)),
+            Span::styled(
+                task.name.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(format!(
+            "Status: {}  Priority: {}  Due: {}",
+            task.status.name, task.priority, due
+        )),
+        Line::from(format!("Assignee: {}", assignee)),
+    ]);
+    frame.render_widget(Paragraph::new(meta), meta_area);
+
+    let options = RenderOptions {
+        width: content_area.width.saturating_sub(1).max(20) as usize,
+        color: false,
+    };
+    let content = match app.detail_tab {
+        DetailTab::Description => match task.description.as_deref() {
+            Some(description) if !description.trim().is_empty() => {
+                markdown::render(description, &options)
+            }
+            _ => "(no description)".to_string(),
+        },
+        DetailTab::Comments => {
+            if detail.comments.is_empty() {
+                "(no comments)".to_string()
+            } else {
+                detail
+                    .comments
+                    .iter()
+                    .map(|c| {
+                        format!(
+                            "{} · {}\n{}",
+                            c.user.full_name,
+                            format_date(c.created_at),
+                            markdown::render(&c.comment, &options)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        DetailTab::History => {
+            if detail.history.is_empty() {
+                "(no history)".to_string()
+            } else {
+                detail
+                    .history
+                    .iter()
+                    .map(|h| {
+                        let who = h.user.as_ref().map(|u| u.full_name.as_str()).unwrap_or("-");
+                        let mut entry =
+                            format!("{} {} {}", format_date(h.created_at), who, h.action);
+                        for change in h.changes.iter().flatten() {
+                            entry.push_str(&format!(
+                                "\n  {}: {} → {}",
+                                change.field,
+                                change.from.as_deref().unwrap_or("-"),
+                                change.to.as_deref().unwrap_or("-")
+                            ));
+                        }
+                        entry
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(content)
+            .wrap(Wrap { trim: false })
+            .scroll((app.detail_scroll, 0)),
+        content_area,
+    );
+}
+
+fn inbox_title(item: &InboxItem) -> String {
+    if let Some(task) = &item.task {
+        format!("#{} {}", task.id, task.name)
+    } else if let Some(note) = &item.note {
+        format!("Note #{} {}", note.id, note.name)
+    } else {
+        format!("Notification #{}", item.id)
+    }
+}
+
+fn draw_inbox(frame: &mut Frame, area: Rect, app: &App) {
+    let items: Vec<ListItem> = app
+        .inbox
+        .iter()
+        .map(|item| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", format_date(item.created_at)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(inbox_title(item)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane(format!("Inbox ({})", app.inbox.len()), true))
+        .highlight_style(highlight());
+    let mut state = ListState::default().with_selected(Some(app.inbox_index));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_inbox_preview(frame: &mut Frame, area: Rect, app: &App) {
+    let block = pane("Notification".to_string(), false);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(item) = app.selected_inbox() else {
+        frame.render_widget(
+            Paragraph::new("Inbox is empty").style(Style::default().fg(Color::DarkGray)),
+            inner,
+        );
+        return;
+    };
+    let options = RenderOptions {
+        width: inner.width.saturating_sub(1).max(20) as usize,
+        color: false,
+    };
+    let mut text = inbox_title(item);
+    if let Some(comment) = &item.comment {
+        text.push_str(&format!(
+            "\n\n{} · {}\n{}",
+            comment.user.full_name,
+            format_date(comment.created_at),
+            markdown::render(&comment.comment, &options)
+        ));
+    }
+    text.push_str("\n\nEnter: open task   m: mark read   M: mark all read");
+    frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: false }), inner);
+}
+
+/// Rectangle of the given size centered in `area`, clipped to fit
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn draw_picker(frame: &mut Frame, title: &str, items: Vec<String>, index: usize) {
+    let height = items.len() as u16 + 2;
+    let area = centered(frame.area(), 40, height.min(16));
+    let list = List::new(items.into_iter().map(ListItem::new).collect::<Vec<_>>())
+        .block(pane(title.to_string(), true))
+        .highlight_style(highlight());
+    let mut state = ListState::default().with_selected(Some(index));
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_comment_prompt(frame: &mut Frame, text: &str) {
+    let area = centered(frame.area(), 70, 5);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(format!("{}█", text))
+            .wrap(Wrap { trim: false })
+            .block(pane(
+                "Comment (Enter to send, Esc to cancel)".to_string(),
+                true,
+            )),
+        area,
+    );
+}
+
+fn draw_help(frame: &mut Frame) {
+    let area = centered(frame.area(), 56, HELP.len() as u16 + 2);
+    let lines: Vec<Line> = HELP
+        .iter()
+        .map(|(keys, action)| {
+            Line::from(vec![
+                Span::styled(format!("{:<18}", keys), Style::default().fg(Color::Cyan)),
+                Span::raw(*action),
+            ])
+        })
+        .collect();
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(pane("Keys".to_string(), true)),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn screen(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_draw_empty_app() {
+        let screen = screen(&App::new());
+        assert!(screen.contains("Projects (0)"));
+        assert!(screen.contains("Select a task and press Enter"));
+        assert!(screen.contains("? help"));
+    }
+
+    #[test]
+    fn test_draw_filter_and_help() {
+        let mut app = App::new();
+        app.mode = Mode::Filter;
+        app.filter = "bug".to_string();
+        assert!(screen(&app).contains("/bug"));
+
+        app.mode = Mode::Help;
+        assert!(screen(&app).contains("Reassign"));
+    }
+
+    #[test]
+    fn test_draw_inbox_view() {
+        let mut app = App::new();
+        app.focus = Focus::Inbox;
+        assert!(screen(&app).contains("Inbox is empty"));
+    }
+}